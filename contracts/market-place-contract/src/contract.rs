@@ -1,7 +1,157 @@
 use crate::error::ContractError;
 use crate::events::*;
+use crate::interfaces::ImpactNftClient;
 use crate::storage::{self, *};
-use soroban_sdk::{token, Address, Env, String, Vec, Map};
+use soroban_sdk::{token, Address, BytesN, Env, String, Vec, Map};
+
+/// Proposes `new_admin` as the next admin, replacing any earlier proposal. The admin
+/// only changes once `new_admin` accepts.
+pub fn propose_admin(
+    env: &Env,
+    admin: &Address,
+    new_admin: &Address,
+    live_until_ledger: Option<u32>,
+) -> Result<(), ContractError> {
+    if storage::get_admin(env).as_ref() != Some(admin) {
+        return Err(ContractError::AdminOnly);
+    }
+
+    if live_until_ledger.is_some_and(|ledger| ledger < env.ledger().sequence()) {
+        return Err(ContractError::InvalidExpiry);
+    }
+
+    let transfer = AdminTransfer {
+        new_admin: new_admin.clone(),
+        live_until_ledger,
+    };
+    storage::set_pending_admin(env, &transfer);
+
+    emit_admin_proposed(env, admin.clone(), new_admin.clone(), live_until_ledger);
+
+    Ok(())
+}
+
+/// Makes the proposed admin the admin. The proposed admin must authorize this.
+pub fn accept_admin(env: &Env) -> Result<Address, ContractError> {
+    let transfer = storage::get_pending_admin(env).ok_or(ContractError::NoPendingAdmin)?;
+    transfer.new_admin.require_auth();
+
+    if transfer
+        .live_until_ledger
+        .is_some_and(|ledger| ledger < env.ledger().sequence())
+    {
+        return Err(ContractError::AdminTransferExpired);
+    }
+
+    let previous_admin = storage::get_admin(env).ok_or(ContractError::OperationNotAllowed)?;
+    storage::set_admin(env, &transfer.new_admin);
+    storage::remove_pending_admin(env);
+
+    emit_admin_accepted(env, previous_admin, transfer.new_admin.clone());
+
+    Ok(transfer.new_admin)
+}
+
+/// Withdraws the pending admin transfer. The admin may cancel it and the proposed admin
+/// may decline it.
+pub fn cancel_admin_transfer(env: &Env, caller: &Address) -> Result<(), ContractError> {
+    let transfer = storage::get_pending_admin(env).ok_or(ContractError::NoPendingAdmin)?;
+
+    if *caller != transfer.new_admin && storage::get_admin(env).as_ref() != Some(caller) {
+        return Err(ContractError::AdminOnly);
+    }
+
+    storage::remove_pending_admin(env);
+
+    emit_admin_transfer_cancelled(env, caller.clone(), transfer.new_admin);
+
+    Ok(())
+}
+
+/// Replaces the contract code with previously uploaded wasm. Storage is kept as is;
+/// `migrate` brings it up to the new code's schema.
+pub fn upgrade(env: &Env, admin: &Address, new_wasm_hash: BytesN<32>) -> Result<(), ContractError> {
+    if storage::get_admin(env).as_ref() != Some(admin) {
+        return Err(ContractError::AdminOnly);
+    }
+
+    env.deployer()
+        .update_current_contract_wasm(new_wasm_hash.clone());
+
+    emit_contract_upgraded(env, admin.clone(), new_wasm_hash);
+
+    Ok(())
+}
+
+/// Runs the data migrations from the stored schema version up to `SCHEMA_VERSION`
+/// and returns the resulting version.
+pub fn migrate(env: &Env, admin: &Address) -> Result<u32, ContractError> {
+    if storage::get_admin(env).as_ref() != Some(admin) {
+        return Err(ContractError::AdminOnly);
+    }
+
+    let from_version = storage::get_schema_version(env);
+    if from_version > SCHEMA_VERSION {
+        return Err(ContractError::UnsupportedSchemaVersion);
+    }
+
+    storage::set_schema_version(env, SCHEMA_VERSION);
+
+    emit_schema_migrated(env, admin.clone(), from_version, SCHEMA_VERSION);
+
+    Ok(SCHEMA_VERSION)
+}
+
+/// Links an NFT to the ImpactProductNFT token it represents, so its sales pay
+/// that token's royalty. The creator must own the impact token.
+pub fn link_impact_token(
+    env: &Env,
+    creator: &Address,
+    nft_id: u64,
+    impact_contract: &Address,
+    token_id: u32,
+) -> Result<(), ContractError> {
+    let nft = get_nft(env, nft_id)?;
+
+    if nft.creator != *creator {
+        return Err(ContractError::CreatorOnly);
+    }
+
+    if nft.status == NFTStatus::Sold {
+        return Err(ContractError::OperationNotAllowed);
+    }
+
+    let impact_client = ImpactNftClient::new(env, impact_contract);
+    match impact_client.try_owner_of(&token_id) {
+        Ok(Ok(owner)) if owner == *creator => {}
+        _ => return Err(ContractError::ImpactTokenNotOwned),
+    }
+
+    let impact_token = ImpactToken {
+        contract: impact_contract.clone(),
+        token_id,
+    };
+
+    // An impact token backs at most one listing that can still sell.
+    if let Some(listing_id) = get_impact_token_listing(env, &impact_token) {
+        let listed = get_nft(env, listing_id)?;
+        if listing_id != nft_id && listed.status != NFTStatus::Sold {
+            return Err(ContractError::ImpactTokenAlreadyLinked);
+        }
+    }
+
+    if let Some(previous) = storage::get_impact_token(env, nft_id) {
+        if get_impact_token_listing(env, &previous) == Some(nft_id) {
+            remove_impact_token_listing(env, &previous);
+        }
+    }
+    set_impact_token(env, nft_id, &impact_token);
+    set_impact_token_listing(env, &impact_token, nft_id);
+
+    emit_impact_token_linked(env, nft_id, creator.clone(), impact_contract.clone(), token_id);
+
+    Ok(())
+}
 
 /// Creates a new NFT, assigning it to the creator and storing its metadata.
 pub fn create_nft(
@@ -71,8 +221,32 @@ pub fn buy_nft(
         return Err(ContractError::NFTAlreadyOwned);
     }
     
+    let price = nft.price as i128;
+
+    // The royalty comes out of the seller's share and can never exceed it. The creator
+    // must still own the linked impact token, or the listing no longer represents it.
+    let (royalty_receiver, royalty) = match storage::get_impact_token(env, nft_id) {
+        Some(impact_token) => {
+            let impact_client = ImpactNftClient::new(env, &impact_token.contract);
+            if !impact_client.is_active(&impact_token.token_id) {
+                return Err(ContractError::ImpactTokenInactive);
+            }
+            match impact_client.try_owner_of(&impact_token.token_id) {
+                Ok(Ok(owner)) if owner == nft.creator => {}
+                _ => return Err(ContractError::ImpactTokenNotOwned),
+            }
+            impact_client.royalty_info(&impact_token.token_id, &price)
+        }
+        None => (nft.creator.clone(), 0),
+    };
+    let royalty = royalty.clamp(0, price);
+
     let token_client = token::Client::new(env, &nft.token_address);
-    token_client.transfer(buyer, &nft.owner, &(nft.price as i128));
+    token_client.transfer(buyer, &nft.owner, &(price - royalty));
+
+    if royalty > 0 {
+        token_client.transfer(buyer, &royalty_receiver, &royalty);
+    }
     
     let previous_owner = nft.owner.clone();
     remove_owner_nft(env, &previous_owner, nft_id);
@@ -91,6 +265,7 @@ pub fn buy_nft(
         previous_owner,
         nft.price,
         nft.token_address.clone(),
+        royalty as u128,
     );
     
     Ok(())
@@ -153,6 +328,11 @@ pub fn toggle_nft_availability(
 
 // --- Read-Only Functions for Frontend ---
 
+/// Gets the ImpactProductNFT token an NFT is linked to, if any.
+pub fn get_impact_token(env: &Env, nft_id: u64) -> Option<ImpactToken> {
+    storage::get_impact_token(env, nft_id)
+}
+
 /// Gets the details for a single NFT.
 pub fn get_nft(env: &Env, nft_id: u64) -> Result<NFT, ContractError> {
     storage::get_nft(env, nft_id).ok_or(ContractError::NFTNotFound)
@@ -19,4 +19,15 @@ pub enum ContractError {
     // General
     InvalidPrice = 7,
     OperationNotAllowed = 8,
+    ImpactTokenInactive = 10,
+    ImpactTokenNotOwned = 15,
+    ImpactTokenAlreadyLinked = 16,
+
+    // Upgrades
+    UnsupportedSchemaVersion = 11,
+
+    // Admin transfer
+    NoPendingAdmin = 12,
+    AdminTransferExpired = 13,
+    InvalidExpiry = 14,
 }
@@ -1,4 +1,4 @@
-use soroban_sdk::{contracttype, Address, Env, String};
+use soroban_sdk::{contracttype, Address, BytesN, Env, String};
 
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -18,6 +18,7 @@ pub struct NFTSoldEvent {
     pub previous_owner: Address,
     pub price: u128,
     pub token_address: Address,
+    pub royalty: u128,
 }
 
 #[contracttype]
@@ -37,6 +38,52 @@ pub struct NFTAvailabilityToggledEvent {
     pub is_available: bool,
 }
 
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ImpactTokenLinkedEvent {
+    pub nft_id: u64,
+    pub creator: Address,
+    pub impact_contract: Address,
+    pub token_id: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ContractUpgradedEvent {
+    pub admin: Address,
+    pub new_wasm_hash: BytesN<32>,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SchemaMigratedEvent {
+    pub admin: Address,
+    pub from_version: u32,
+    pub to_version: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AdminProposedEvent {
+    pub admin: Address,
+    pub new_admin: Address,
+    pub live_until_ledger: Option<u32>,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AdminAcceptedEvent {
+    pub previous_admin: Address,
+    pub new_admin: Address,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AdminTransferCancelledEvent {
+    pub caller: Address,
+    pub new_admin: Address,
+}
+
 pub fn emit_nft_created(
     env: &Env,
     nft_id: u64,
@@ -62,6 +109,7 @@ pub fn emit_nft_sold(
     previous_owner: Address,
     price: u128,
     token_address: Address,
+    royalty: u128,
 ) {
     let event = NFTSoldEvent {
         nft_id,
@@ -69,6 +117,7 @@ pub fn emit_nft_sold(
         previous_owner,
         price,
         token_address,
+        royalty,
     };
     env.events().publish(("nft_sold",), event);
 }
@@ -102,3 +151,63 @@ pub fn emit_nft_availability_toggled(
     };
     env.events().publish(("nft_availability_toggled",), event);
 }
+
+pub fn emit_impact_token_linked(
+    env: &Env,
+    nft_id: u64,
+    creator: Address,
+    impact_contract: Address,
+    token_id: u32,
+) {
+    let event = ImpactTokenLinkedEvent {
+        nft_id,
+        creator,
+        impact_contract,
+        token_id,
+    };
+    env.events().publish(("impact_token_linked",), event);
+}
+
+pub fn emit_contract_upgraded(env: &Env, admin: Address, new_wasm_hash: BytesN<32>) {
+    let event = ContractUpgradedEvent {
+        admin,
+        new_wasm_hash,
+    };
+    env.events().publish(("contract_upgraded",), event);
+}
+
+pub fn emit_schema_migrated(env: &Env, admin: Address, from_version: u32, to_version: u32) {
+    let event = SchemaMigratedEvent {
+        admin,
+        from_version,
+        to_version,
+    };
+    env.events().publish(("schema_migrated",), event);
+}
+
+pub fn emit_admin_proposed(
+    env: &Env,
+    admin: Address,
+    new_admin: Address,
+    live_until_ledger: Option<u32>,
+) {
+    let event = AdminProposedEvent {
+        admin,
+        new_admin,
+        live_until_ledger,
+    };
+    env.events().publish(("admin_proposed",), event);
+}
+
+pub fn emit_admin_accepted(env: &Env, previous_admin: Address, new_admin: Address) {
+    let event = AdminAcceptedEvent {
+        previous_admin,
+        new_admin,
+    };
+    env.events().publish(("admin_accepted",), event);
+}
+
+pub fn emit_admin_transfer_cancelled(env: &Env, caller: Address, new_admin: Address) {
+    let event = AdminTransferCancelledEvent { caller, new_admin };
+    env.events().publish(("admin_transfer_cancelled",), event);
+}
@@ -0,0 +1,17 @@
+use soroban_sdk::{contractclient, Address, Env};
+
+/// The part of ImpactProductNFT the marketplace relies on when selling NFTs
+/// linked to an impact token.
+#[contractclient(name = "ImpactNftClient")]
+#[allow(dead_code)]
+pub trait ImpactNftInterface {
+    /// Returns the royalty receiver and the amount owed to them when the
+    /// token sells for `sale_price`.
+    fn royalty_info(env: Env, token_id: u32, sale_price: i128) -> (Address, i128);
+
+    /// Returns false once the token has expired or been retired.
+    fn is_active(env: Env, token_id: u32) -> bool;
+
+    /// Returns the current owner of the token.
+    fn owner_of(env: Env, token_id: u32) -> Address;
+}
@@ -3,15 +3,16 @@
 mod contract;
 mod error;
 mod events;
+mod interfaces;
 mod storage;
 mod test;
 
-use soroban_sdk::{contract, contractimpl, Address, Env, String, Vec, Map};
+use soroban_sdk::{contract, contractimpl, Address, BytesN, Env, String, Vec, Map};
 
 pub use contract::*;
 pub use error::ContractError;
 pub use events::*;
-pub use storage::{NFT, NFTStatus};
+pub use storage::{AdminTransfer, ImpactToken, NFT, NFTStatus};
 
 
 #[contract]
@@ -25,9 +26,45 @@ impl MarketplaceContract {
         }
         admin.require_auth();
         storage::set_admin(&env, &admin);
+        storage::set_schema_version(&env, storage::SCHEMA_VERSION);
         Ok(())
     }
 
+    /// Proposes a new admin, who takes over once they call `accept_admin` (by
+    /// `live_until_ledger`, if set). Admin only.
+    pub fn propose_admin(
+        env: Env,
+        admin: Address,
+        new_admin: Address,
+        live_until_ledger: Option<u32>,
+    ) -> Result<(), ContractError> {
+        admin.require_auth();
+        contract::propose_admin(&env, &admin, &new_admin, live_until_ledger)
+    }
+
+    /// Completes the pending admin transfer. Must be authorized by the proposed admin.
+    pub fn accept_admin(env: Env) -> Result<Address, ContractError> {
+        contract::accept_admin(&env)
+    }
+
+    /// Cancels the pending admin transfer. Callable by the admin or the proposed admin.
+    pub fn cancel_admin_transfer(env: Env, caller: Address) -> Result<(), ContractError> {
+        caller.require_auth();
+        contract::cancel_admin_transfer(&env, &caller)
+    }
+
+    /// Replaces the contract code with previously uploaded wasm, keeping storage. Admin only.
+    pub fn upgrade(env: Env, admin: Address, new_wasm_hash: BytesN<32>) -> Result<(), ContractError> {
+        admin.require_auth();
+        contract::upgrade(&env, &admin, new_wasm_hash)
+    }
+
+    /// Brings stored data up to the schema version of the running code. Admin only.
+    pub fn migrate(env: Env, admin: Address) -> Result<u32, ContractError> {
+        admin.require_auth();
+        contract::migrate(&env, &admin)
+    }
+
     /// Creates a new NFT representing a real-world impact product.
     pub fn create_nft(
         env: Env,
@@ -52,7 +89,22 @@ impl MarketplaceContract {
         )
     }
 
-    /// Buys an NFT from the marketplace.
+    /// Links an NFT to an ImpactProductNFT token so its sales pay that token's royalty.
+    /// The creator must own the impact token.
+    pub fn link_impact_token(
+        env: Env,
+        creator: Address,
+        nft_id: u64,
+        impact_contract: Address,
+        token_id: u32,
+    ) -> Result<(), ContractError> {
+        creator.require_auth();
+        contract::link_impact_token(&env, &creator, nft_id, &impact_contract, token_id)
+    }
+
+    /// Buys an NFT from the marketplace. For NFTs linked to an impact token, the token's
+    /// royalty goes to its receiver. Linked NFTs cannot be bought once their impact token
+    /// has expired, been retired or left the creator's hands.
     pub fn buy_nft(env: Env, buyer: Address, nft_id: u64) -> Result<(), ContractError> {
         buyer.require_auth();
         contract::buy_nft(&env, &buyer, nft_id)
@@ -86,6 +138,21 @@ impl MarketplaceContract {
         contract::get_nft(&env, nft_id)
     }
 
+    /// Retrieves the pending admin transfer, if any.
+    pub fn get_pending_admin(env: Env) -> Option<AdminTransfer> {
+        storage::get_pending_admin(&env)
+    }
+
+    /// Retrieves the stored schema version, 0 for contracts initialized before versioning.
+    pub fn schema_version(env: Env) -> u32 {
+        storage::get_schema_version(&env)
+    }
+
+    /// Retrieves the ImpactProductNFT token an NFT is linked to, if any.
+    pub fn get_impact_token(env: Env, nft_id: u64) -> Option<ImpactToken> {
+        contract::get_impact_token(&env, nft_id)
+    }
+
     /// Retrieves all NFTs created.
     pub fn get_all_nfts(env: Env) -> Vec<NFT> {
         contract::get_all_nfts(&env)
@@ -1,5 +1,8 @@
 use soroban_sdk::{contracttype, symbol_short, Address, Env, String, Symbol, Vec};
 
+/// Storage layout version written by this build.
+pub const SCHEMA_VERSION: u32 = 1;
+
 // --- Data Structures ---
 
 #[contracttype]
@@ -27,6 +30,24 @@ pub struct NFT {
     pub sold_at: Option<u64>,
 }
 
+/// An ImpactProductNFT token a marketplace listing represents. Sales of the
+/// listing pay the royalty that token reports.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ImpactToken {
+    pub contract: Address,
+    pub token_id: u32,
+}
+
+/// A proposed hand-over of the admin role. `new_admin` must accept it, by
+/// `live_until_ledger` if set.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AdminTransfer {
+    pub new_admin: Address,
+    pub live_until_ledger: Option<u32>,
+}
+
 // --- Storage Keys ---
 
 #[contracttype]
@@ -39,6 +60,10 @@ pub enum StorageKey {
     OwnerNFTs(Address),
     CategoryNFTs(String),
     AllNFTs,
+    ImpactToken(u64),
+    ImpactTokenListing(ImpactToken),
+    SchemaVersion,
+    PendingAdmin,
 }
 
 const NFT_COUNTER: Symbol = symbol_short!("NFT_CTR");
@@ -53,6 +78,35 @@ pub fn set_admin(env: &Env, admin: &Address) {
     env.storage().instance().set(&StorageKey::Admin, admin);
 }
 
+pub fn get_admin(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&StorageKey::Admin)
+}
+
+pub fn get_pending_admin(env: &Env) -> Option<AdminTransfer> {
+    env.storage().instance().get(&StorageKey::PendingAdmin)
+}
+
+pub fn set_pending_admin(env: &Env, transfer: &AdminTransfer) {
+    env.storage().instance().set(&StorageKey::PendingAdmin, transfer);
+}
+
+pub fn remove_pending_admin(env: &Env) {
+    env.storage().instance().remove(&StorageKey::PendingAdmin);
+}
+
+// --- Schema ---
+
+pub fn get_schema_version(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&StorageKey::SchemaVersion)
+        .unwrap_or(0)
+}
+
+pub fn set_schema_version(env: &Env, version: u32) {
+    env.storage().instance().set(&StorageKey::SchemaVersion, &version);
+}
+
 // --- NFT Counter ---
 
 pub fn get_nft_counter(env: &Env) -> u64 {
@@ -78,6 +132,32 @@ pub fn set_nft(env: &Env, nft: &NFT) {
     env.storage().persistent().set(&key, nft);
 }
 
+pub fn get_impact_token(env: &Env, nft_id: u64) -> Option<ImpactToken> {
+    let key = StorageKey::ImpactToken(nft_id);
+    env.storage().persistent().get(&key)
+}
+
+pub fn set_impact_token(env: &Env, nft_id: u64, impact_token: &ImpactToken) {
+    let key = StorageKey::ImpactToken(nft_id);
+    env.storage().persistent().set(&key, impact_token);
+}
+
+/// The NFT most recently linked to `impact_token`.
+pub fn get_impact_token_listing(env: &Env, impact_token: &ImpactToken) -> Option<u64> {
+    let key = StorageKey::ImpactTokenListing(impact_token.clone());
+    env.storage().persistent().get(&key)
+}
+
+pub fn set_impact_token_listing(env: &Env, impact_token: &ImpactToken, nft_id: u64) {
+    let key = StorageKey::ImpactTokenListing(impact_token.clone());
+    env.storage().persistent().set(&key, &nft_id);
+}
+
+pub fn remove_impact_token_listing(env: &Env, impact_token: &ImpactToken) {
+    let key = StorageKey::ImpactTokenListing(impact_token.clone());
+    env.storage().persistent().remove(&key);
+}
+
 // --- Indexed Lists ---
 
 pub fn get_creator_nfts(env: &Env, creator: &Address) -> Vec<u64> {
@@ -3,7 +3,7 @@ extern crate std;
 
 use super::*;
 use crate::error::ContractError;
-use soroban_sdk::testutils::Address as _;
+use soroban_sdk::testutils::{Address as _, Ledger};
 use soroban_sdk::{token, Address, Env, IntoVal, String};
 use token::Client as TokenClient;
 use token::StellarAssetClient as TokenAdminClient;
@@ -250,3 +250,255 @@ fn test_get_functions() {
     assert_eq!(stats.get(String::from_str(&test.env, "available_nfts")), Some(2));
     assert_eq!(stats.get(String::from_str(&test.env, "sold_nfts")), Some(1));
 }
+
+// --- Fees and Royalties ---
+
+mod impact_nft {
+    use soroban_sdk::{contract, contractimpl, Address, Env};
+
+    /// Stands in for ImpactProductNFT, charging a fixed 5% royalty.
+    #[contract]
+    pub struct MockRoyaltyNft;
+
+    #[contractimpl]
+    impl MockRoyaltyNft {
+        pub fn __constructor(env: Env, receiver: Address) {
+            env.storage().instance().set(&0u32, &receiver);
+        }
+
+        pub fn royalty_info(env: Env, _token_id: u32, sale_price: i128) -> (Address, i128) {
+            let receiver: Address = env.storage().instance().get(&0u32).unwrap();
+            (receiver, sale_price * 500 / 10_000)
+        }
+
+        pub fn expire(env: Env, token_id: u32) {
+            env.storage().instance().set(&token_id, &true);
+        }
+
+        pub fn is_active(env: Env, token_id: u32) -> bool {
+            !env.storage().instance().has(&token_id)
+        }
+
+        pub fn set_owner(env: Env, token_id: u32, owner: Address) {
+            env.storage().persistent().set(&token_id, &owner);
+        }
+
+        pub fn owner_of(env: Env, token_id: u32) -> Address {
+            env.storage().persistent().get(&token_id).unwrap()
+        }
+    }
+}
+
+#[test]
+fn test_buy_nft_pays_royalty() {
+    let test = MarketplaceTest::setup();
+    let client = test.contract_client();
+    let token_client = test.token_client();
+    let royalty_receiver = Address::generate(&test.env);
+    let impact_contract = test
+        .env
+        .register(impact_nft::MockRoyaltyNft, (royalty_receiver.clone(),));
+    let impact_client = impact_nft::MockRoyaltyNftClient::new(&test.env, &impact_contract);
+    let nft_id = test.create_nft();
+
+    impact_client.set_owner(&7, &test.creator);
+    client.link_impact_token(&test.creator, &nft_id, &impact_contract, &7);
+    assert_eq!(
+        client.get_impact_token(&nft_id),
+        Some(ImpactToken {
+            contract: impact_contract,
+            token_id: 7
+        })
+    );
+
+    let creator_balance_before = token_client.balance(&test.creator);
+    client.buy_nft(&test.buyer, &nft_id);
+
+    // The 5% royalty comes out of the creator's proceeds.
+    assert_eq!(token_client.balance(&royalty_receiver), 5);
+    assert_eq!(
+        token_client.balance(&test.creator),
+        creator_balance_before + 95
+    );
+    assert_eq!(
+        token_client.balance(&test.buyer),
+        USER_STARTING_BALANCE - (NFT_PRICE as i128)
+    );
+}
+
+#[test]
+fn test_link_impact_token_errors() {
+    let test = MarketplaceTest::setup();
+    let client = test.contract_client();
+    let impact_contract = test
+        .env
+        .register(impact_nft::MockRoyaltyNft, (test.creator.clone(),));
+    let impact_client = impact_nft::MockRoyaltyNftClient::new(&test.env, &impact_contract);
+    let nft_id = test.create_nft();
+    let other_nft_id = test.create_nft();
+
+    let result = client.try_link_impact_token(&test.buyer, &nft_id, &test.token_address, &1);
+    assert_eq!(result.unwrap_err(), Ok(ContractError::CreatorOnly));
+
+    // A token backs one listing at a time, until that listing sells or links elsewhere.
+    impact_client.set_owner(&7, &test.creator);
+    impact_client.set_owner(&8, &test.creator);
+    client.link_impact_token(&test.creator, &nft_id, &impact_contract, &7);
+    client.link_impact_token(&test.creator, &nft_id, &impact_contract, &7);
+    let result = client.try_link_impact_token(&test.creator, &other_nft_id, &impact_contract, &7);
+    assert_eq!(result.unwrap_err(), Ok(ContractError::ImpactTokenAlreadyLinked));
+
+    client.link_impact_token(&test.creator, &nft_id, &impact_contract, &8);
+    client.link_impact_token(&test.creator, &other_nft_id, &impact_contract, &7);
+    let result = client.try_link_impact_token(&test.creator, &nft_id, &impact_contract, &7);
+    assert_eq!(result.unwrap_err(), Ok(ContractError::ImpactTokenAlreadyLinked));
+
+    client.buy_nft(&test.buyer, &other_nft_id);
+    client.link_impact_token(&test.creator, &nft_id, &impact_contract, &7);
+}
+
+#[test]
+fn test_link_impact_token_requires_ownership() {
+    let test = MarketplaceTest::setup();
+    let client = test.contract_client();
+    let impact_contract = test
+        .env
+        .register(impact_nft::MockRoyaltyNft, (test.creator.clone(),));
+    let impact_client = impact_nft::MockRoyaltyNftClient::new(&test.env, &impact_contract);
+    let nft_id = test.create_nft();
+
+    // Someone else's token, and a token that does not exist, cannot be linked.
+    impact_client.set_owner(&7, &test.buyer);
+    let result = client.try_link_impact_token(&test.creator, &nft_id, &impact_contract, &7);
+    assert_eq!(result.unwrap_err(), Ok(ContractError::ImpactTokenNotOwned));
+    let result = client.try_link_impact_token(&test.creator, &nft_id, &impact_contract, &8);
+    assert_eq!(result.unwrap_err(), Ok(ContractError::ImpactTokenNotOwned));
+    assert_eq!(client.get_impact_token(&nft_id), None);
+
+    // The listing cannot sell once the creator has parted with the linked token.
+    impact_client.set_owner(&7, &test.creator);
+    client.link_impact_token(&test.creator, &nft_id, &impact_contract, &7);
+    impact_client.set_owner(&7, &test.buyer);
+    let result = client.try_buy_nft(&test.buyer, &nft_id);
+    assert_eq!(result.unwrap_err(), Ok(ContractError::ImpactTokenNotOwned));
+}
+
+#[test]
+fn test_buy_nft_with_inactive_impact_token() {
+    let test = MarketplaceTest::setup();
+    let client = test.contract_client();
+    let impact_contract = test
+        .env
+        .register(impact_nft::MockRoyaltyNft, (test.creator.clone(),));
+    let impact_client = impact_nft::MockRoyaltyNftClient::new(&test.env, &impact_contract);
+    let nft_id = test.create_nft();
+    impact_client.set_owner(&7, &test.creator);
+    client.link_impact_token(&test.creator, &nft_id, &impact_contract, &7);
+
+    impact_client.expire(&7);
+    let result = client.try_buy_nft(&test.buyer, &nft_id);
+    assert_eq!(result.unwrap_err(), Ok(ContractError::ImpactTokenInactive));
+}
+
+// --- Upgrades ---
+
+/// Release build of this contract, uploaded as the upgrade target.
+mod contract_marketplace {
+    soroban_sdk::contractimport!(
+        file = "target/wasm32v1-none/release/market_place_contract.wasm"
+    );
+}
+
+#[test]
+fn test_migrate() {
+    let test = MarketplaceTest::setup();
+    let client = test.contract_client();
+    assert_eq!(client.schema_version(), storage::SCHEMA_VERSION);
+
+    // Migrating an up-to-date contract is a no-op.
+    assert_eq!(client.migrate(&test.admin), storage::SCHEMA_VERSION);
+    assert_eq!(client.schema_version(), storage::SCHEMA_VERSION);
+
+    let result = client.try_migrate(&test.creator);
+    assert_eq!(result.unwrap_err(), Ok(ContractError::AdminOnly));
+
+    // Data written by a newer build is left alone.
+    test.env.as_contract(&test.contract_address, || {
+        storage::set_schema_version(&test.env, storage::SCHEMA_VERSION + 1);
+    });
+    let result = client.try_migrate(&test.admin);
+    assert_eq!(result.unwrap_err(), Ok(ContractError::UnsupportedSchemaVersion));
+}
+
+#[test]
+fn test_upgrade_keeps_state() {
+    let test = MarketplaceTest::setup();
+    let client = test.contract_client();
+    let nft_id = test.create_nft();
+
+    let new_wasm_hash = test
+        .env
+        .deployer()
+        .upload_contract_wasm(contract_marketplace::WASM);
+    let result = client.try_upgrade(&test.creator, &new_wasm_hash);
+    assert_eq!(result.unwrap_err(), Ok(ContractError::AdminOnly));
+
+    client.upgrade(&test.admin, &new_wasm_hash);
+
+    // Storage is untouched by the code swap.
+    test.env.as_contract(&test.contract_address, || {
+        assert_eq!(storage::get_admin(&test.env), Some(test.admin.clone()));
+        assert_eq!(storage::get_schema_version(&test.env), storage::SCHEMA_VERSION);
+        assert_eq!(storage::get_nft(&test.env, nft_id).unwrap().creator, test.creator);
+    });
+}
+
+// --- Admin Transfer ---
+
+#[test]
+fn test_two_step_admin_transfer() {
+    let test = MarketplaceTest::setup();
+    let client = test.contract_client();
+    let new_admin = Address::generate(&test.env);
+
+    let result = client.try_accept_admin();
+    assert_eq!(result.unwrap_err(), Ok(ContractError::NoPendingAdmin));
+    let result = client.try_propose_admin(&test.creator, &new_admin, &None);
+    assert_eq!(result.unwrap_err(), Ok(ContractError::AdminOnly));
+
+    // Proposing leaves the current admin in charge until the transfer is accepted.
+    client.propose_admin(&test.admin, &new_admin, &None);
+    assert_eq!(client.get_pending_admin().unwrap().new_admin, new_admin);
+    client.migrate(&test.admin);
+
+    // Only the admin or the proposed admin may cancel.
+    let result = client.try_cancel_admin_transfer(&test.creator);
+    assert_eq!(result.unwrap_err(), Ok(ContractError::AdminOnly));
+    client.cancel_admin_transfer(&test.admin);
+    assert_eq!(client.get_pending_admin(), None);
+
+    client.propose_admin(&test.admin, &new_admin, &None);
+    assert_eq!(client.accept_admin(), new_admin);
+    assert_eq!(test.env.auths()[0].0, new_admin);
+    assert_eq!(client.get_pending_admin(), None);
+
+    let result = client.try_migrate(&test.admin);
+    assert_eq!(result.unwrap_err(), Ok(ContractError::AdminOnly));
+    client.migrate(&new_admin);
+}
+
+#[test]
+fn test_admin_transfer_expiry() {
+    let test = MarketplaceTest::setup();
+    let client = test.contract_client();
+    let new_admin = Address::generate(&test.env);
+    test.env.ledger().set_sequence_number(100);
+
+    let result = client.try_propose_admin(&test.admin, &new_admin, &Some(99));
+    assert_eq!(result.unwrap_err(), Ok(ContractError::InvalidExpiry));
+
+    client.propose_admin(&test.admin, &new_admin, &Some(110));
+    test.env.ledger().set_sequence_number(111);
+    let result = client.try_accept_admin();
+    assert_eq!(result.unwrap_err(), Ok(ContractError::AdminTransferExpired));
+}
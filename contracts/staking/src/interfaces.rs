@@ -1,27 +1,36 @@
-use soroban_sdk::{contractclient, Address, Env, String, Val, Vec};
+use soroban_sdk::{contractclient, Address, Env, String};
 
-/// Standard NFT interface that follows common NFT contract patterns
+use crate::types::ImpactData;
+
+/// Standard NFT interface (SEP-0050) implemented by ImpactProductNFT
 #[contractclient(name = "NftClient")]
 #[allow(dead_code)]
 pub trait NftInterface {
+    /// Returns the number of tokens owned by an address
+    fn balance(env: Env, owner: Address) -> u32;
+
     /// Returns the owner of a specific NFT token
-    fn owner(env: Env, token_id: String) -> Address;
+    fn owner_of(env: Env, token_id: u32) -> Address;
 
     /// Transfers an NFT from one address to another
     /// Requires authorization from the 'from' address
-    fn transfer(env: Env, from: Address, to: Address, token_id: String);
+    fn transfer(env: Env, from: Address, to: Address, token_id: u32);
 
-    /// Balance of tokens owned by an address
-    fn balance(env: Env, owner: Address) -> i128;
+    /// Transfers an NFT on behalf of its owner
+    /// Requires authorization from an approved 'spender'
+    fn transfer_from(env: Env, spender: Address, from: Address, to: Address, token_id: u32);
+
+    /// Returns the address approved for a specific token, if any
+    fn get_approved(env: Env, token_id: u32) -> Option<Address>;
 
-    /// Mints an NFT to a specific address
-    fn mint(env: Env, to: Address, token_id: String);
+    /// Returns true if the operator may manage all tokens of the owner
+    fn is_approved_for_all(env: Env, owner: Address, operator: Address) -> bool;
 
-    /// Returns true if an address is authorized to manage a specific token
-    fn is_authorized(env: Env, owner: Address, spender: Address, token_id: String) -> bool;
+    /// Get the metadata URI for a specific token
+    fn token_uri(env: Env, token_id: u32) -> String;
 
-    /// Get metadata for a specific token
-    fn token_metadata(env: Env, token_id: String) -> String;
+    /// Returns false once a token has been retired or has expired
+    fn is_active(env: Env, token_id: u32) -> bool;
 }
 
 /// Impact data interface for NFTs that have impact metrics
@@ -29,8 +38,7 @@ pub trait NftInterface {
 #[allow(dead_code)]
 pub trait ImpactInterface {
     /// Get impact data for a specific token
-    /// Returns a vector containing [impact_value: u64, verified: bool]
-    fn get_impact_data(env: Env, token_id: String) -> Vec<Val>;
+    fn get_impact_data(env: Env, token_id: u32) -> ImpactData;
 }
 
 /// Standard token interface for payment tokens
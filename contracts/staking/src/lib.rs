@@ -1,7 +1,5 @@
 #![no_std]
-use soroban_sdk::{
-    contract, contractimpl, symbol_short, Address, Env, String, TryFromVal, Val, Vec,
-};
+use soroban_sdk::{contract, contractimpl, symbol_short, Address, BytesN, Env, Vec};
 
 mod types;
 use types::*;
@@ -41,6 +39,31 @@ pub struct StakingParamsUpdatedEvent {
     pub max_lock_period: u64,
 }
 
+#[contracttype]
+pub struct AdminProposedEvent {
+    pub admin: Address,
+    pub new_admin: Address,
+    pub live_until_ledger: Option<u32>,
+}
+
+#[contracttype]
+pub struct AdminAcceptedEvent {
+    pub previous_admin: Address,
+    pub new_admin: Address,
+}
+
+#[contracttype]
+pub struct AdminCancelledEvent {
+    pub caller: Address,
+    pub new_admin: Address,
+}
+
+#[contracttype]
+pub struct SchemaMigratedEvent {
+    pub from_version: u32,
+    pub to_version: u32,
+}
+
 #[contract]
 pub struct ImpactProductStaking;
 
@@ -64,6 +87,9 @@ impl ImpactProductStaking {
             max_lock_period: 365 * 24 * 60 * 60, // 365 days
         };
         env.storage().instance().set(&DataKey::Params, &params);
+        env.storage()
+            .instance()
+            .set(&DataKey::SchemaVersion, &SCHEMA_VERSION);
     }
 
     pub fn stake_nft(env: Env, user: Address, token_id: u32, lock_period: u64) {
@@ -81,15 +107,19 @@ impl ImpactProductStaking {
 
         // Use proper NFT interface to check ownership
         let nft_client = NftClient::new(&env, &nft_contract);
-        let token_id_str = token_id_to_string(&env, token_id);
-        let owner = nft_client.owner(&token_id_str);
+        let owner = nft_client.owner_of(&token_id);
 
         if owner != user {
             panic!("Not the token owner");
         }
 
+        // Expired or retired impact can no longer be staked
+        if !nft_client.is_active(&token_id) {
+            panic!("Token is not active");
+        }
+
         // Transfer NFT to this contract using proper interface
-        nft_client.transfer(&user, &env.current_contract_address(), &token_id_str);
+        nft_client.transfer(&user, &env.current_contract_address(), &token_id);
         let now = env.ledger().timestamp();
         let lock_end_time = now + lock_period;
         let multiplier = calculate_multiplier(lock_period);
@@ -129,36 +159,7 @@ impl ImpactProductStaking {
 
     pub fn claim_rewards(env: Env, user: Address, token_id: u32) -> u64 {
         user.require_auth();
-        let mut stake: NFTStake = env
-            .storage()
-            .instance()
-            .get(&DataKey::Stake(token_id))
-            .unwrap();
-        if stake.owner != user {
-            panic!("Not stake owner");
-        }
-        let reward = calculate_rewards(&env, &stake);
-        if reward == 0 {
-            panic!("No rewards to claim");
-        }
-        stake.last_claim_time = env.ledger().timestamp();
-        env.storage()
-            .instance()
-            .set(&DataKey::Stake(token_id), &stake);
-        // Cross-contract call to REBAZ token contract to mint reward
-        let rebaz_token: Address = env.storage().instance().get(&DataKey::REBAZToken).unwrap();
-        let token_client = TokenClient::new(&env, &rebaz_token);
-        token_client.mint(&user, &(reward as i128));
-        // Emit RewardsClaimed event
-        env.events().publish(
-            (symbol_short!("r_clmd"),),
-            RewardsClaimedEvent {
-                token_id,
-                owner: user,
-                amount: reward,
-            },
-        );
-        reward
+        claim(&env, user, token_id)
     }
 
     pub fn unstake_nft(env: Env, user: Address, token_id: u32) -> u64 {
@@ -174,7 +175,7 @@ impl ImpactProductStaking {
         if env.ledger().timestamp() < stake.lock_end_time {
             panic!("Lock period not ended");
         }
-        let reward = Self::claim_rewards(env.clone(), user.clone(), token_id);
+        let reward = claim(&env, user.clone(), token_id);
         env.storage().instance().remove(&DataKey::Stake(token_id));
         let mut staked_tokens: Vec<u32> = env
             .storage()
@@ -197,8 +198,7 @@ impl ImpactProductStaking {
         // Cross-contract call to NFT contract to transfer NFT back to user
         let nft_contract: Address = env.storage().instance().get(&DataKey::NFTContract).unwrap();
         let nft_client = NftClient::new(&env, &nft_contract);
-        let token_id_str = token_id_to_string(&env, token_id);
-        nft_client.transfer(&env.current_contract_address(), &user, &token_id_str);
+        nft_client.transfer(&env.current_contract_address(), &user, &token_id);
         // Emit NFTUnstaked event
         env.events().publish(
             (symbol_short!("nft_unstk"),),
@@ -241,10 +241,7 @@ impl ImpactProductStaking {
         max_lock_period: u64,
     ) {
         admin.require_auth();
-        let stored_admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
-        if stored_admin != admin {
-            panic!("Not admin");
-        }
+        require_admin(&env, &admin);
         let params = StakingParams {
             base_reward_rate,
             min_lock_period,
@@ -261,6 +258,132 @@ impl ImpactProductStaking {
             },
         );
     }
+
+    // The admin only changes once new_admin calls accept_admin
+    pub fn propose_admin(
+        env: Env,
+        admin: Address,
+        new_admin: Address,
+        live_until_ledger: Option<u32>,
+    ) {
+        admin.require_auth();
+        require_admin(&env, &admin);
+        if live_until_ledger.is_some_and(|ledger| ledger < env.ledger().sequence()) {
+            panic!("Invalid expiry");
+        }
+        env.storage().instance().set(
+            &DataKey::PendingAdmin,
+            &AdminTransfer {
+                new_admin: new_admin.clone(),
+                live_until_ledger,
+            },
+        );
+        env.events().publish(
+            (symbol_short!("adm_prop"),),
+            AdminProposedEvent {
+                admin,
+                new_admin,
+                live_until_ledger,
+            },
+        );
+    }
+
+    pub fn accept_admin(env: Env) -> Address {
+        let transfer: AdminTransfer = env
+            .storage()
+            .instance()
+            .get(&DataKey::PendingAdmin)
+            .expect("No pending admin");
+        transfer.new_admin.require_auth();
+        if transfer
+            .live_until_ledger
+            .is_some_and(|ledger| ledger < env.ledger().sequence())
+        {
+            panic!("Admin transfer expired");
+        }
+        let previous_admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        env.storage()
+            .instance()
+            .set(&DataKey::Admin, &transfer.new_admin);
+        env.storage().instance().remove(&DataKey::PendingAdmin);
+        env.events().publish(
+            (symbol_short!("adm_accpt"),),
+            AdminAcceptedEvent {
+                previous_admin,
+                new_admin: transfer.new_admin.clone(),
+            },
+        );
+        transfer.new_admin
+    }
+
+    pub fn cancel_admin_transfer(env: Env, caller: Address) {
+        caller.require_auth();
+        let transfer: AdminTransfer = env
+            .storage()
+            .instance()
+            .get(&DataKey::PendingAdmin)
+            .expect("No pending admin");
+        if caller != transfer.new_admin {
+            require_admin(&env, &caller);
+        }
+        env.storage().instance().remove(&DataKey::PendingAdmin);
+        env.events().publish(
+            (symbol_short!("adm_cncl"),),
+            AdminCancelledEvent {
+                caller,
+                new_admin: transfer.new_admin,
+            },
+        );
+    }
+
+    pub fn get_pending_admin(env: Env) -> Option<AdminTransfer> {
+        env.storage().instance().get(&DataKey::PendingAdmin)
+    }
+
+    pub fn upgrade(env: Env, admin: Address, new_wasm_hash: BytesN<32>) {
+        admin.require_auth();
+        require_admin(&env, &admin);
+        // Storage is kept; migrate brings it up to the new code's schema
+        env.deployer()
+            .update_current_contract_wasm(new_wasm_hash.clone());
+        env.events()
+            .publish((symbol_short!("upgrade"),), new_wasm_hash);
+    }
+
+    // Stored layout version, 0 for contracts initialized before versioning
+    pub fn schema_version(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::SchemaVersion)
+            .unwrap_or(0)
+    }
+
+    pub fn migrate(env: Env, admin: Address) -> u32 {
+        admin.require_auth();
+        require_admin(&env, &admin);
+        let from_version = Self::schema_version(env.clone());
+        if from_version > SCHEMA_VERSION {
+            panic!("Unsupported schema version");
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::SchemaVersion, &SCHEMA_VERSION);
+        env.events().publish(
+            (symbol_short!("migrate"),),
+            SchemaMigratedEvent {
+                from_version,
+                to_version: SCHEMA_VERSION,
+            },
+        );
+        SCHEMA_VERSION
+    }
+}
+
+fn require_admin(env: &Env, admin: &Address) {
+    let stored_admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+    if stored_admin != *admin {
+        panic!("Not admin");
+    }
 }
 
 fn calculate_multiplier(lock_period: u64) -> u32 {
@@ -278,16 +401,47 @@ fn calculate_multiplier(lock_period: u64) -> u32 {
     }
 }
 
+// Mints the rewards accrued on a stake to its owner and restarts accrual
+fn claim(env: &Env, user: Address, token_id: u32) -> u64 {
+    let mut stake: NFTStake = env
+        .storage()
+        .instance()
+        .get(&DataKey::Stake(token_id))
+        .unwrap();
+    if stake.owner != user {
+        panic!("Not stake owner");
+    }
+    let reward = calculate_rewards(env, &stake);
+    if reward == 0 {
+        panic!("No rewards to claim");
+    }
+    stake.last_claim_time = env.ledger().timestamp();
+    env.storage()
+        .instance()
+        .set(&DataKey::Stake(token_id), &stake);
+    // Cross-contract call to REBAZ token contract to mint reward
+    let rebaz_token: Address = env.storage().instance().get(&DataKey::REBAZToken).unwrap();
+    let token_client = TokenClient::new(env, &rebaz_token);
+    token_client.mint(&user, &(reward as i128));
+    // Emit RewardsClaimed event
+    env.events().publish(
+        (symbol_short!("r_clmd"),),
+        RewardsClaimedEvent {
+            token_id,
+            owner: user,
+            amount: reward,
+        },
+    );
+    reward
+}
+
 fn calculate_rewards(env: &Env, stake: &NFTStake) -> u64 {
     let nft_contract: Address = env.storage().instance().get(&DataKey::NFTContract).unwrap();
     // Use proper impact interface to get impact data
     let impact_client = ImpactClient::new(env, &nft_contract);
-    let token_id_str = token_id_to_string(env, stake.token_id);
-    let impact_vec: Vec<Val> = impact_client.get_impact_data(&token_id_str);
-    let impact_value = u64::try_from_val(env, &impact_vec.get(0).unwrap()).unwrap();
-    let verified = bool::try_from_val(env, &impact_vec.get(1).unwrap()).unwrap();
-    let mut impact = impact_value;
-    if verified {
+    let impact_data: ImpactData = impact_client.get_impact_data(&stake.token_id);
+    let mut impact = u64::try_from(impact_data.impact_value).expect("Impact value too large");
+    if impact_data.verified {
         impact = (impact * 120) / 100;
     }
     let params: StakingParams = env.storage().instance().get(&DataKey::Params).unwrap();
@@ -298,16 +452,3 @@ fn calculate_rewards(env: &Env, stake: &NFTStake) -> u64 {
             / (10000 * 10000);
     rewards
 }
-
-// Helper function to convert token_id to string
-fn token_id_to_string(env: &Env, token_id: u32) -> String {
-    // Simple conversion for now - can be improved
-    match token_id {
-        1 => String::from_str(env, "1"),
-        2 => String::from_str(env, "2"),
-        3 => String::from_str(env, "3"),
-        4 => String::from_str(env, "4"),
-        5 => String::from_str(env, "5"),
-        _ => String::from_str(env, "1"), // Default fallback
-    }
-}
@@ -1,4 +1,6 @@
-use soroban_sdk::{contracttype, Address};
+use soroban_sdk::{contracttype, Address, String};
+
+pub const SCHEMA_VERSION: u32 = 1;
 
 #[contracttype]
 pub struct NFTStake {
@@ -11,6 +13,19 @@ pub struct NFTStake {
     pub multiplier: u32,
 }
 
+// Impact data as returned by ImpactProductNFT::get_impact_data
+#[contracttype]
+pub struct ImpactData {
+    pub category: String,
+    pub impact_value: u128,
+    pub location: String,
+    pub start_date: u128,
+    pub end_date: u128,
+    pub beneficiaries: String,
+    pub verified: bool,
+    pub metadata_uri: String,
+}
+
 #[contracttype]
 pub struct StakingParams {
     pub base_reward_rate: u32,
@@ -18,6 +33,13 @@ pub struct StakingParams {
     pub max_lock_period: u64,
 }
 
+// A proposed admin hand-over, acceptable until live_until_ledger if set
+#[contracttype]
+pub struct AdminTransfer {
+    pub new_admin: Address,
+    pub live_until_ledger: Option<u32>,
+}
+
 #[contracttype]
 pub enum DataKey {
     Stake(u32),            // token_id -> NFTStake
@@ -26,6 +48,8 @@ pub enum DataKey {
     Admin,
     NFTContract,
     REBAZToken,
-    Owner(u32),  // token_id -> Address
-    Impact(u32), // token_id -> Vec<Val>
+    Owner(u32),    // token_id -> Address
+    Impact(u32),   // token_id -> ImpactData
+    SchemaVersion, // storage layout version
+    PendingAdmin,  // AdminTransfer awaiting acceptance
 }
@@ -1,5 +1,5 @@
-use crate::types::{ContractConfig, ImpactProduct, Purchase};
-use soroban_sdk::{contractclient, Address, Env, Map, String, Vec};
+use crate::types::{AdminTransfer, ContractConfig, ImpactProduct, Purchase};
+use soroban_sdk::{contractclient, Address, BytesN, Env, Map, String, Vec};
 
 /// This trait defines the interface for the ImpactBuyerContract
 /// Other contracts can use this interface to interact with our marketplace
@@ -15,7 +15,7 @@ pub trait ImpactBuyerInterface {
         price: i128,
         token: Address,
         nft_contract: Address,
-        nft_token_id: String,
+        nft_token_id: u32,
         impact_metrics: Map<String, String>,
     ) -> u32;
 
@@ -69,4 +69,25 @@ pub trait ImpactBuyerInterface {
 
     /// Get the admin address
     fn get_admin(env: Env) -> Address;
+
+    /// Propose a new admin, who takes over once they accept (admin only)
+    fn propose_admin(env: Env, admin: Address, new_admin: Address, live_until_ledger: Option<u32>);
+
+    /// Accept a pending admin transfer (proposed admin only)
+    fn accept_admin(env: Env) -> Address;
+
+    /// Cancel a pending admin transfer (admin or proposed admin)
+    fn cancel_admin_transfer(env: Env, caller: Address);
+
+    /// Get the pending admin transfer, if any
+    fn get_pending_admin(env: Env) -> Option<AdminTransfer>;
+
+    /// Replace the contract code with an uploaded wasm, keeping storage (admin only)
+    fn upgrade(env: Env, admin: Address, new_wasm_hash: BytesN<32>);
+
+    /// Get the storage schema version (0 for contracts initialized before versioning)
+    fn schema_version(env: Env) -> u32;
+
+    /// Run the data migrations up to this build's schema version (admin only)
+    fn migrate(env: Env, admin: Address) -> u32;
 }
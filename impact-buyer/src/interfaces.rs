@@ -3,36 +3,42 @@ use soroban_sdk::{contractclient, Address, Env, String};
 /// This trait defines the expected interface for NFT contracts
 /// that our impact buyer contract will interact with.
 ///
-/// It's based on a simple NFT standard that includes ownership
-/// and transfer capabilities, which are the minimum required
-/// functions for our impact marketplace.
+/// It follows the Stellar non-fungible token standard (SEP-0050)
+/// implemented by ImpactProductNFT, of which ownership and transfer
+/// are the minimum required functions for our impact marketplace.
 #[contractclient(name = "NftClient")]
 pub trait NftInterface {
-    /// Initialize the NFT contract with basic metadata
-    fn initialize(env: Env, admin: Address, name: String, symbol: String);
+    /// Balance of tokens owned by an address
+    fn balance(env: Env, owner: Address) -> u32;
 
     /// Returns the owner of a specific NFT token
-    fn owner(env: Env, token_id: String) -> Address;
+    fn owner_of(env: Env, token_id: u32) -> Address;
 
     /// Transfers an NFT from one address to another
     /// Requires authorization from the 'from' address
-    fn transfer(env: Env, from: Address, to: Address, token_id: String);
+    fn transfer(env: Env, from: Address, to: Address, token_id: u32);
 
-    /// Balance of tokens owned by an address (may not be relevant for all NFTs)
-    /// For standard NFTs, this should return the count of NFTs owned
-    fn balance(env: Env, owner: Address) -> i128;
-
-    /// Mints an NFT to a specific address
-    fn mint(env: Env, to: Address, token_id: String);
-
-    /// Returns true if an address is authorized to manage a specific token
+    /// Transfers an NFT on behalf of its owner
     /// This is useful for marketplaces and other contracts that need to
     /// transfer NFTs on behalf of users
-    fn is_authorized(env: Env, owner: Address, spender: Address, token_id: String) -> bool;
+    fn transfer_from(env: Env, spender: Address, from: Address, to: Address, token_id: u32);
+
+    /// Returns the address approved to transfer a specific token, if any
+    fn get_approved(env: Env, token_id: u32) -> Option<Address>;
+
+    /// Returns true if the operator may manage every token of the owner
+    fn is_approved_for_all(env: Env, owner: Address, operator: Address) -> bool;
+
+    /// Returns the metadata URI for a specific token
+    fn token_uri(env: Env, token_id: u32) -> String;
+
+    /// Returns the royalty receiver and the amount owed to them when the
+    /// token sells for `sale_price` (EIP-2981 style)
+    fn royalty_info(env: Env, token_id: u32, sale_price: i128) -> (Address, i128);
 
-    /// Optional: Get metadata for a specific token
-    /// Returns a string that might contain JSON or other encoded metadata
-    fn token_metadata(env: Env, token_id: String) -> String;
+    /// Returns false once a token has expired or been retired and can no
+    /// longer be sold
+    fn is_active(env: Env, token_id: u32) -> bool;
 }
 
 /// Standard token interface for payment tokens
@@ -5,8 +5,10 @@ mod types;
 
 pub use client::{ImpactBuyerClient, ImpactBuyerInterface};
 use interfaces::{NftClient, TokenClient};
-use soroban_sdk::{contract, contractimpl, Address, Env, Map, String, Vec};
-use types::{ContractConfig, DataKey, ErrorCode, ImpactProduct, Purchase};
+use soroban_sdk::{contract, contractimpl, symbol_short, Address, BytesN, Env, Map, String, Vec};
+use types::{
+    AdminTransfer, ContractConfig, DataKey, ErrorCode, ImpactProduct, Purchase, SCHEMA_VERSION,
+};
 
 #[contract]
 pub struct ImpactBuyerContract;
@@ -45,6 +47,11 @@ impl ImpactBuyerInterface for ImpactBuyerContract {
         env.storage()
             .instance()
             .set(&DataKey::PurchaseCounter, &0u32);
+
+        // Record the storage layout version
+        env.storage()
+            .instance()
+            .set(&DataKey::SchemaVersion, &SCHEMA_VERSION);
     }
 
     // List a new impact NFT product
@@ -54,7 +61,7 @@ impl ImpactBuyerInterface for ImpactBuyerContract {
         price: i128,
         token: Address,
         nft_contract: Address,
-        nft_token_id: String,
+        nft_token_id: u32,
         impact_metrics: Map<String, String>,
     ) -> u32 {
         // Check if contract is paused
@@ -65,12 +72,17 @@ impl ImpactBuyerInterface for ImpactBuyerContract {
 
         // Verify the seller owns the NFT
         let nft_client = NftClient::new(&env, &nft_contract);
-        let nft_owner = nft_client.owner(&nft_token_id);
+        let nft_owner = nft_client.owner_of(&nft_token_id);
 
         if nft_owner != seller {
             panic!("{:?}", ErrorCode::Unauthorized);
         }
 
+        // Expired or retired impact cannot be listed
+        if !Self.is_token_active(&nft_client, nft_token_id) {
+            panic!("{:?}", ErrorCode::ProductInactive);
+        }
+
         // Get and increment product counter
         let product_counter: u32 = env
             .storage()
@@ -90,7 +102,7 @@ impl ImpactBuyerInterface for ImpactBuyerContract {
             seller: seller.clone(),
             token,
             nft_contract,
-            nft_token_id: nft_token_id.clone(),
+            nft_token_id,
             impact_metrics,
             is_listed: true,
         };
@@ -262,10 +274,21 @@ impl ImpactBuyerInterface for ImpactBuyerContract {
             panic!("{:?}", ErrorCode::CannotBuyOwnNFT);
         }
 
+        // Check the NFT has not expired or been retired since it was listed
+        let nft_client = NftClient::new(&env, &product.nft_contract);
+        if !Self.is_token_active(&nft_client, product.nft_token_id) {
+            panic!("{:?}", ErrorCode::ProductInactive);
+        }
+
         // Calculate total price and platform fee
         let total_price = product.price;
         let fee = Self.calculate_fee(&env, total_price);
-        let seller_amount = total_price - fee;
+
+        // Ask the NFT contract for its royalty, never paying more than the seller's share
+        let (royalty_receiver, royalty) =
+            Self.calculate_royalty(&nft_client, product.nft_token_id, total_price);
+        let royalty = royalty.clamp(0, total_price - fee);
+        let seller_amount = total_price - fee - royalty;
 
         // Transfer payment tokens from buyer to seller and admin
         let token_client = TokenClient::new(&env, &product.token);
@@ -281,8 +304,12 @@ impl ImpactBuyerInterface for ImpactBuyerContract {
         let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
         token_client.transfer(&buyer, &admin, &fee);
 
+        // Transfer royalty to the NFT's royalty receiver
+        if royalty > 0 {
+            token_client.transfer(&buyer, &royalty_receiver, &royalty);
+        }
+
         // Transfer NFT from contract to buyer (from escrow)
-        let nft_client = NftClient::new(&env, &product.nft_contract);
         let contract_address = env.current_contract_address();
         nft_client.transfer(&contract_address, &buyer, &product.nft_token_id);
 
@@ -310,6 +337,7 @@ impl ImpactBuyerInterface for ImpactBuyerContract {
             buyer: buyer.clone(),
             total_price,
             platform_fee: fee,
+            royalty_fee: royalty,
             nft_contract: product.nft_contract,
             nft_token_id: product.nft_token_id,
             timestamp: env.ledger().timestamp(),
@@ -513,6 +541,131 @@ impl ImpactBuyerInterface for ImpactBuyerContract {
     fn get_admin(env: Env) -> Address {
         env.storage().instance().get(&DataKey::Admin).unwrap()
     }
+
+    // Propose a new admin (admin only)
+    fn propose_admin(env: Env, admin: Address, new_admin: Address, live_until_ledger: Option<u32>) {
+        admin.require_auth();
+
+        // Check if admin
+        if !Self.is_admin(&env, &admin) {
+            panic!("{:?}", ErrorCode::Unauthorized);
+        }
+
+        // An expiry must not already have passed
+        if live_until_ledger.is_some_and(|ledger| ledger < env.ledger().sequence()) {
+            panic!("{:?}", ErrorCode::InvalidExpiry);
+        }
+
+        // Replace any earlier proposal
+        let transfer = AdminTransfer {
+            new_admin: new_admin.clone(),
+            live_until_ledger,
+        };
+        env.storage()
+            .instance()
+            .set(&DataKey::PendingAdmin, &transfer);
+
+        let topics = (DataKey::AdminProposed, admin);
+        env.events().publish(topics, (new_admin, live_until_ledger));
+    }
+
+    // Accept a pending admin transfer (proposed admin only)
+    fn accept_admin(env: Env) -> Address {
+        let transfer = Self::get_pending_admin(env.clone())
+            .unwrap_or_else(|| panic!("{:?}", ErrorCode::NoPendingAdmin));
+        transfer.new_admin.require_auth();
+
+        // Check the proposal has not lapsed
+        if transfer
+            .live_until_ledger
+            .is_some_and(|ledger| ledger < env.ledger().sequence())
+        {
+            panic!("{:?}", ErrorCode::AdminTransferExpired);
+        }
+
+        // Hand over the admin role
+        let previous_admin = Self::get_admin(env.clone());
+        env.storage()
+            .instance()
+            .set(&DataKey::Admin, &transfer.new_admin);
+        env.storage().instance().remove(&DataKey::PendingAdmin);
+
+        let topics = (DataKey::AdminAccepted, previous_admin);
+        env.events().publish(topics, transfer.new_admin.clone());
+
+        transfer.new_admin
+    }
+
+    // Cancel a pending admin transfer (admin or proposed admin)
+    fn cancel_admin_transfer(env: Env, caller: Address) {
+        caller.require_auth();
+
+        let transfer = Self::get_pending_admin(env.clone())
+            .unwrap_or_else(|| panic!("{:?}", ErrorCode::NoPendingAdmin));
+
+        // The admin can withdraw the proposal and the proposed admin can decline it
+        if caller != transfer.new_admin && !Self.is_admin(&env, &caller) {
+            panic!("{:?}", ErrorCode::Unauthorized);
+        }
+
+        env.storage().instance().remove(&DataKey::PendingAdmin);
+
+        let topics = (DataKey::AdminCancelled, caller);
+        env.events().publish(topics, transfer.new_admin);
+    }
+
+    // Get the pending admin transfer
+    fn get_pending_admin(env: Env) -> Option<AdminTransfer> {
+        env.storage().instance().get(&DataKey::PendingAdmin)
+    }
+
+    // Upgrade the contract code (admin only)
+    fn upgrade(env: Env, admin: Address, new_wasm_hash: BytesN<32>) {
+        admin.require_auth();
+
+        // Check if admin
+        if !Self.is_admin(&env, &admin) {
+            panic!("{:?}", ErrorCode::Unauthorized);
+        }
+
+        // Storage is kept; `migrate` brings it up to the new code's schema
+        env.deployer()
+            .update_current_contract_wasm(new_wasm_hash.clone());
+        env.events()
+            .publish((symbol_short!("upgrade"),), new_wasm_hash);
+    }
+
+    // Get the storage schema version
+    fn schema_version(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::SchemaVersion)
+            .unwrap_or(0)
+    }
+
+    // Run data migrations (admin only)
+    fn migrate(env: Env, admin: Address) -> u32 {
+        admin.require_auth();
+
+        // Check if admin
+        if !Self.is_admin(&env, &admin) {
+            panic!("{:?}", ErrorCode::Unauthorized);
+        }
+
+        // Refuse to run against data written by a newer build
+        let from = Self::schema_version(env.clone());
+        if from > SCHEMA_VERSION {
+            panic!("{:?}", ErrorCode::UnsupportedSchemaVersion);
+        }
+
+        env.storage()
+            .instance()
+            .set(&DataKey::SchemaVersion, &SCHEMA_VERSION);
+        env.events()
+            .publish((symbol_short!("migrate"),), (from, SCHEMA_VERSION));
+
+        SCHEMA_VERSION
+    }
 }
 
 impl ImpactBuyerContract {
@@ -534,6 +687,23 @@ impl ImpactBuyerContract {
         (amount * (config.fee_percentage as i128)) / 1000i128
     }
 
+    // Query the royalty owed on a sale. A failing query aborts the sale rather
+    // than letting it through without the royalty.
+    fn calculate_royalty(
+        &self,
+        nft_client: &NftClient,
+        token_id: u32,
+        sale_price: i128,
+    ) -> (Address, i128) {
+        nft_client.royalty_info(&token_id, &sale_price)
+    }
+
+    // Check whether a token can still be sold. A failing query aborts the
+    // listing or sale rather than treating the token as active.
+    fn is_token_active(&self, nft_client: &NftClient, token_id: u32) -> bool {
+        nft_client.is_active(&token_id)
+    }
+
     // Ensure contract is not paused
     fn ensure_not_paused(&self, env: &Env) {
         if self.is_paused(env) {
@@ -1,11 +1,15 @@
 #![cfg(test)]
 
 use super::*;
-use crate::interfaces::{NftClient, NftInterface};
 use soroban_sdk::token::Client as TokenClient;
 use soroban_sdk::token::StellarAssetClient as TokenAdmin;
-use soroban_sdk::{contract, contractimpl, contracttype, Address, Env, String};
-use soroban_sdk::{map, testutils::Address as _, Map};
+use soroban_sdk::{contract, contractimpl, contracttype, Address, BytesN, Env, String};
+use soroban_sdk::{map, testutils::Address as _, testutils::Ledger, Map};
+
+// Release build of this contract, uploaded as the upgrade target
+mod contract_buyer {
+    soroban_sdk::contractimport!(file = "target/wasm32-unknown-unknown/release/impact_buyer.wasm");
+}
 
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -14,9 +18,12 @@ pub enum DataKey {
     Name,
     Symbol,
     TokenCounter,     // Counter for token IDs
-    Token(String),    // Token data by ID
-    Owner(String),    // Owner of a specific token
+    Token(u32),       // Token data by ID
+    Owner(u32),       // Owner of a specific token
     Balance(Address), // Balance of an address
+    Royalty(u32),     // Royalty receiver and basis points of a token
+    Inactive(u32),    // Tokens marked as expired or retired
+    RoyaltyBroken,    // Royalty queries fail
 }
 
 #[contract]
@@ -32,11 +39,11 @@ impl MockNftContract {
         storage.set(&DataKey::Admin, &admin);
         storage.set(&DataKey::Name, &name);
         storage.set(&DataKey::Symbol, &symbol);
-        storage.set(&DataKey::TokenCounter, &0i128);
+        storage.set(&DataKey::TokenCounter, &0u32);
     }
 
     // Returns the owner of a specific token
-    pub fn owner(env: Env, token_id: String) -> Address {
+    pub fn owner_of(env: Env, token_id: u32) -> Address {
         let storage = env.storage().persistent();
 
         storage
@@ -45,29 +52,29 @@ impl MockNftContract {
     }
 
     // Mint a new token to the specified address
-    pub fn mint(env: Env, to: Address, token_id: String) {
+    pub fn mint(env: Env, to: Address, token_id: u32) {
         let storage = env.storage().persistent();
 
         // Store owner information with token ID
-        storage.set(&DataKey::Owner(token_id.clone()), &to);
+        storage.set(&DataKey::Owner(token_id), &to);
 
         // Update token counter
         let counter_key = DataKey::TokenCounter;
-        let counter: i128 = storage.get(&counter_key).unwrap_or(0);
+        let counter: u32 = storage.get(&counter_key).unwrap_or(0);
         storage.set(&counter_key, &(counter + 1));
 
         // Store empty metadata for this token
-        let metadata_key = DataKey::Token(token_id.clone());
+        let metadata_key = DataKey::Token(token_id);
         storage.set(&metadata_key, &String::from_str(&env, "{}"));
 
         // Update balance for the owner
         let balance_key = DataKey::Balance(to);
-        let balance: i128 = storage.get(&balance_key).unwrap_or(0);
+        let balance: u32 = storage.get(&balance_key).unwrap_or(0);
         storage.set(&balance_key, &(balance + 1));
     }
 
     // Get token balance for an address
-    pub fn balance(env: Env, owner: Address) -> i128 {
+    pub fn balance(env: Env, owner: Address) -> u32 {
         env.storage()
             .persistent()
             .get(&DataKey::Balance(owner))
@@ -75,11 +82,11 @@ impl MockNftContract {
     }
 
     // Transfer a token from one address to another
-    pub fn transfer(env: Env, from: Address, to: Address, token_id: String) {
+    pub fn transfer(env: Env, from: Address, to: Address, token_id: u32) {
         from.require_auth();
 
         let storage = env.storage().persistent();
-        let owner_key = DataKey::Owner(token_id.clone());
+        let owner_key = DataKey::Owner(token_id);
 
         // Check current owner
         let _: Address = storage
@@ -96,35 +103,75 @@ impl MockNftContract {
 
         // Update balances
         let balance_key = DataKey::Balance(from);
-        let balance: i128 = storage.get(&balance_key).unwrap_or(0);
-        storage.set(&balance_key, &(balance - 1));
+        let balance: u32 = storage.get(&balance_key).unwrap_or(0);
+        storage.set(&balance_key, &balance.saturating_sub(1));
 
         let balance_key = DataKey::Balance(to);
-        let balance: i128 = storage.get(&balance_key).unwrap_or(0);
+        let balance: u32 = storage.get(&balance_key).unwrap_or(0);
         storage.set(&balance_key, &(balance + 1));
     }
 
-    // Check if spender is authorized for this token
-    pub fn is_authorized(env: Env, owner: Address, spender: Address, token_id: String) -> bool {
-        // Simple implementation - only token owner is authorized
-        let storage = env.storage().persistent();
-        let owner_key = DataKey::Owner(token_id.clone());
-        let current_owner: Option<Address> = storage.get(&owner_key);
+    // Returns the address approved for this token
+    pub fn get_approved(_env: Env, _token_id: u32) -> Option<Address> {
+        // Simple implementation - no per-token approvals
+        None
+    }
 
-        match current_owner {
-            Some(addr) => addr == spender,
-            None => false,
-        }
+    // Check if operator may manage all tokens of the owner
+    pub fn is_approved_for_all(_env: Env, _owner: Address, _operator: Address) -> bool {
+        // Simple implementation - no operators
+        false
     }
 
-    // Get token metadata
-    pub fn token_metadata(env: Env, token_id: String) -> String {
+    // Get token metadata URI
+    pub fn token_uri(env: Env, token_id: u32) -> String {
         let storage = env.storage().persistent();
-        let metadata_key = DataKey::Token(token_id.clone());
+        let metadata_key = DataKey::Token(token_id);
         storage
             .get(&metadata_key)
             .unwrap_or_else(|| String::from_str(&env, "{}"))
     }
+
+    // Set the royalty charged on sales of a token
+    pub fn set_royalty(env: Env, token_id: u32, receiver: Address, basis_points: u32) {
+        env.storage()
+            .persistent()
+            .set(&DataKey::Royalty(token_id), &(receiver, basis_points));
+    }
+
+    // Mark a token as expired or retired
+    pub fn deactivate(env: Env, token_id: u32) {
+        env.storage()
+            .persistent()
+            .set(&DataKey::Inactive(token_id), &true);
+    }
+
+    // Check whether a token can still be sold
+    pub fn is_active(env: Env, token_id: u32) -> bool {
+        !env.storage().persistent().has(&DataKey::Inactive(token_id))
+    }
+
+    // Make every royalty query fail
+    pub fn break_royalty(env: Env) {
+        env.storage().persistent().set(&DataKey::RoyaltyBroken, &true);
+    }
+
+    // Get the royalty receiver and amount owed on a sale
+    pub fn royalty_info(env: Env, token_id: u32, sale_price: i128) -> (Address, i128) {
+        if env.storage().persistent().has(&DataKey::RoyaltyBroken) {
+            panic!("royalty unavailable");
+        }
+        match env
+            .storage()
+            .persistent()
+            .get::<DataKey, (Address, u32)>(&DataKey::Royalty(token_id))
+        {
+            Some((receiver, basis_points)) => {
+                (receiver, sale_price * basis_points as i128 / 10_000)
+            }
+            None => (env.current_contract_address(), 0),
+        }
+    }
 }
 
 // Create a simple token for testing
@@ -147,7 +194,7 @@ fn create_token_contract<'a>(
 // Define a contract to mock an NFT
 
 // Create a mock NFT contract for testing
-fn create_nft_contract(e: &Env) -> (Address, NftClient) {
+fn create_nft_contract(e: &Env) -> (Address, MockNftContractClient) {
     // Register the contract in the environment
     let contract_id = e.register_contract(None, MockNftContract);
 
@@ -156,17 +203,14 @@ fn create_nft_contract(e: &Env) -> (Address, NftClient) {
     e.mock_all_auths();
 
     // Call the initialize function
-    let client = NftClient::new(e, &contract_id);
+    let client = MockNftContractClient::new(e, &contract_id);
     client.initialize(
         &admin,
         &String::from_str(e, "TestNFT"),
         &String::from_str(e, "TNFT"),
     );
 
-    // Create an NftClient using the interfaces module
-    let nft_client = NftClient::new(e, &contract_id);
-
-    (contract_id, nft_client)
+    (contract_id, client)
 }
 
 fn create_impact_buyer_contract(e: &Env) -> (Address, ImpactBuyerClient) {
@@ -217,7 +261,7 @@ fn test_list_and_buy_product() {
     token_admin.mint(&buyer, &1_000_000_000);
 
     // Create an NFT for the seller
-    let nft_id = String::from_str(&env, "NFT001");
+    let nft_id = 1u32;
     nft_client.mint(&seller, &nft_id);
 
     // Create impact buyer contract
@@ -315,6 +359,121 @@ fn test_list_and_buy_product() {
     assert_eq!(buyer_purchases.len(), 1);
 }
 
+#[test]
+fn test_buy_product_pays_royalty() {
+    let env = Env::default();
+    let admin = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let creator = Address::generate(&env);
+
+    let (token_address, token_client, token_admin) = create_token_contract(&env, &admin);
+    let (nft_address, nft_client) = create_nft_contract(&env);
+    token_admin.mint(&buyer, &1_000_000_000);
+
+    // Mint the NFT with a 5% royalty to its creator
+    let nft_id = 1u32;
+    nft_client.mint(&seller, &nft_id);
+    nft_client.set_royalty(&nft_id, &creator, &500u32);
+
+    // Initialize marketplace with 2.5% fee
+    let (marketplace_address, marketplace) = create_impact_buyer_contract(&env);
+    env.mock_all_auths();
+    marketplace.initialize(&admin, &25u32);
+
+    let price = 100_000_000i128;
+    let product_id = marketplace.list_product(
+        &seller,
+        &price,
+        &token_address,
+        &nft_address,
+        &nft_id,
+        &Map::new(&env),
+    );
+    nft_client.transfer(&seller, &marketplace_address, &nft_id);
+
+    let admin_balance = token_client.balance(&admin);
+    let purchase_id = marketplace.buy_product(&buyer, &product_id);
+
+    // Platform fee, royalty and seller share add up to the price
+    let purchase = marketplace.get_purchase(&purchase_id).unwrap();
+    assert_eq!(purchase.platform_fee, 2_500_000);
+    assert_eq!(purchase.royalty_fee, 5_000_000);
+    assert_eq!(token_client.balance(&creator), 5_000_000);
+    assert_eq!(token_client.balance(&admin) - admin_balance, 2_500_000);
+    assert_eq!(token_client.balance(&seller), 92_500_000);
+    assert_eq!(token_client.balance(&buyer), 1_000_000_000 - price);
+}
+
+#[test]
+fn test_buy_product_fails_when_royalty_query_fails() {
+    let env = Env::default();
+    let admin = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    let (token_address, token_client, token_admin) = create_token_contract(&env, &admin);
+    let (nft_address, nft_client) = create_nft_contract(&env);
+    token_admin.mint(&buyer, &1_000_000_000);
+
+    let nft_id = 1u32;
+    nft_client.mint(&seller, &nft_id);
+
+    let (marketplace_address, marketplace) = create_impact_buyer_contract(&env);
+    env.mock_all_auths();
+    marketplace.initialize(&admin, &25u32);
+
+    let product_id = marketplace.list_product(
+        &seller,
+        &100_000_000i128,
+        &token_address,
+        &nft_address,
+        &nft_id,
+        &Map::new(&env),
+    );
+    nft_client.transfer(&seller, &marketplace_address, &nft_id);
+
+    // The sale does not go through without the royalty
+    nft_client.break_royalty();
+    assert!(marketplace.try_buy_product(&buyer, &product_id).is_err());
+    assert_eq!(nft_client.owner_of(&nft_id), marketplace_address);
+    assert_eq!(token_client.balance(&buyer), 1_000_000_000);
+}
+
+#[test]
+#[should_panic(expected = "ProductInactive")]
+fn test_cannot_buy_inactive_product() {
+    let env = Env::default();
+    let admin = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    let (token_address, _, token_admin) = create_token_contract(&env, &admin);
+    let (nft_address, nft_client) = create_nft_contract(&env);
+    token_admin.mint(&buyer, &1_000_000_000);
+
+    let nft_id = 1u32;
+    nft_client.mint(&seller, &nft_id);
+
+    let (marketplace_address, marketplace) = create_impact_buyer_contract(&env);
+    env.mock_all_auths();
+    marketplace.initialize(&admin, &25u32);
+
+    let product_id = marketplace.list_product(
+        &seller,
+        &100_000_000i128,
+        &token_address,
+        &nft_address,
+        &nft_id,
+        &Map::new(&env),
+    );
+    nft_client.transfer(&seller, &marketplace_address, &nft_id);
+
+    // The NFT expires while listed
+    nft_client.deactivate(&nft_id);
+    marketplace.buy_product(&buyer, &product_id);
+}
+
 #[test]
 fn test_admin_functions() {
     let env = Env::default();
@@ -366,7 +525,7 @@ fn test_unlist_product() {
     let (token_address, _, _) = create_token_contract(&env, &admin);
 
     // Create an NFT
-    let nft_id = String::from_str(&env, "NFT001");
+    let nft_id = 1u32;
     nft_client.mint(&seller, &nft_id);
 
     // Create marketplace
@@ -429,7 +588,7 @@ fn test_cannot_list_when_paused() {
     marketplace.initialize(&admin, &25u32);
 
     // Create an NFT
-    let nft_id = String::from_str(&env, "NFT001");
+    let nft_id = 1u32;
     nft_client.mint(&seller, &nft_id);
 
     // Pause the contract
@@ -442,7 +601,7 @@ fn test_cannot_list_when_paused() {
         &100_000_000i128,
         &token_address,
         &nft_address,
-        &String::from_str(&env, "NFT001"),
+        &1u32,
         &Map::new(&env),
     );
 }
@@ -465,7 +624,7 @@ fn test_contract_pausing_behavior() {
     marketplace.initialize(&admin, &25u32);
 
     // Create an NFT
-    let nft_id = String::from_str(&env, "NFT001");
+    let nft_id = 1u32;
     nft_client.mint(&seller, &nft_id);
 
     // Verify listing works when not paused
@@ -498,7 +657,7 @@ fn test_contract_pausing_behavior() {
 
     // Verify listing works again after unpausing
     env.mock_all_auths();
-    let nft_id2 = String::from_str(&env, "NFT002");
+    let nft_id2 = 2u32;
     nft_client.mint(&seller, &nft_id2);
 
     let product_id2 = marketplace.list_product(
@@ -511,3 +670,158 @@ fn test_contract_pausing_behavior() {
     );
     assert_eq!(product_id2, 2);
 }
+
+#[test]
+fn test_schema_version_and_migrate() {
+    let env = Env::default();
+    let admin = Address::generate(&env);
+
+    let (_, marketplace) = create_impact_buyer_contract(&env);
+
+    // Contracts initialized before versioning report schema 0
+    assert_eq!(marketplace.schema_version(), 0);
+
+    env.mock_all_auths();
+    marketplace.initialize(&admin, &25u32);
+    assert_eq!(marketplace.schema_version(), SCHEMA_VERSION);
+
+    // Migrating an up-to-date contract is a no-op
+    env.mock_all_auths();
+    assert_eq!(marketplace.migrate(&admin), SCHEMA_VERSION);
+    assert_eq!(marketplace.schema_version(), SCHEMA_VERSION);
+}
+
+#[test]
+#[should_panic(expected = "Unauthorized")]
+fn test_migrate_unauthorized() {
+    let env = Env::default();
+    let admin = Address::generate(&env);
+    let other = Address::generate(&env);
+
+    let (_, marketplace) = create_impact_buyer_contract(&env);
+
+    env.mock_all_auths();
+    marketplace.initialize(&admin, &25u32);
+
+    env.mock_all_auths();
+    marketplace.migrate(&other);
+}
+
+#[test]
+fn test_upgrade_keeps_state() {
+    let env = Env::default();
+    let admin = Address::generate(&env);
+    let seller = Address::generate(&env);
+
+    // Create NFT and token contracts
+    let (nft_address, nft_client) = create_nft_contract(&env);
+    let (token_address, _, _) = create_token_contract(&env, &admin);
+
+    let (contract_id, marketplace) = create_impact_buyer_contract(&env);
+
+    env.mock_all_auths();
+    marketplace.initialize(&admin, &25u32);
+
+    // List a product before upgrading
+    nft_client.mint(&seller, &1u32);
+    env.mock_all_auths();
+    let product_id = marketplace.list_product(
+        &seller,
+        &100_000_000i128,
+        &token_address,
+        &nft_address,
+        &1u32,
+        &Map::new(&env),
+    );
+
+    // Upgrade to the uploaded code
+    let new_wasm_hash: BytesN<32> = env.deployer().upload_contract_wasm(contract_buyer::WASM);
+    env.mock_all_auths();
+    marketplace.upgrade(&admin, &new_wasm_hash);
+
+    // Storage is untouched by the code swap
+    env.as_contract(&contract_id, || {
+        let storage = env.storage().instance();
+        let stored_admin: Address = storage.get(&types::DataKey::Admin).unwrap();
+        assert_eq!(stored_admin, admin);
+        let version: u32 = storage.get(&types::DataKey::SchemaVersion).unwrap();
+        assert_eq!(version, SCHEMA_VERSION);
+        assert!(storage.has(&types::DataKey::Product(product_id)));
+    });
+}
+
+#[test]
+fn test_two_step_admin_transfer() {
+    let env = Env::default();
+    let admin = Address::generate(&env);
+    let new_admin = Address::generate(&env);
+
+    let (_, marketplace) = create_impact_buyer_contract(&env);
+
+    env.mock_all_auths();
+    marketplace.initialize(&admin, &25u32);
+
+    // Proposing does not change the admin yet
+    env.mock_all_auths();
+    marketplace.propose_admin(&admin, &new_admin, &None);
+    assert_eq!(marketplace.get_admin(), admin);
+    assert_eq!(
+        marketplace.get_pending_admin().unwrap().new_admin,
+        new_admin
+    );
+
+    // The proposed admin can decline
+    env.mock_all_auths();
+    marketplace.cancel_admin_transfer(&new_admin);
+    assert!(marketplace.get_pending_admin().is_none());
+
+    // Propose again and accept
+    env.mock_all_auths();
+    marketplace.propose_admin(&admin, &new_admin, &None);
+    env.mock_all_auths();
+    assert_eq!(marketplace.accept_admin(), new_admin);
+    assert_eq!(env.auths()[0].0, new_admin);
+    assert_eq!(marketplace.get_admin(), new_admin);
+    assert!(marketplace.get_pending_admin().is_none());
+
+    // Only the new admin has admin rights
+    env.mock_all_auths();
+    assert!(marketplace.pause_contract(&new_admin));
+}
+
+#[test]
+#[should_panic(expected = "AdminTransferExpired")]
+fn test_accept_admin_expired() {
+    let env = Env::default();
+    let admin = Address::generate(&env);
+    let new_admin = Address::generate(&env);
+
+    let (_, marketplace) = create_impact_buyer_contract(&env);
+
+    env.mock_all_auths();
+    marketplace.initialize(&admin, &25u32);
+
+    env.mock_all_auths();
+    marketplace.propose_admin(&admin, &new_admin, &Some(env.ledger().sequence() + 10));
+
+    // Accept after the proposal has lapsed
+    env.ledger()
+        .set_sequence_number(env.ledger().sequence() + 11);
+    env.mock_all_auths();
+    marketplace.accept_admin();
+}
+
+#[test]
+#[should_panic(expected = "NoPendingAdmin")]
+fn test_accept_admin_without_proposal() {
+    let env = Env::default();
+    let admin = Address::generate(&env);
+
+    let (_, marketplace) = create_impact_buyer_contract(&env);
+
+    env.mock_all_auths();
+    marketplace.initialize(&admin, &25u32);
+
+    env.mock_all_auths();
+    marketplace.accept_admin();
+}
@@ -1,5 +1,8 @@
 use soroban_sdk::{contracterror, contracttype, Address, Map, String};
 
+// Define the storage layout version
+pub const SCHEMA_VERSION: u32 = 1;
+
 // Define the NFT impact product structure
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -15,7 +18,7 @@ pub struct ImpactProduct {
     // NFT contract address
     pub nft_contract: Address,
     // NFT token ID in the NFT contract
-    pub nft_token_id: String,
+    pub nft_token_id: u32,
     // Impact metrics or certifications
     pub impact_metrics: Map<String, String>,
     // Whether the NFT is still listed for sale
@@ -36,10 +39,12 @@ pub struct Purchase {
     pub total_price: i128,
     // Platform fee paid
     pub platform_fee: i128,
+    // Royalty paid to the NFT's royalty receiver
+    pub royalty_fee: i128,
     // NFT contract address
     pub nft_contract: Address,
     // NFT token ID that was transferred
-    pub nft_token_id: String,
+    pub nft_token_id: u32,
     // Timestamp of purchase
     pub timestamp: u64,
 }
@@ -54,6 +59,16 @@ pub struct ContractConfig {
     pub is_paused: bool,
 }
 
+// Define a pending admin hand-over
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AdminTransfer {
+    // Proposed admin, who must accept the transfer
+    pub new_admin: Address,
+    // Last ledger the proposal can be accepted in (no expiry if None)
+    pub live_until_ledger: Option<u32>,
+}
+
 // Define storage keys
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -68,6 +83,11 @@ pub enum DataKey {
     SellerProducts(Address), // List of products by seller
     ProductListed,           // Product listed event by ID
     ProductBought,           // Product bought event by ID
+    SchemaVersion,           // Storage layout version
+    PendingAdmin,            // Proposed admin awaiting acceptance
+    AdminProposed,           // Admin transfer proposed event
+    AdminAccepted,           // Admin transfer accepted event
+    AdminCancelled,          // Admin transfer cancelled event
 }
 
 // Define error codes
@@ -81,4 +101,9 @@ pub enum ErrorCode {
     InsufficientFunds = 4,
     CannotBuyOwnNFT = 5,
     ContractPaused = 6,
+    ProductInactive = 7,
+    UnsupportedSchemaVersion = 8,
+    NoPendingAdmin = 9,
+    AdminTransferExpired = 10,
+    InvalidExpiry = 11,
 }
@@ -0,0 +1,202 @@
+#![no_std]
+//! Role based access control shared by the Regen Bazaar NFT contracts.
+//!
+//! Roles are identified by a `Symbol` and can be held by any number of addresses.
+//! Each membership is its own persistent entry, so the contract instance does not grow
+//! with the number of holders. Every role is managed by an admin role (`ADMIN` unless
+//! changed with `set_role_admin`), whose holders may grant and revoke it.
+//!
+//! `ADMIN` itself changes hands in two steps: a holder proposes a successor with
+//! `propose_admin`, and the role only moves once that successor calls `accept_admin`.
+
+use soroban_sdk::{contracterror, contracttype, symbol_short, Address, Env, Symbol, Vec};
+
+pub const DEFAULT_ADMIN_ROLE: Symbol = symbol_short!("ADMIN");
+
+const DAY_IN_LEDGERS: u32 = 17280;
+/// Memberships with fewer ledgers than this left are extended when granted or checked.
+const MEMBERSHIP_TTL_THRESHOLD: u32 = 7 * DAY_IN_LEDGERS;
+const MEMBERSHIP_TTL_EXTEND_TO: u32 = 30 * DAY_IN_LEDGERS;
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum AccessControlError {
+    MissingRole = 1,
+    RoleNotHeld = 2,
+    LastAdmin = 3,
+    NoPendingAdmin = 4,
+    AdminTransferExpired = 5,
+    InvalidLiveUntilLedger = 6,
+}
+
+/// A proposed hand-over of `ADMIN` from `proposer` to `new_admin`, acceptable until
+/// `live_until_ledger` (inclusive) or indefinitely when unset.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AdminTransfer {
+    pub proposer: Address,
+    pub new_admin: Address,
+    pub live_until_ledger: Option<u32>
+}
+
+#[contracttype]
+pub enum AccessKey {
+    /// Holders of a role as one instance list, the layout before per-member entries.
+    RoleMembers(Symbol),
+    RoleMember(Symbol, Address),
+    RoleMemberCount(Symbol),
+    RoleAdmin(Symbol),
+    PendingAdmin
+}
+
+pub fn get_role_member_count(env: &Env, role: &Symbol) -> u32 {
+    env.storage().instance().get(&AccessKey::RoleMemberCount(role.clone())).unwrap_or(0)
+}
+
+pub fn has_role(env: &Env, role: &Symbol, account: &Address) -> bool {
+    let key: AccessKey = AccessKey::RoleMember(role.clone(), account.clone());
+    let held: bool = env.storage().persistent().get(&key).unwrap_or(false);
+    if held {
+        env.storage().persistent().extend_ttl(&key, MEMBERSHIP_TTL_THRESHOLD, MEMBERSHIP_TTL_EXTEND_TO);
+    }
+    held
+}
+
+pub fn get_role_admin(env: &Env, role: &Symbol) -> Symbol {
+    env.storage().instance().get(&AccessKey::RoleAdmin(role.clone())).unwrap_or(DEFAULT_ADMIN_ROLE)
+}
+
+/// Authenticates `account` and checks it holds `role`.
+pub fn require_role(env: &Env, role: &Symbol, account: &Address) -> Result<(), AccessControlError> {
+    account.require_auth();
+    if !has_role(env, role, account) {
+        return Err(AccessControlError::MissingRole);
+    }
+    Ok(())
+}
+
+pub fn grant_role(env: &Env, caller: &Address, role: &Symbol, account: &Address) -> Result<(), AccessControlError> {
+    require_role(env, &get_role_admin(env, role), caller)?;
+    _grant_role(env, role, account, caller);
+    Ok(())
+}
+
+pub fn revoke_role(env: &Env, caller: &Address, role: &Symbol, account: &Address) -> Result<(), AccessControlError> {
+    require_role(env, &get_role_admin(env, role), caller)?;
+    _revoke_role(env, role, account, caller)
+}
+
+pub fn renounce_role(env: &Env, account: &Address, role: &Symbol) -> Result<(), AccessControlError> {
+    account.require_auth();
+    _revoke_role(env, role, account, account)
+}
+
+pub fn set_role_admin(env: &Env, caller: &Address, role: &Symbol, admin_role: &Symbol) -> Result<(), AccessControlError> {
+    require_role(env, &DEFAULT_ADMIN_ROLE, caller)?;
+    _set_role_admin(env, role, admin_role);
+    Ok(())
+}
+
+pub fn get_pending_admin(env: &Env) -> Option<AdminTransfer> {
+    env.storage().instance().get(&AccessKey::PendingAdmin)
+}
+
+/// Proposes `new_admin` as the successor of `caller`, replacing any pending proposal.
+pub fn propose_admin(env: &Env, caller: &Address, new_admin: &Address, live_until_ledger: Option<u32>) -> Result<(), AccessControlError> {
+    require_role(env, &DEFAULT_ADMIN_ROLE, caller)?;
+    if live_until_ledger.is_some_and(|ledger| ledger < env.ledger().sequence()) {
+        return Err(AccessControlError::InvalidLiveUntilLedger);
+    }
+    let transfer: AdminTransfer = AdminTransfer { proposer: caller.clone(), new_admin: new_admin.clone(), live_until_ledger };
+    env.storage().instance().set(&AccessKey::PendingAdmin, &transfer);
+    env.events().publish((Symbol::new(env, "AdminProposed"), caller.clone()), (new_admin.clone(), live_until_ledger));
+    Ok(())
+}
+
+/// Completes the pending transfer: the proposed admin is granted `ADMIN` and the proposer
+/// loses it. The proposal lapses once expired or if the proposer no longer holds `ADMIN`.
+/// Returns the new admin.
+pub fn accept_admin(env: &Env) -> Result<Address, AccessControlError> {
+    let transfer: AdminTransfer = get_pending_admin(env).ok_or(AccessControlError::NoPendingAdmin)?;
+    transfer.new_admin.require_auth();
+    if transfer.live_until_ledger.is_some_and(|ledger| ledger < env.ledger().sequence()) {
+        return Err(AccessControlError::AdminTransferExpired);
+    }
+    if !has_role(env, &DEFAULT_ADMIN_ROLE, &transfer.proposer) {
+        return Err(AccessControlError::MissingRole);
+    }
+
+    env.storage().instance().remove(&AccessKey::PendingAdmin);
+    _grant_role(env, &DEFAULT_ADMIN_ROLE, &transfer.new_admin, &transfer.proposer);
+    if transfer.proposer != transfer.new_admin {
+        _revoke_role(env, &DEFAULT_ADMIN_ROLE, &transfer.proposer, &transfer.new_admin)?;
+    }
+    env.events().publish((Symbol::new(env, "AdminAccepted"), transfer.proposer), transfer.new_admin.clone());
+    Ok(transfer.new_admin)
+}
+
+/// Withdraws the pending transfer. Any `ADMIN` holder may cancel it, and the proposed admin
+/// may decline it.
+pub fn cancel_admin_transfer(env: &Env, caller: &Address) -> Result<(), AccessControlError> {
+    let transfer: AdminTransfer = get_pending_admin(env).ok_or(AccessControlError::NoPendingAdmin)?;
+    if *caller == transfer.new_admin {
+        caller.require_auth();
+    } else {
+        require_role(env, &DEFAULT_ADMIN_ROLE, caller)?;
+    }
+    env.storage().instance().remove(&AccessKey::PendingAdmin);
+    env.events().publish((Symbol::new(env, "AdminTransferCancelled"), transfer.proposer), (transfer.new_admin, caller.clone()));
+    Ok(())
+}
+
+/// Grants `role` without any authorization check. Meant for constructors.
+pub fn _grant_role(env: &Env, role: &Symbol, account: &Address, sender: &Address) {
+    if !_add_member(env, role, account) {
+        return;
+    }
+    env.events().publish((Symbol::new(env, "RoleGranted"), role.clone()), (account.clone(), sender.clone()));
+}
+
+fn _revoke_role(env: &Env, role: &Symbol, account: &Address, sender: &Address) -> Result<(), AccessControlError> {
+    if !has_role(env, role, account) {
+        return Err(AccessControlError::RoleNotHeld);
+    }
+    let count: u32 = get_role_member_count(env, role);
+    if *role == DEFAULT_ADMIN_ROLE && count == 1 {
+        return Err(AccessControlError::LastAdmin);
+    }
+    env.storage().persistent().remove(&AccessKey::RoleMember(role.clone(), account.clone()));
+    env.storage().instance().set(&AccessKey::RoleMemberCount(role.clone()), &(count - 1));
+    env.events().publish((Symbol::new(env, "RoleRevoked"), role.clone()), (account.clone(), sender.clone()));
+    Ok(())
+}
+
+/// Stores `account`'s membership of `role`. Returns false if it already held the role.
+fn _add_member(env: &Env, role: &Symbol, account: &Address) -> bool {
+    if has_role(env, role, account) {
+        return false;
+    }
+    let key: AccessKey = AccessKey::RoleMember(role.clone(), account.clone());
+    env.storage().persistent().set(&key, &true);
+    env.storage().persistent().extend_ttl(&key, MEMBERSHIP_TTL_THRESHOLD, MEMBERSHIP_TTL_EXTEND_TO);
+    env.storage().instance().set(&AccessKey::RoleMemberCount(role.clone()), &(get_role_member_count(env, role) + 1));
+    true
+}
+
+/// Moves the holders of `role` from the instance list of the earlier layout to their own
+/// entries. Roles already moved are left alone.
+pub fn migrate_role_members(env: &Env, role: &Symbol) {
+    let legacy_key: AccessKey = AccessKey::RoleMembers(role.clone());
+    let Some(members) = env.storage().instance().get::<AccessKey, Vec<Address>>(&legacy_key) else {
+        return;
+    };
+    for account in members.iter() {
+        _add_member(env, role, &account);
+    }
+    env.storage().instance().remove(&legacy_key);
+}
+
+pub fn _set_role_admin(env: &Env, role: &Symbol, admin_role: &Symbol) {
+    env.storage().instance().set(&AccessKey::RoleAdmin(role.clone()), admin_role);
+}
@@ -0,0 +1,217 @@
+use soroban_sdk::{contracttype, Env, Map, String, Vec};
+
+use crate::contract_nft::AttributeValue;
+use crate::{ContractError, DataKey};
+
+/// Highest UN Sustainable Development Goal number.
+const MAX_SDG: u32 = 17;
+
+/// Most attributes a category schema may declare.
+pub const MAX_SCHEMA_ATTRIBUTES: u32 = 20;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum CategoryStatus {
+    Active,
+    /// Closed to new mints. Tokens already minted in the category stay valid.
+    Deprecated
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CategoryMetadata {
+    /// Unit the base impact value is measured in, e.g. "tonnes CO2" or "hectares".
+    pub unit: String,
+    pub description: String,
+    /// Evidence a creator is expected to provide for products in the category.
+    pub required_evidence: Vec<String>,
+    /// UN Sustainable Development Goals (1-17) the category contributes to.
+    pub sdg_tags: Vec<u32>
+}
+
+/// An impact category products can be minted in.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ImpactCategory {
+    pub name: String,
+    /// Scales the base impact value, in basis points.
+    pub base_multiplier: u128,
+    pub status: CategoryStatus,
+    pub metadata: CategoryMetadata,
+    pub created_at: u64,
+    pub updated_at: u64
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AttributeType {
+    Text,
+    Integer,
+    Boolean
+}
+
+/// An attribute products in a category may carry. `min` and `max` bound integer values,
+/// or the byte length of text values; boolean attributes take no bounds.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AttributeSpec {
+    pub name: String,
+    pub attribute_type: AttributeType,
+    pub required: bool,
+    pub min: Option<i128>,
+    pub max: Option<i128>
+}
+
+/// Per-category parameters as stored under `DataKey::ImpactParameters` before schema version 2.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) struct LegacyImpactParams {
+    pub category: String,
+    pub base_multiplier: u128,
+    pub verified: bool
+}
+
+pub fn get_category(env: &Env, name: &String) -> Option<ImpactCategory> {
+    env.storage().persistent().get(&DataKey::Category(name.clone()))
+}
+
+pub fn set_category(env: &Env, category: &ImpactCategory) {
+    env.storage().persistent().set(&DataKey::Category(category.name.clone()), category);
+}
+
+/// Names of every category, active or deprecated, in creation order.
+pub fn get_category_names(env: &Env) -> Vec<String> {
+    env.storage().persistent().get(&DataKey::ImpactCategories).unwrap_or_else(|| Vec::new(env))
+}
+
+pub fn get_categories(env: &Env) -> Vec<ImpactCategory> {
+    let mut categories: Vec<ImpactCategory> = Vec::new(env);
+    for name in get_category_names(env).iter() {
+        categories.push_back(get_category(env, &name).expect("listed category should exist"));
+    }
+    categories
+}
+
+pub fn add_category(env: &Env, category: &ImpactCategory) {
+    set_category(env, category);
+    let mut names: Vec<String> = get_category_names(env);
+    names.push_back(category.name.clone());
+    env.storage().persistent().set(&DataKey::ImpactCategories, &names);
+}
+
+pub fn remove_category(env: &Env, name: &String) {
+    env.storage().persistent().remove(&DataKey::Category(name.clone()));
+    env.storage().persistent().remove(&DataKey::CategorySchema(name.clone()));
+    let mut names: Vec<String> = get_category_names(env);
+    if let Some(index) = names.first_index_of(name) {
+        names.remove(index);
+    }
+    env.storage().persistent().set(&DataKey::ImpactCategories, &names);
+}
+
+pub fn require_category(env: &Env, name: &String) -> Result<ImpactCategory, ContractError> {
+    get_category(env, name).ok_or(ContractError::UnsupportedCategory)
+}
+
+/// Returns the category `name` if new products may be minted in it.
+pub fn require_mintable(env: &Env, name: &String) -> Result<ImpactCategory, ContractError> {
+    let category: ImpactCategory = require_category(env, name)?;
+    if category.status == CategoryStatus::Deprecated {
+        return Err(ContractError::CategoryDeprecated);
+    }
+    Ok(category)
+}
+
+pub fn validate_metadata(metadata: &CategoryMetadata) -> Result<(), ContractError> {
+    if metadata.unit.is_empty() {
+        return Err(ContractError::InvalidCategoryMetadata);
+    }
+    if metadata.sdg_tags.iter().any(|goal| goal == 0 || goal > MAX_SDG) {
+        return Err(ContractError::InvalidCategoryMetadata);
+    }
+    Ok(())
+}
+
+/// Attributes products in the category may carry. Categories without a schema take none.
+pub fn get_schema(env: &Env, name: &String) -> Vec<AttributeSpec> {
+    env.storage().persistent().get(&DataKey::CategorySchema(name.clone())).unwrap_or_else(|| Vec::new(env))
+}
+
+pub fn set_schema(env: &Env, name: &String, schema: &Vec<AttributeSpec>) {
+    env.storage().persistent().set(&DataKey::CategorySchema(name.clone()), schema);
+}
+
+pub fn validate_schema(schema: &Vec<AttributeSpec>) -> Result<(), ContractError> {
+    if schema.len() > MAX_SCHEMA_ATTRIBUTES {
+        return Err(ContractError::InvalidAttributeSchema);
+    }
+    for (index, spec) in schema.iter().enumerate() {
+        if spec.name.is_empty() || schema.iter().skip(index + 1).any(|other| other.name == spec.name) {
+            return Err(ContractError::InvalidAttributeSchema);
+        }
+        let bounds_valid: bool = match spec.attribute_type {
+            AttributeType::Text => spec.min.is_none_or(|min| min >= 0) && spec.max.is_none_or(|max| max >= 0),
+            AttributeType::Integer => true,
+            AttributeType::Boolean => spec.min.is_none() && spec.max.is_none()
+        };
+        if !bounds_valid || spec.min.zip(spec.max).is_some_and(|(min, max)| min > max) {
+            return Err(ContractError::InvalidAttributeSchema);
+        }
+    }
+    Ok(())
+}
+
+/// Checks `attributes` against the schema of `category`: every required attribute is
+/// present, none is undeclared, and each value has the declared type and bounds.
+pub fn validate_attributes(env: &Env, category: &String, attributes: &Map<String, AttributeValue>) -> Result<(), ContractError> {
+    let schema: Vec<AttributeSpec> = get_schema(env, category);
+    for name in attributes.keys().iter() {
+        if !schema.iter().any(|spec| spec.name == name) {
+            return Err(ContractError::UnknownAttribute);
+        }
+    }
+    for spec in schema.iter() {
+        match attributes.get(spec.name.clone()) {
+            Some(value) => validate_value(&spec, &value)?,
+            None if spec.required => return Err(ContractError::MissingAttribute),
+            None => {}
+        }
+    }
+    Ok(())
+}
+
+fn validate_value(spec: &AttributeSpec, value: &AttributeValue) -> Result<(), ContractError> {
+    let measured: Option<i128> = match (&spec.attribute_type, value) {
+        (AttributeType::Text, AttributeValue::Text(text)) => Some(text.len() as i128),
+        (AttributeType::Integer, AttributeValue::Integer(number)) => Some(*number),
+        (AttributeType::Boolean, AttributeValue::Boolean(_)) => None,
+        _ => return Err(ContractError::InvalidAttributeValue)
+    };
+    if let Some(measured) = measured && (spec.min.is_some_and(|min| measured < min) || spec.max.is_some_and(|max| measured > max)) {
+        return Err(ContractError::InvalidAttributeValue);
+    }
+    Ok(())
+}
+
+/// Moves categories from the version 1 `ImpactParameters` map to one entry per category.
+/// Migrated categories are active and carry empty metadata until an admin fills it in.
+pub fn migrate_legacy_params(env: &Env) {
+    let Some(params) = env.storage().persistent().get::<DataKey, Map<String, LegacyImpactParams>>(&DataKey::ImpactParameters) else {
+        return;
+    };
+    let now: u64 = env.ledger().timestamp();
+    let mut names: Vec<String> = Vec::new(env);
+    for name in get_category_names(env).iter() {
+        if get_category(env, &name).is_none() {
+            // Names left without parameters were never usable, so they are dropped.
+            let Some(legacy) = params.get(name.clone()) else {
+                continue;
+            };
+            let metadata: CategoryMetadata = CategoryMetadata { unit: String::from_str(env, ""), description: String::from_str(env, ""), required_evidence: Vec::new(env), sdg_tags: Vec::new(env) };
+            set_category(env, &ImpactCategory { name: name.clone(), base_multiplier: legacy.base_multiplier, status: CategoryStatus::Active, metadata, created_at: now, updated_at: now });
+        }
+        names.push_back(name);
+    }
+    env.storage().persistent().set(&DataKey::ImpactCategories, &names);
+    env.storage().persistent().remove(&DataKey::ImpactParameters);
+}
@@ -0,0 +1,96 @@
+use soroban_sdk::{contracttype, Address, Env, String, Vec};
+
+use crate::{ContractError, DataKey};
+
+/// A project developer approved to mint through the factory.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CreatorProfile {
+    pub organisation_name: String,
+    pub country: String,
+    pub website: String,
+    /// Set by an admin once the organisation's identity has been verified. Cleared
+    /// whenever the creator edits the profile.
+    pub kyc_attested: bool,
+    pub suspended: bool,
+    /// Most tokens the creator may mint in total; `None` is unlimited.
+    pub mint_quota: Option<u32>,
+    pub minted: u32,
+    pub registered_at: u64
+}
+
+pub fn get_creator(env: &Env, creator: &Address) -> Option<CreatorProfile> {
+    env.storage().persistent().get(&DataKey::Creator(creator.clone()))
+}
+
+pub fn set_creator(env: &Env, creator: &Address, profile: &CreatorProfile) {
+    env.storage().persistent().set(&DataKey::Creator(creator.clone()), profile);
+}
+
+/// Registered creators in registration order.
+pub fn get_creators(env: &Env) -> Vec<Address> {
+    env.storage().persistent().get(&DataKey::Creators).unwrap_or_else(|| Vec::new(env))
+}
+
+pub fn add_creator(env: &Env, creator: &Address, profile: &CreatorProfile) {
+    set_creator(env, creator, profile);
+    let mut creators: Vec<Address> = get_creators(env);
+    creators.push_back(creator.clone());
+    env.storage().persistent().set(&DataKey::Creators, &creators);
+}
+
+/// Returns the profile of a creator registered with `register_creator`.
+pub fn require_creator(env: &Env, creator: &Address) -> Result<CreatorProfile, ContractError> {
+    get_creator(env, creator).filter(is_registered).ok_or(ContractError::CreatorNotRegistered)
+}
+
+/// Whether `profile` was filled in by `register_creator`, as opposed to kept for a role
+/// holder that was never registered.
+pub fn is_registered(profile: &CreatorProfile) -> bool {
+    !profile.organisation_name.is_empty()
+}
+
+/// Returns the profile of `creator`, or an empty unregistered one it would start from. Role
+/// holders granted without registering get theirs stored once they mint, are suspended or
+/// are given a quota. Unregistered profiles are not listed by `get_creators`.
+pub fn get_or_default(env: &Env, creator: &Address) -> CreatorProfile {
+    get_creator(env, creator).unwrap_or_else(|| {
+        let empty: String = String::from_str(env, "");
+        CreatorProfile { organisation_name: empty.clone(), country: empty.clone(), website: empty, kyc_attested: false, suspended: false, mint_quota: None, minted: 0, registered_at: env.ledger().timestamp() }
+    })
+}
+
+pub fn validate_profile(organisation_name: &String, country: &String) -> Result<(), ContractError> {
+    if organisation_name.is_empty() || country.is_empty() {
+        return Err(ContractError::InvalidCreatorProfile);
+    }
+    Ok(())
+}
+
+/// Checks `creator` may mint `count` more tokens and counts them against its quota.
+pub fn record_mints(env: &Env, creator: &Address, count: u32) -> Result<(), ContractError> {
+    let mut profile: CreatorProfile = get_or_default(env, creator);
+    require_active(&profile)?;
+    let minted: u32 = profile.minted.saturating_add(count);
+    if profile.mint_quota.is_some_and(|quota| minted > quota) {
+        return Err(ContractError::MintQuotaExceeded);
+    }
+    profile.minted = minted;
+    set_creator(env, creator, &profile);
+    Ok(())
+}
+
+/// Fails for suspended creators. Creators without a profile have never been suspended.
+pub fn require_not_suspended(env: &Env, creator: &Address) -> Result<(), ContractError> {
+    match get_creator(env, creator) {
+        Some(profile) => require_active(&profile),
+        None => Ok(())
+    }
+}
+
+fn require_active(profile: &CreatorProfile) -> Result<(), ContractError> {
+    if profile.suspended {
+        return Err(ContractError::CreatorSuspended);
+    }
+    Ok(())
+}
@@ -0,0 +1,74 @@
+use access_control::AccessControlError;
+use soroban_sdk::contracterror;
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum ContractError {
+    // Authorization
+    MissingRole = 1,
+    RoleNotHeld = 2,
+    LastAdmin = 3,
+
+    // Impact products
+    InvalidImpactValue = 4,
+    InvalidPrice = 5,
+    InvalidPeriod = 11,
+    InvalidBatchSize = 12,
+    BatchLengthMismatch = 31,
+
+    // Categories
+    EmptyCategory = 6,
+    UnsupportedCategory = 7,
+    CategoryAlreadyExists = 8,
+    InvalidMultiplier = 9,
+    CategoryDeprecated = 24,
+    InvalidCategoryStatus = 25,
+    InvalidCategoryMetadata = 26,
+
+    // General
+    ContractPaused = 10,
+    UnsupportedSchemaVersion = 13,
+
+    // Admin transfer
+    NoPendingAdmin = 14,
+    AdminTransferExpired = 15,
+    InvalidLiveUntilLedger = 16,
+
+    // Collections
+    NftWasmHashNotSet = 17,
+    InvalidCollectionMetadata = 18,
+
+    // Creators
+    CreatorAlreadyRegistered = 19,
+    CreatorNotRegistered = 20,
+    CreatorSuspended = 21,
+    MintQuotaExceeded = 22,
+    InvalidCreatorProfile = 23,
+
+    // Attributes
+    InvalidAttributeSchema = 27,
+    MissingAttribute = 28,
+    UnknownAttribute = 29,
+    InvalidAttributeValue = 30,
+
+    // Projects
+    ProjectNotFound = 32,
+    InvalidProject = 33,
+    InvalidProjectStatus = 34,
+    ProjectNotActive = 35,
+    NotProjectDeveloper = 36,
+}
+
+impl From<AccessControlError> for ContractError {
+    fn from(error: AccessControlError) -> Self {
+        match error {
+            AccessControlError::MissingRole => ContractError::MissingRole,
+            AccessControlError::RoleNotHeld => ContractError::RoleNotHeld,
+            AccessControlError::LastAdmin => ContractError::LastAdmin,
+            AccessControlError::NoPendingAdmin => ContractError::NoPendingAdmin,
+            AccessControlError::AdminTransferExpired => ContractError::AdminTransferExpired,
+            AccessControlError::InvalidLiveUntilLedger => ContractError::InvalidLiveUntilLedger,
+        }
+    }
+}
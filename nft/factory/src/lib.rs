@@ -1,9 +1,21 @@
 #![no_std]
+mod categories;
 mod contract;
+mod creators;
+mod error;
+mod projects;
+mod registry;
 
-use soroban_sdk::{contract, contractimpl, contracttype, Address, Env, Map, String, Vec};
+use access_control::{AdminTransfer, DEFAULT_ADMIN_ROLE};
+use soroban_sdk::{contract, contractimpl, contracttype, symbol_short, vec, Address, BytesN, Env, Map, String, Symbol, Vec};
 
-use crate::contract_nft::ImpactData;
+use crate::contract_nft::{AttributeValue, ImpactData, ProjectTotals};
+use crate::projects::ProjectIssuance;
+pub use categories::{AttributeSpec, AttributeType, CategoryMetadata, CategoryStatus, ImpactCategory};
+pub use creators::CreatorProfile;
+pub use error::ContractError;
+pub use projects::{Project, ProjectImpact, ProjectStatus};
+pub use registry::Collection;
 
 mod contract_nft {
     soroban_sdk::contractimport!(
@@ -27,188 +39,636 @@ pub struct ImpactProductData {
     pub metadata_uri: String
 }
 
-#[contracttype]
-#[derive(Clone, Debug, Eq, PartialEq)]
-struct ImpactParams {
-    pub category: String,
-    pub base_multiplier: u128,
-    pub verified: bool
-}
-
 #[contracttype]
 pub enum DataKey {
-    ADMIN,
-    CREATOR,
-    VERIFIER,
     IsPaused,
     ImpactProductNft,
+    /// Schema version 1 category parameters, replaced by `Category` entries.
     ImpactParameters,
-    ImpactCategories
+    ImpactCategories,
+    Category(String),
+    CategorySchema(String),
+    SchemaVersion,
+    NftWasmHash,
+    Collections,
+    Collection(Address),
+    OwnerCollections(Address),
+    Creator(Address),
+    Creators,
+    ProjectCount,
+    Project(u32),
+    DeveloperProjects(Address),
+    ProjectIssuance(u32),
+    ProjectToken(u32, u32),
+    TokenProject(u32)
 }
 
+pub const CREATOR_ROLE: Symbol = symbol_short!("CREATOR");
+pub const VERIFIER_ROLE: Symbol = symbol_short!("VERIFIER");
+
+pub const SCHEMA_VERSION: u32 = 3;
+
 #[contractimpl]
 impl ImpactProductFactory {
     pub fn __constructor(env: Env, admin: Address, nft_contract: Address) {
         env.storage().instance().set(&DataKey::IsPaused, &false);
-        env.storage().instance().set(&DataKey::ADMIN, &admin);
-        env.storage().instance().set(&DataKey::CREATOR, &admin);
-        env.storage().instance().set(&DataKey::VERIFIER, &admin);
+        access_control::_grant_role(&env, &DEFAULT_ADMIN_ROLE, &admin, &admin);
+        access_control::_grant_role(&env, &CREATOR_ROLE, &admin, &admin);
+        access_control::_grant_role(&env, &VERIFIER_ROLE, &admin, &admin);
 
         env.storage().instance().set(&DataKey::ImpactProductNft, &nft_contract);
+        env.storage().instance().set(&DataKey::SchemaVersion, &SCHEMA_VERSION);
 
-        let impact_params: Map<String, ImpactParams> = Map::new(&env);
-        env.storage().persistent().set(&DataKey::ImpactParameters, &impact_params);
-
-        let impact_categories: Vec<String> = Vec::new(&env);
-        env.storage().persistent().set(&DataKey::ImpactCategories, &impact_categories);
+        let default_categories: [(&str, u128, &str, &[u32]); 7] = [
+            ("Community gardens", 1000, "square metres", &[2, 11]),
+            ("Tree preservation", 2500, "trees", &[13, 15]),
+            ("Eco tourism", 1500, "visitors", &[8, 12]),
+            ("Educational programs", 2000, "participants", &[4]),
+            ("Wildlife Conservation", 3000, "hectares", &[14, 15]),
+            ("CO2 Emissions Reduction", 3500, "tonnes CO2", &[7, 13]),
+            ("Waste Management", 1200, "tonnes", &[11, 12])
+        ];
+        for (category, base_multiplier, unit, sdg_tags) in default_categories.iter() {
+            let metadata: CategoryMetadata = CategoryMetadata { unit: String::from_str(&env, unit), description: String::from_str(&env, ""), required_evidence: Vec::new(&env), sdg_tags: Vec::from_slice(&env, sdg_tags) };
+            Self::_add_impact_category(&env, String::from_str(&env, category), *base_multiplier, metadata).expect("default categories are valid");
+        }
+    }
 
-        Self::_add_impact_category(env.clone(), String::from_str(&env, "Community gardens"), 1000);
-        Self::_add_impact_category(env.clone(), String::from_str(&env, "Tree preservation"), 2500);
-        Self::_add_impact_category(env.clone(), String::from_str(&env, "Eco tourism"), 1500);
-        Self::_add_impact_category(env.clone(), String::from_str(&env, "Educational programs"), 2000);
-        Self::_add_impact_category(env.clone(), String::from_str(&env, "Wildlife Conservation"), 3000);
-        Self::_add_impact_category(env.clone(), String::from_str(&env, "CO2 Emissions Reduction"), 3500);
-        Self::_add_impact_category(env.clone(), String::from_str(&env, "Waste Management"), 1200);
+    pub fn create_impact_product(env: Env, creator: Address, impact_product_data: ImpactProductData) -> Result<u32, ContractError> {
+        Self::create_with_attributes(env.clone(), creator, impact_product_data, Map::new(&env))
     }
 
-    pub fn create_impact_product(env: Env, impact_product_data: ImpactProductData) -> u128 {
-        let creator: Address = env.storage().instance().get(&DataKey::CREATOR).expect("CREATOR not found");
-        creator.require_auth();
+    /// Mints like `create_impact_product`, recording `attributes` on the token once they
+    /// pass the category's attribute schema.
+    pub fn create_with_attributes(env: Env, creator: Address, impact_product_data: ImpactProductData, attributes: Map<String, AttributeValue>) -> Result<u32, ContractError> {
+        Self::_create(&env, &creator, None, impact_product_data, &attributes)
+    }
 
-        let is_paused: bool = env.storage().instance().get(&DataKey::IsPaused).expect("contains value");
-        if is_paused {
-            panic!("contract paused")
-        }
-        if impact_product_data.base_impact_value == 0 {
-            panic!("Impact value must be positive")
+    /// Mints one token per entry of `products` to `creator`, applying each entry's category
+    /// multiplier. The NFT contract mints the whole batch or none of it and caps the batch
+    /// size. Returns the new token IDs.
+    pub fn batch_create_impact_products(env: Env, creator: Address, products: Vec<ImpactProductData>) -> Result<Vec<u32>, ContractError> {
+        let mut attributes: Vec<Map<String, AttributeValue>> = Vec::new(&env);
+        for _ in products.iter() {
+            attributes.push_back(Map::new(&env));
         }
-        if impact_product_data.listing_price == 0 {
-            panic!("Price must be positive")
+        Self::batch_create_with_attributes(env, creator, products, attributes)
+    }
+
+    /// Batch form of `create_with_attributes`; `attributes` matches `products` entry for entry.
+    pub fn batch_create_with_attributes(env: Env, creator: Address, products: Vec<ImpactProductData>, attributes: Vec<Map<String, AttributeValue>>) -> Result<Vec<u32>, ContractError> {
+        Self::_batch_create(&env, &creator, None, products, &attributes)
+    }
+
+    /// Registers a project owned by `developer`, a holder of the `CREATOR` role. Projects
+    /// start as drafts and issue tokens once an admin activates them. Returns the project ID.
+    pub fn register_project(env: Env, developer: Address, name: String, location: String, methodology: String, documentation_uri: String) -> Result<u32, ContractError> {
+        access_control::require_role(&env, &CREATOR_ROLE, &developer)?;
+        Self::_require_not_paused(&env)?;
+        creators::require_not_suspended(&env, &developer)?;
+        projects::validate_project(&name, &location, &methodology)?;
+
+        let now: u64 = env.ledger().timestamp();
+        let project: Project = Project { id: 0, name, developer: developer.clone(), location, methodology, documentation_uri, status: ProjectStatus::Draft, registered_at: now, updated_at: now };
+        let project_id: u32 = projects::add_project(&env, project);
+        env.events().publish((symbol_short!("project"), symbol_short!("register")), (project_id, developer));
+        Ok(project_id)
+    }
+
+    /// Moves a project through draft, active, suspended and completed. Admins make every
+    /// transition; a developer may also complete its own active project.
+    pub fn set_project_status(env: Env, caller: Address, project_id: u32, status: ProjectStatus) -> Result<(), ContractError> {
+        Self::_require_not_paused(&env)?;
+        let mut project: Project = projects::require_project(&env, project_id)?;
+        if caller == project.developer && project.status == ProjectStatus::Active && status == ProjectStatus::Completed {
+            caller.require_auth();
+        } else {
+            access_control::require_role(&env, &DEFAULT_ADMIN_ROLE, &caller)?;
         }
-        if String::len(&impact_product_data.category) == 0 {
-            panic!("Category cannot be empty")
+        if !projects::is_valid_transition(&project.status, &status) {
+            return Err(ContractError::InvalidProjectStatus);
         }
-        if !Self::is_category_supported(env.clone(), impact_product_data.category.clone()) {
-            panic!("Unsupported impact category")
+
+        project.status = status.clone();
+        project.updated_at = env.ledger().timestamp();
+        projects::set_project(&env, &project);
+        env.events().publish((symbol_short!("project"), symbol_short!("status")), (project_id, status));
+        Ok(())
+    }
+
+    /// Mints like `create_with_attributes` and links the token to `project_id`, which must
+    /// be active and belong to `creator`.
+    pub fn issue_project_product(env: Env, creator: Address, project_id: u32, impact_product_data: ImpactProductData, attributes: Map<String, AttributeValue>) -> Result<u32, ContractError> {
+        let token_id: u32 = Self::_create(&env, &creator, Some(project_id), impact_product_data, &attributes)?;
+        env.events().publish((symbol_short!("project"), symbol_short!("issue")), (project_id, vec![&env, token_id]));
+        Ok(token_id)
+    }
+
+    pub fn batch_issue_project_products(env: Env, creator: Address, project_id: u32, products: Vec<ImpactProductData>, attributes: Vec<Map<String, AttributeValue>>) -> Result<Vec<u32>, ContractError> {
+        let token_ids: Vec<u32> = Self::_batch_create(&env, &creator, Some(project_id), products, &attributes)?;
+        env.events().publish((symbol_short!("project"), symbol_short!("issue")), (project_id, token_ids.clone()));
+        Ok(token_ids)
+    }
+
+    pub fn get_project(env: Env, project_id: u32) -> Option<Project> {
+        projects::get_project(&env, project_id)
+    }
+
+    pub fn get_projects_by_developer(env: Env, developer: Address) -> Vec<Project> {
+        projects::get_projects_by_developer(&env, &developer)
+    }
+
+    /// Impact issued under the project, and the verified and retired totals the NFT contract
+    /// keeps for its tokens. Retired impact is not also counted as verified.
+    pub fn get_project_impact(env: Env, project_id: u32) -> Result<ProjectImpact, ContractError> {
+        projects::require_project(&env, project_id)?;
+        let issuance: ProjectIssuance = projects::get_issuance(&env, project_id);
+        let totals: ProjectTotals = Self::_nft_client(&env).get_project_totals(&project_id);
+        Ok(ProjectImpact { token_count: issuance.token_count, issued: issuance.issued, verified: totals.verified, retired: totals.retired })
+    }
+
+    /// Up to `limit` token IDs issued under the project, from the `start`th onwards.
+    pub fn get_project_tokens(env: Env, project_id: u32, start: u32, limit: u32) -> Vec<u32> {
+        projects::get_tokens(&env, project_id, start, limit)
+    }
+
+    pub fn get_token_project(env: Env, token_id: u32) -> Option<u32> {
+        projects::get_token_project(&env, token_id)
+    }
+
+    /// Deploys a new ImpactProductNFT collection branded with `name` and `symbol`, whose roles
+    /// are all held by `owner`, and records it in the registry. Deployments use the wasm
+    /// hash set with `set_nft_wasm_hash`; `salt` must be unique per deployment.
+    pub fn deploy_collection(env: Env, owner: Address, name: String, symbol: String, base_uri: String, salt: BytesN<32>) -> Result<Address, ContractError> {
+        access_control::require_role(&env, &CREATOR_ROLE, &owner)?;
+        Self::_require_not_paused(&env)?;
+        creators::require_not_suspended(&env, &owner)?;
+        if name.is_empty() || symbol.is_empty() {
+            return Err(ContractError::InvalidCollectionMetadata);
         }
-        
-        let final_impact_value: u128 = Self::calculate_impact_value(env.clone(), impact_product_data.category.clone(), impact_product_data.base_impact_value);
-        
-        let contract: Address = env.storage().instance().get(&DataKey::ImpactProductNft).expect("Should contain nft address");
-        let client: contract_nft::Client<'_> = contract_nft::Client::new(&env, &contract);
+        let wasm_hash: BytesN<32> = registry::get_nft_wasm_hash(&env).ok_or(ContractError::NftWasmHashNotSet)?;
 
-        let impact_data: ImpactData = ImpactData { beneficiaries: impact_product_data.beneficiaries, category: impact_product_data.category, end_date: impact_product_data.end_date, impact_value: final_impact_value, location: impact_product_data.location, metadata_uri: impact_product_data.metadata_uri, start_date: impact_product_data.start_date, verified: false };
-        let token_id: u128 = client.create_impact_product(&creator, &impact_data, &impact_product_data.listing_price);
-        
-        token_id
+        let address: Address = env.deployer().with_current_contract(salt).deploy_v2(wasm_hash.clone(), (owner.clone(), base_uri, None::<u32>, name.clone(), symbol.clone()));
+        registry::add_collection(&env, &Collection { address: address.clone(), owner: owner.clone(), name, symbol, wasm_hash, deployed_at: env.ledger().timestamp() });
+        env.events().publish((symbol_short!("deploy"), owner), address.clone());
+        Ok(address)
     }
 
-    pub fn verify_impact_product(env: Env, token_id: u128, validators: Vec<Address> ) -> bool {
-        let contract: Address = env.storage().instance().get(&DataKey::ImpactProductNft).expect("Should contain nft address");
-        let client: contract_nft::Client<'_> = contract_nft::Client::new(&env, &contract);
-        client.verify_token(&token_id, &validators)
+    /// Sets the uploaded ImpactProductNFT wasm that future `deploy_collection` calls deploy.
+    /// Collections already deployed keep their code.
+    pub fn set_nft_wasm_hash(env: Env, admin: Address, wasm_hash: BytesN<32>) -> Result<(), ContractError> {
+        access_control::require_role(&env, &DEFAULT_ADMIN_ROLE, &admin)?;
+        registry::set_nft_wasm_hash(&env, &wasm_hash);
+        env.events().publish((symbol_short!("wasm_hash"),), wasm_hash);
+        Ok(())
+    }
+
+    pub fn nft_wasm_hash(env: Env) -> Option<BytesN<32>> {
+        registry::get_nft_wasm_hash(&env)
+    }
+
+    pub fn get_collection(env: Env, address: Address) -> Option<Collection> {
+        registry::get_collection(&env, &address)
+    }
+
+    /// Every collection deployed by the factory, oldest first.
+    pub fn get_collections(env: Env) -> Vec<Collection> {
+        registry::get_collections(&env)
+    }
+
+    pub fn get_collections_by_owner(env: Env, owner: Address) -> Vec<Collection> {
+        registry::get_collections_by_owner(&env, &owner)
+    }
+
+    /// Submits `validator`'s attestation for `token_id` to the NFT contract's validator quorum.
+    pub fn verify_impact_product(env: Env, validator: Address, token_id: u32, approved: bool, evidence_uri: String) -> Result<bool, ContractError> {
+        validator.require_auth();
+        let client: contract_nft::Client<'_> = Self::_nft_client(&env);
+        Ok(client.verify_token(&validator, &token_id, &approved, &evidence_uri))
     }
 
+    /// Names of the categories open for minting.
     pub fn get_supported_categories(env: Env) -> Vec<String> {
-        let impact_categories: Vec<String> = env.storage().persistent().get(&DataKey::ImpactCategories).expect("Should contain Impact Categories");
-        impact_categories
+        let mut supported: Vec<String> = Vec::new(&env);
+        for category in categories::get_categories(&env).iter() {
+            if category.status == CategoryStatus::Active {
+                supported.push_back(category.name);
+            }
+        }
+        supported
     }
 
-    fn is_category_supported(env: Env, category: String) -> bool {
-        let impact_parameters: Map<String, ImpactParams> = env.storage().persistent().get(&DataKey::ImpactParameters).expect("Should contain Impact Categories");
-        let impact_category: ImpactParams = impact_parameters.get(category).unwrap_or_else( || ImpactParams { category: String::from_str(&env, ""), base_multiplier: 0, verified: false});
-        impact_category.base_multiplier > 0
+    pub fn get_category(env: Env, category: String) -> Option<ImpactCategory> {
+        categories::get_category(&env, &category)
     }
 
-    pub fn add_impact_category(env: Env, category: String, base_multiplier: u128) {
-        let admin: Address = env.storage().instance().get(&DataKey::ADMIN).expect("contains ADMIN");
-        admin.require_auth();
-        Self::_add_impact_category(env, category, base_multiplier);
+    /// Every category, including deprecated ones, in creation order.
+    pub fn get_categories(env: Env) -> Vec<ImpactCategory> {
+        categories::get_categories(&env)
     }
 
-    fn _add_impact_category(env: Env, category: String, base_multiplier: u128) {
+    pub fn add_impact_category(env: Env, admin: Address, category: String, base_multiplier: u128, metadata: CategoryMetadata) -> Result<(), ContractError> {
+        access_control::require_role(&env, &DEFAULT_ADMIN_ROLE, &admin)?;
+        Self::_add_impact_category(&env, category.clone(), base_multiplier, metadata)?;
+        env.events().publish((symbol_short!("category"), symbol_short!("created")), category);
+        Ok(())
+    }
+
+    fn _add_impact_category(env: &Env, category: String, base_multiplier: u128, metadata: CategoryMetadata) -> Result<(), ContractError> {
         if String::len(&category) == 0 {
-            panic!("Category cannot be empty")
+            return Err(ContractError::EmptyCategory);
         }
         if base_multiplier == 0 {
-            panic!("Multiplier must be positive")
+            return Err(ContractError::InvalidMultiplier);
+        }
+        if categories::get_category(env, &category).is_some() {
+            return Err(ContractError::CategoryAlreadyExists);
         }
-        if Self::is_category_supported(env.clone(), category.clone()) {
-            panic!("Category already exists")
+        categories::validate_metadata(&metadata)?;
+
+        let now: u64 = env.ledger().timestamp();
+        categories::add_category(env, &ImpactCategory { name: category, base_multiplier, status: CategoryStatus::Active, metadata, created_at: now, updated_at: now });
+        Ok(())
+    }
+
+    /// Deletes `category` so its name can be registered again. Tokens already minted in it
+    /// keep their data.
+    pub fn remove_impact_category(env: Env, admin: Address, category: String) -> Result<(), ContractError> {
+        access_control::require_role(&env, &DEFAULT_ADMIN_ROLE, &admin)?;
+        categories::require_category(&env, &category)?;
+        categories::remove_category(&env, &category);
+        env.events().publish((symbol_short!("category"), symbol_short!("deleted")), category);
+        Ok(())
+    }
+
+    /// Closes `category` to new mints. Tokens already minted in it stay valid.
+    pub fn deprecate_impact_category(env: Env, admin: Address, category: String) -> Result<(), ContractError> {
+        Self::_set_category_status(&env, &admin, &category, CategoryStatus::Deprecated)
+    }
+
+    pub fn reactivate_impact_category(env: Env, admin: Address, category: String) -> Result<(), ContractError> {
+        Self::_set_category_status(&env, &admin, &category, CategoryStatus::Active)
+    }
+
+    pub fn update_impact_params(env: Env, admin: Address, category: String, base_multiplier: u128) -> Result<(), ContractError> {
+        access_control::require_role(&env, &DEFAULT_ADMIN_ROLE, &admin)?;
+        let mut impact_category: ImpactCategory = categories::require_category(&env, &category)?;
+        if base_multiplier == 0 {
+            return Err(ContractError::InvalidMultiplier);
         }
-        
-        let mut impact_categories: Vec<String> = env.storage().persistent().get(&DataKey::ImpactCategories).expect("Should contain Impact Categories");
-        impact_categories.push_back(category.clone());
-        env.storage().persistent().set(&DataKey::ImpactCategories, &impact_categories);
+        impact_category.base_multiplier = base_multiplier;
+        impact_category.updated_at = env.ledger().timestamp();
+        categories::set_category(&env, &impact_category);
+        env.events().publish((symbol_short!("category"), symbol_short!("params")), (category, base_multiplier));
+        Ok(())
+    }
+
+    pub fn update_category_metadata(env: Env, admin: Address, category: String, metadata: CategoryMetadata) -> Result<(), ContractError> {
+        access_control::require_role(&env, &DEFAULT_ADMIN_ROLE, &admin)?;
+        let mut impact_category: ImpactCategory = categories::require_category(&env, &category)?;
+        categories::validate_metadata(&metadata)?;
+        impact_category.metadata = metadata;
+        impact_category.updated_at = env.ledger().timestamp();
+        categories::set_category(&env, &impact_category);
+        env.events().publish((symbol_short!("category"), symbol_short!("metadata")), category);
+        Ok(())
+    }
+
+    /// Replaces the attributes products in `category` must or may carry. Tokens already
+    /// minted keep the attributes they were minted with.
+    pub fn set_category_schema(env: Env, admin: Address, category: String, schema: Vec<AttributeSpec>) -> Result<(), ContractError> {
+        access_control::require_role(&env, &DEFAULT_ADMIN_ROLE, &admin)?;
+        categories::require_category(&env, &category)?;
+        categories::validate_schema(&schema)?;
+        categories::set_schema(&env, &category, &schema);
+        env.events().publish((symbol_short!("category"), symbol_short!("schema")), category);
+        Ok(())
+    }
 
-        Self::calculate_and_store_impact_params(env, category, base_multiplier, false);
+    pub fn get_category_schema(env: Env, category: String) -> Result<Vec<AttributeSpec>, ContractError> {
+        categories::require_category(&env, &category)?;
+        Ok(categories::get_schema(&env, &category))
     }
 
-    pub fn remove_impact_category(env: Env, category: String) {
-        let admin: Address = env.storage().instance().get(&DataKey::ADMIN).expect("contains ADMIN");
-        admin.require_auth();
-        if !Self::is_category_supported(env.clone(), category.clone()) {
-            panic!("Category already exists")
+    fn _set_category_status(env: &Env, admin: &Address, category: &String, status: CategoryStatus) -> Result<(), ContractError> {
+        access_control::require_role(env, &DEFAULT_ADMIN_ROLE, admin)?;
+        let mut impact_category: ImpactCategory = categories::require_category(env, category)?;
+        if impact_category.status == status {
+            return Err(ContractError::InvalidCategoryStatus);
         }
+        let action: Symbol = if status == CategoryStatus::Deprecated { symbol_short!("deprecate") } else { symbol_short!("activate") };
+        impact_category.status = status;
+        impact_category.updated_at = env.ledger().timestamp();
+        categories::set_category(env, &impact_category);
+        env.events().publish((symbol_short!("category"), action), category.clone());
+        Ok(())
+    }
 
-        let mut impact_categories: Vec<String> = env.storage().persistent().get(&DataKey::ImpactCategories).expect("Should contain Impact Categories");
+    fn calculate_impact_value(env: &Env, category: &String, base_value: u128) -> Result<u128, ContractError> {
+        let params: ImpactCategory = categories::require_mintable(env, category)?;
+        let calculated_value: u128 = base_value * params.base_multiplier / 10000;
+        Ok(calculated_value)
+    }
 
-        let mut item: u32 = 0;
-        for value in impact_categories.iter() {
-            if value == category {
-                impact_categories.remove(item);
-                item -= 1;
+    /// Validates `impact_product_data` and its `attributes` and converts it to the NFT's
+    /// impact data, scaling the base impact value by its category multiplier.
+    fn _build_impact_data(env: &Env, impact_product_data: ImpactProductData, attributes: &Map<String, AttributeValue>) -> Result<ImpactData, ContractError> {
+        if impact_product_data.base_impact_value == 0 {
+            return Err(ContractError::InvalidImpactValue);
+        }
+        if impact_product_data.listing_price == 0 {
+            return Err(ContractError::InvalidPrice);
+        }
+        if String::len(&impact_product_data.category) == 0 {
+            return Err(ContractError::EmptyCategory);
+        }
+        if impact_product_data.end_date <= impact_product_data.start_date {
+            return Err(ContractError::InvalidPeriod);
+        }
+
+        let final_impact_value: u128 = Self::calculate_impact_value(env, &impact_product_data.category, impact_product_data.base_impact_value)?;
+        categories::validate_attributes(env, &impact_product_data.category, attributes)?;
+        Ok(ImpactData { beneficiaries: impact_product_data.beneficiaries, category: impact_product_data.category, end_date: impact_product_data.end_date, impact_value: final_impact_value, location: impact_product_data.location, metadata_uri: impact_product_data.metadata_uri, start_date: impact_product_data.start_date, verified: false })
+    }
+
+    /// Mints one product to `creator`, linking it to `project_id` when set.
+    fn _create(env: &Env, creator: &Address, project_id: Option<u32>, impact_product_data: ImpactProductData, attributes: &Map<String, AttributeValue>) -> Result<u32, ContractError> {
+        access_control::require_role(env, &CREATOR_ROLE, creator)?;
+        Self::_require_not_paused(env)?;
+        if let Some(project_id) = project_id {
+            Self::_require_issuing_project(env, creator, project_id)?;
+        }
+
+        let listing_price: u128 = impact_product_data.listing_price;
+        let impact_data: ImpactData = Self::_build_impact_data(env, impact_product_data, attributes)?;
+        creators::record_mints(env, creator, 1)?;
+
+        let client: contract_nft::Client<'_> = Self::_nft_client(env);
+        let token_id: u32 = client.create_with_attributes(&env.current_contract_address(), creator, &impact_data, &listing_price, attributes);
+        if let Some(project_id) = project_id {
+            projects::add_token(env, project_id, token_id, impact_data.impact_value);
+            client.assign_project(&env.current_contract_address(), &vec![env, token_id], &project_id);
+        }
+        Ok(token_id)
+    }
+
+    fn _batch_create(env: &Env, creator: &Address, project_id: Option<u32>, products: Vec<ImpactProductData>, attributes: &Vec<Map<String, AttributeValue>>) -> Result<Vec<u32>, ContractError> {
+        access_control::require_role(env, &CREATOR_ROLE, creator)?;
+        Self::_require_not_paused(env)?;
+        if products.is_empty() {
+            return Err(ContractError::InvalidBatchSize);
+        }
+        if products.len() != attributes.len() {
+            return Err(ContractError::BatchLengthMismatch);
+        }
+        if let Some(project_id) = project_id {
+            Self::_require_issuing_project(env, creator, project_id)?;
+        }
+
+        let mut impact_data: Vec<ImpactData> = Vec::new(env);
+        let mut prices: Vec<u128> = Vec::new(env);
+        for (product, product_attributes) in products.iter().zip(attributes.iter()) {
+            prices.push_back(product.listing_price);
+            impact_data.push_back(Self::_build_impact_data(env, product, &product_attributes)?);
+        }
+        creators::record_mints(env, creator, products.len())?;
+
+        let client: contract_nft::Client<'_> = Self::_nft_client(env);
+        let token_ids: Vec<u32> = client.batch_create_with_attributes(&env.current_contract_address(), creator, &impact_data, &prices, attributes);
+        if let Some(project_id) = project_id {
+            for (token_id, item) in token_ids.iter().zip(impact_data.iter()) {
+                projects::add_token(env, project_id, token_id, item.impact_value);
             }
-            item += 1;
+            client.assign_project(&env.current_contract_address(), &token_ids, &project_id);
         }
-        env.storage().persistent().set(&DataKey::ImpactCategories, &impact_categories);
+        Ok(token_ids)
     }
 
-    pub fn update_impact_params(env: Env, category: String, base_multiplier: u128) {
-        let admin: Address = env.storage().instance().get(&DataKey::ADMIN).expect("contains ADMIN");
-        admin.require_auth();
-        if !Self::is_category_supported(env.clone(), category.clone()) {
-            panic!("Category already exists")
+    fn _require_issuing_project(env: &Env, creator: &Address, project_id: u32) -> Result<(), ContractError> {
+        let project: Project = projects::require_project(env, project_id)?;
+        if project.developer != *creator {
+            return Err(ContractError::NotProjectDeveloper);
         }
-        if base_multiplier == 0 {
-            panic!("Multiplier must be positive")
+        if project.status != ProjectStatus::Active {
+            return Err(ContractError::ProjectNotActive);
+        }
+        Ok(())
+    }
+
+    fn _nft_client(env: &Env) -> contract_nft::Client<'_> {
+        let contract: Address = env.storage().instance().get(&DataKey::ImpactProductNft).expect("Should contain nft address");
+        contract_nft::Client::new(env, &contract)
+    }
+
+    fn _require_not_paused(env: &Env) -> Result<(), ContractError> {
+        let is_paused: bool = env.storage().instance().get(&DataKey::IsPaused).unwrap_or(false);
+        if is_paused {
+            return Err(ContractError::ContractPaused);
         }
-        Self::calculate_and_store_impact_params(env, category, base_multiplier, false);
+        Ok(())
     }
 
-    fn calculate_and_store_impact_params(env: Env, category: String, mult: u128, verified: bool) {
-        let mut impact_parameters: Map<String, ImpactParams> = env.storage().persistent().get(&DataKey::ImpactParameters).expect("Should contain Impact Parameters");
-        let data: ImpactParams = ImpactParams { category: category.clone(), base_multiplier: mult, verified: verified };
-        impact_parameters.set(category, data);
-        env.storage().persistent().set(&DataKey::ImpactParameters, &impact_parameters);
+    /// Adds `creator` to the set of addresses allowed to mint through the factory. Its mints
+    /// count against the quota and suspension set by admins even without `register_creator`.
+    pub fn grant_creator_role(env: Env, admin: Address, creator: Address) -> Result<(), ContractError> {
+        access_control::grant_role(&env, &admin, &CREATOR_ROLE, &creator)?;
+        Ok(())
     }
 
-    fn calculate_impact_value(env: Env, category: String, base_value: u128) -> u128 {
-        if !Self::is_category_supported(env.clone(), category.clone()) {
-            panic!("Unsupported impact category")
+    /// Approves `creator` to mint through the factory and records its profile. Profiles start
+    /// without KYC attestation.
+    pub fn register_creator(env: Env, admin: Address, creator: Address, organisation_name: String, country: String, website: String, mint_quota: Option<u32>) -> Result<(), ContractError> {
+        access_control::require_role(&env, &DEFAULT_ADMIN_ROLE, &admin)?;
+        let existing: Option<CreatorProfile> = creators::get_creator(&env, &creator);
+        if existing.as_ref().is_some_and(creators::is_registered) {
+            return Err(ContractError::CreatorAlreadyRegistered);
         }
-        let impact_parameters: Map<String, ImpactParams> = env.storage().persistent().get(&DataKey::ImpactParameters).expect("Should contain Impact Parameters");
-        let params: ImpactParams = impact_parameters.get(category).expect("should contain category data");
-        let calculated_value: u128 = base_value * params.base_multiplier / 10000;
-        calculated_value 
+        creators::validate_profile(&organisation_name, &country)?;
+
+        // Role holders that minted before registering keep their count and any suspension.
+        let (minted, suspended): (u32, bool) = existing.map_or((0, false), |profile| (profile.minted, profile.suspended));
+        let profile: CreatorProfile = CreatorProfile { organisation_name, country, website, kyc_attested: false, suspended, mint_quota, minted, registered_at: env.ledger().timestamp() };
+        creators::add_creator(&env, &creator, &profile);
+        access_control::_grant_role(&env, &CREATOR_ROLE, &creator, &admin);
+        env.events().publish((symbol_short!("creator"), symbol_short!("register")), creator);
+        Ok(())
+    }
+
+    /// Lets a registered creator edit its own profile. Any KYC attestation is cleared until an
+    /// admin reviews the new details.
+    pub fn update_creator_profile(env: Env, creator: Address, organisation_name: String, country: String, website: String) -> Result<(), ContractError> {
+        creator.require_auth();
+        let mut profile: CreatorProfile = creators::require_creator(&env, &creator)?;
+        creators::validate_profile(&organisation_name, &country)?;
+
+        profile.organisation_name = organisation_name;
+        profile.country = country;
+        profile.website = website;
+        profile.kyc_attested = false;
+        creators::set_creator(&env, &creator, &profile);
+        env.events().publish((symbol_short!("creator"), symbol_short!("profile")), creator);
+        Ok(())
+    }
+
+    pub fn set_creator_kyc(env: Env, admin: Address, creator: Address, attested: bool) -> Result<(), ContractError> {
+        access_control::require_role(&env, &DEFAULT_ADMIN_ROLE, &admin)?;
+        let mut profile: CreatorProfile = creators::require_creator(&env, &creator)?;
+        profile.kyc_attested = attested;
+        creators::set_creator(&env, &creator, &profile);
+        env.events().publish((symbol_short!("creator"), symbol_short!("kyc")), (creator, attested));
+        Ok(())
+    }
+
+    /// Blocks `creator` from minting and deploying collections until reactivated. Its tokens
+    /// are unaffected.
+    pub fn suspend_creator(env: Env, admin: Address, creator: Address) -> Result<(), ContractError> {
+        Self::_set_creator_suspended(&env, &admin, &creator, true)
+    }
+
+    pub fn reactivate_creator(env: Env, admin: Address, creator: Address) -> Result<(), ContractError> {
+        Self::_set_creator_suspended(&env, &admin, &creator, false)
+    }
+
+    /// Caps the total number of tokens `creator` may mint; `None` removes the cap. Tokens
+    /// already minted count towards the new quota.
+    pub fn set_creator_quota(env: Env, admin: Address, creator: Address, mint_quota: Option<u32>) -> Result<(), ContractError> {
+        access_control::require_role(&env, &DEFAULT_ADMIN_ROLE, &admin)?;
+        let mut profile: CreatorProfile = Self::_creator_profile(&env, &creator)?;
+        profile.mint_quota = mint_quota;
+        creators::set_creator(&env, &creator, &profile);
+        env.events().publish((symbol_short!("creator"), symbol_short!("quota")), (creator, mint_quota));
+        Ok(())
     }
 
-    pub fn grant_creator_role(env: Env, creator: Address) {
-        let admin: Address = env.storage().instance().get(&DataKey::ADMIN).expect("PAUSER not found");
-        admin.require_auth();
-        env.storage().instance().set(&DataKey::CREATOR, &creator);
+    pub fn get_creator(env: Env, creator: Address) -> Option<CreatorProfile> {
+        creators::get_creator(&env, &creator)
+    }
+
+    /// Every registered creator, in registration order.
+    pub fn get_creators(env: Env) -> Vec<Address> {
+        creators::get_creators(&env)
+    }
+
+    /// Returns the profile of a registered creator or of a CREATOR role holder, which starts
+    /// from an unregistered one if it has none. Nothing is stored until the caller saves it.
+    fn _creator_profile(env: &Env, creator: &Address) -> Result<CreatorProfile, ContractError> {
+        if creators::get_creator(env, creator).is_none() && !access_control::has_role(env, &CREATOR_ROLE, creator) {
+            return Err(ContractError::CreatorNotRegistered);
+        }
+        Ok(creators::get_or_default(env, creator))
+    }
+
+    fn _set_creator_suspended(env: &Env, admin: &Address, creator: &Address, suspended: bool) -> Result<(), ContractError> {
+        access_control::require_role(env, &DEFAULT_ADMIN_ROLE, admin)?;
+        let mut profile: CreatorProfile = Self::_creator_profile(env, creator)?;
+        profile.suspended = suspended;
+        creators::set_creator(env, creator, &profile);
+        let action: Symbol = if suspended { symbol_short!("suspend") } else { symbol_short!("activate") };
+        env.events().publish((symbol_short!("creator"), action), creator.clone());
+        Ok(())
+    }
+
+    pub fn grant_role(env: Env, caller: Address, role: Symbol, account: Address) -> Result<(), ContractError> {
+        access_control::grant_role(&env, &caller, &role, &account)?;
+        Ok(())
+    }
+
+    pub fn revoke_role(env: Env, caller: Address, role: Symbol, account: Address) -> Result<(), ContractError> {
+        access_control::revoke_role(&env, &caller, &role, &account)?;
+        Ok(())
+    }
+
+    pub fn renounce_role(env: Env, account: Address, role: Symbol) -> Result<(), ContractError> {
+        access_control::renounce_role(&env, &account, &role)?;
+        Ok(())
+    }
+
+    pub fn set_role_admin(env: Env, caller: Address, role: Symbol, admin_role: Symbol) -> Result<(), ContractError> {
+        access_control::set_role_admin(&env, &caller, &role, &admin_role)?;
+        Ok(())
+    }
+
+    pub fn has_role(env: Env, role: Symbol, account: Address) -> bool {
+        access_control::has_role(&env, &role, &account)
+    }
+
+    pub fn get_role_member_count(env: Env, role: Symbol) -> u32 {
+        access_control::get_role_member_count(&env, &role)
+    }
+
+    pub fn get_role_admin(env: Env, role: Symbol) -> Symbol {
+        access_control::get_role_admin(&env, &role)
+    }
+
+    /// Proposes `new_admin` to take over `admin`'s `ADMIN` role once it calls `accept_admin`,
+    /// by `live_until_ledger` if set.
+    pub fn propose_admin(env: Env, admin: Address, new_admin: Address, live_until_ledger: Option<u32>) -> Result<(), ContractError> {
+        access_control::propose_admin(&env, &admin, &new_admin, live_until_ledger)?;
+        Ok(())
+    }
+
+    pub fn accept_admin(env: Env) -> Result<Address, ContractError> {
+        Ok(access_control::accept_admin(&env)?)
+    }
+
+    pub fn cancel_admin_transfer(env: Env, caller: Address) -> Result<(), ContractError> {
+        access_control::cancel_admin_transfer(&env, &caller)?;
+        Ok(())
+    }
+
+    pub fn pending_admin(env: Env) -> Option<AdminTransfer> {
+        access_control::get_pending_admin(&env)
+    }
+
+    /// Replaces the factory code with the uploaded wasm `new_wasm_hash`, keeping storage.
+    /// Call `migrate` afterwards to bring storage up to the new code's schema version.
+    pub fn upgrade(env: Env, admin: Address, new_wasm_hash: BytesN<32>) -> Result<(), ContractError> {
+        access_control::require_role(&env, &DEFAULT_ADMIN_ROLE, &admin)?;
+        env.deployer().update_current_contract_wasm(new_wasm_hash.clone());
+        env.events().publish((symbol_short!("upgrade"),), new_wasm_hash);
+        Ok(())
+    }
+
+    /// Storage layout version. Factories deployed before versioning report 0.
+    pub fn schema_version(env: Env) -> u32 {
+        env.storage().instance().get(&DataKey::SchemaVersion).unwrap_or(0)
+    }
+
+    /// Runs the data migrations between the stored schema version and this build's and
+    /// returns the version now in force.
+    pub fn migrate(env: Env, admin: Address) -> Result<u32, ContractError> {
+        // Admins of an earlier layout are only visible to the role check once moved.
+        access_control::migrate_role_members(&env, &DEFAULT_ADMIN_ROLE);
+        access_control::require_role(&env, &DEFAULT_ADMIN_ROLE, &admin)?;
+        let from: u32 = Self::schema_version(env.clone());
+        if from > SCHEMA_VERSION {
+            return Err(ContractError::UnsupportedSchemaVersion);
+        }
+        // Version 2 stores each category in its own entry.
+        if from < 2 {
+            categories::migrate_legacy_params(&env);
+        }
+        // Version 3 gives each role holder its own entry.
+        if from < 3 {
+            for role in [DEFAULT_ADMIN_ROLE, CREATOR_ROLE, VERIFIER_ROLE] {
+                access_control::migrate_role_members(&env, &role);
+            }
+        }
+        env.storage().instance().set(&DataKey::SchemaVersion, &SCHEMA_VERSION);
+        env.events().publish((symbol_short!("migrate"),), (from, SCHEMA_VERSION));
+        Ok(SCHEMA_VERSION)
     }
 
-    pub fn pause(env: Env) {
-        let admin: Address = env.storage().instance().get(&DataKey::ADMIN).expect("PAUSER not found");
-        admin.require_auth();
+    pub fn pause(env: Env, admin: Address) -> Result<(), ContractError> {
+        access_control::require_role(&env, &DEFAULT_ADMIN_ROLE, &admin)?;
         env.storage().instance().set(&DataKey::IsPaused, &true);
+        Ok(())
     }
 
-    pub fn unpause(env: Env) {
-        let admin: Address = env.storage().instance().get(&DataKey::ADMIN).expect("PAUSER not found");
-        admin.require_auth();
+    pub fn unpause(env: Env, admin: Address) -> Result<(), ContractError> {
+        access_control::require_role(&env, &DEFAULT_ADMIN_ROLE, &admin)?;
         env.storage().instance().set(&DataKey::IsPaused, &false);
+        Ok(())
     }
 }
 
@@ -0,0 +1,131 @@
+use soroban_sdk::{contracttype, Address, Env, String, Vec};
+
+use crate::{ContractError, DataKey};
+
+/// Most token IDs `get_project_tokens` returns per call.
+pub const MAX_PAGE_SIZE: u32 = 100;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ProjectStatus {
+    /// Registered but not yet approved to issue.
+    Draft,
+    Active,
+    /// Issuance halted by an admin until the project is reactivated.
+    Suspended,
+    /// Closed for good. Tokens already issued are unaffected.
+    Completed
+}
+
+/// A real-world site or programme that issues impact products over its lifetime.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Project {
+    pub id: u32,
+    pub name: String,
+    pub developer: Address,
+    pub location: String,
+    pub methodology: String,
+    pub documentation_uri: String,
+    pub status: ProjectStatus,
+    pub registered_at: u64,
+    pub updated_at: u64
+}
+
+/// Impact issued under a project, and how much of it the NFT contract records as verified
+/// or retired.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProjectImpact {
+    pub token_count: u32,
+    pub issued: u128,
+    pub verified: u128,
+    pub retired: u128
+}
+
+/// Tokens issued under a project and their impact at issuance.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) struct ProjectIssuance {
+    pub token_count: u32,
+    pub issued: u128
+}
+
+pub fn get_project(env: &Env, project_id: u32) -> Option<Project> {
+    env.storage().persistent().get(&DataKey::Project(project_id))
+}
+
+pub fn set_project(env: &Env, project: &Project) {
+    env.storage().persistent().set(&DataKey::Project(project.id), project);
+}
+
+pub fn require_project(env: &Env, project_id: u32) -> Result<Project, ContractError> {
+    get_project(env, project_id).ok_or(ContractError::ProjectNotFound)
+}
+
+/// Stores `project` under the next project ID, which starts at 1, and returns the ID.
+pub fn add_project(env: &Env, mut project: Project) -> u32 {
+    let project_id: u32 = env.storage().instance().get(&DataKey::ProjectCount).unwrap_or(0) + 1;
+    env.storage().instance().set(&DataKey::ProjectCount, &project_id);
+    project.id = project_id;
+    set_project(env, &project);
+
+    let developer_key: DataKey = DataKey::DeveloperProjects(project.developer.clone());
+    let mut projects: Vec<u32> = env.storage().persistent().get(&developer_key).unwrap_or_else(|| Vec::new(env));
+    projects.push_back(project_id);
+    env.storage().persistent().set(&developer_key, &projects);
+    project_id
+}
+
+pub fn get_projects_by_developer(env: &Env, developer: &Address) -> Vec<Project> {
+    let project_ids: Vec<u32> = env.storage().persistent().get(&DataKey::DeveloperProjects(developer.clone())).unwrap_or_else(|| Vec::new(env));
+    let mut projects: Vec<Project> = Vec::new(env);
+    for project_id in project_ids.iter() {
+        projects.push_back(get_project(env, project_id).expect("registered project should exist"));
+    }
+    projects
+}
+
+pub fn validate_project(name: &String, location: &String, methodology: &String) -> Result<(), ContractError> {
+    if name.is_empty() || location.is_empty() || methodology.is_empty() {
+        return Err(ContractError::InvalidProject);
+    }
+    Ok(())
+}
+
+/// Whether a project may move from `from` to `to`. Completion is final.
+pub fn is_valid_transition(from: &ProjectStatus, to: &ProjectStatus) -> bool {
+    matches!(
+        (from, to),
+        (ProjectStatus::Draft, ProjectStatus::Active) | (ProjectStatus::Active, ProjectStatus::Suspended) | (ProjectStatus::Suspended, ProjectStatus::Active) | (ProjectStatus::Active, ProjectStatus::Completed) | (ProjectStatus::Suspended, ProjectStatus::Completed)
+    )
+}
+
+pub fn get_issuance(env: &Env, project_id: u32) -> ProjectIssuance {
+    env.storage().persistent().get(&DataKey::ProjectIssuance(project_id)).unwrap_or(ProjectIssuance { token_count: 0, issued: 0 })
+}
+
+/// Links a newly minted token to `project_id` and adds `impact_value` to the issued total.
+pub fn add_token(env: &Env, project_id: u32, token_id: u32, impact_value: u128) {
+    let mut issuance: ProjectIssuance = get_issuance(env, project_id);
+    env.storage().persistent().set(&DataKey::ProjectToken(project_id, issuance.token_count), &token_id);
+    env.storage().persistent().set(&DataKey::TokenProject(token_id), &project_id);
+    issuance.token_count += 1;
+    issuance.issued += impact_value;
+    env.storage().persistent().set(&DataKey::ProjectIssuance(project_id), &issuance);
+}
+
+pub fn get_token_project(env: &Env, token_id: u32) -> Option<u32> {
+    env.storage().persistent().get(&DataKey::TokenProject(token_id))
+}
+
+/// Token IDs issued under `project_id`, starting at the `start`th issued token.
+pub fn get_tokens(env: &Env, project_id: u32, start: u32, limit: u32) -> Vec<u32> {
+    let count: u32 = get_issuance(env, project_id).token_count;
+    let end: u32 = start.saturating_add(limit.min(MAX_PAGE_SIZE)).min(count);
+    let mut token_ids: Vec<u32> = Vec::new(env);
+    for index in start..end {
+        token_ids.push_back(env.storage().persistent().get(&DataKey::ProjectToken(project_id, index)).expect("project token index out of sync"));
+    }
+    token_ids
+}
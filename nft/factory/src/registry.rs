@@ -0,0 +1,64 @@
+use soroban_sdk::{contracttype, Address, BytesN, Env, String, Vec};
+
+use crate::DataKey;
+
+/// An ImpactProductNFT collection deployed by the factory.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Collection {
+    pub address: Address,
+    pub owner: Address,
+    pub name: String,
+    pub symbol: String,
+    /// Wasm the collection was deployed from, which later rotations do not change.
+    pub wasm_hash: BytesN<32>,
+    pub deployed_at: u64
+}
+
+/// Wasm hash `deploy_collection` deploys new collections from.
+pub fn get_nft_wasm_hash(env: &Env) -> Option<BytesN<32>> {
+    env.storage().instance().get(&DataKey::NftWasmHash)
+}
+
+pub fn set_nft_wasm_hash(env: &Env, wasm_hash: &BytesN<32>) {
+    env.storage().instance().set(&DataKey::NftWasmHash, wasm_hash);
+}
+
+pub fn get_collection(env: &Env, address: &Address) -> Option<Collection> {
+    env.storage().persistent().get(&DataKey::Collection(address.clone()))
+}
+
+/// Collections in deployment order.
+pub fn get_collections(env: &Env) -> Vec<Collection> {
+    collections_at(env, &get_collection_addresses(env))
+}
+
+pub fn get_collections_by_owner(env: &Env, owner: &Address) -> Vec<Collection> {
+    let addresses: Vec<Address> = env.storage().persistent().get(&DataKey::OwnerCollections(owner.clone())).unwrap_or_else(|| Vec::new(env));
+    collections_at(env, &addresses)
+}
+
+pub fn add_collection(env: &Env, collection: &Collection) {
+    env.storage().persistent().set(&DataKey::Collection(collection.address.clone()), collection);
+
+    let mut addresses: Vec<Address> = get_collection_addresses(env);
+    addresses.push_back(collection.address.clone());
+    env.storage().persistent().set(&DataKey::Collections, &addresses);
+
+    let owner_key: DataKey = DataKey::OwnerCollections(collection.owner.clone());
+    let mut owned: Vec<Address> = env.storage().persistent().get(&owner_key).unwrap_or_else(|| Vec::new(env));
+    owned.push_back(collection.address.clone());
+    env.storage().persistent().set(&owner_key, &owned);
+}
+
+fn get_collection_addresses(env: &Env) -> Vec<Address> {
+    env.storage().persistent().get(&DataKey::Collections).unwrap_or_else(|| Vec::new(env))
+}
+
+fn collections_at(env: &Env, addresses: &Vec<Address>) -> Vec<Collection> {
+    let mut collections: Vec<Collection> = Vec::new(env);
+    for address in addresses.iter() {
+        collections.push_back(get_collection(env, &address).expect("registered collection should exist"));
+    }
+    collections
+}
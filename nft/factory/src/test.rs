@@ -1,13 +1,25 @@
 #[cfg(test)]
-use crate::{ImpactProductFactory, ImpactProductFactoryClient};
+use crate::{AttributeSpec, AttributeType, CategoryMetadata, CategoryStatus, Collection, ContractError, CreatorProfile, DataKey, ImpactCategory, ImpactProductFactory, ImpactProductFactoryClient, ImpactProductData, Project, ProjectImpact, ProjectStatus, CREATOR_ROLE};
 #[cfg(test)]
-use soroban_sdk::{Env, Address, String, Vec};
+use crate::categories::LegacyImpactParams;
+#[cfg(test)]
+use crate::contract_nft::AttributeValue;
+#[cfg(test)]
+use access_control::{AccessKey, DEFAULT_ADMIN_ROLE};
+#[cfg(test)]
+use soroban_sdk::{Env, Address, BytesN, Map, String, Vec, symbol_short, vec};
 #[cfg(test)]
 use soroban_sdk::testutils::{Address as _};
 
-mod contract_nft {
+// Shares the crate's import so attribute values passed to the factory and read back from
+// the NFT are the same type.
+#[cfg(test)]
+use crate::contract_nft;
+
+#[cfg(test)]
+mod contract_factory {
     soroban_sdk::contractimport!(
-        file = "../../target/wasm32v1-none/release/nft.wasm"
+        file = "../../target/wasm32v1-none/release/nftfactory.wasm"
     );
 }
 
@@ -16,7 +28,7 @@ fn test_factory() {
     let env: Env = Env::default();
     let admin: Address = Address::generate(&env);
 
-    let contract_id_nft: Address = env.register(contract_nft::WASM, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC"));
+    let contract_id_nft: Address = env.register(contract_nft::WASM, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
 
     let contract_id: Address = env.register(ImpactProductFactory, (&admin, contract_id_nft));
     let client: ImpactProductFactoryClient<'_> = ImpactProductFactoryClient::new(&env, &contract_id);
@@ -31,4 +43,561 @@ fn test_factory() {
     impact_categories.push_back(String::from_str(&env, "Waste Management"));
 
     assert_eq!(client.get_supported_categories(), impact_categories)
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_verify_impact_product() {
+    let env: Env = Env::default();
+    env.mock_all_auths();
+    let admin: Address = Address::generate(&env);
+
+    let contract_id_nft: Address = env.register(contract_nft::WASM, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
+    let nft_client: contract_nft::Client<'_> = contract_nft::Client::new(&env, &contract_id_nft);
+
+    let contract_id: Address = env.register(ImpactProductFactory, (&admin, contract_id_nft));
+    let client: ImpactProductFactoryClient<'_> = ImpactProductFactoryClient::new(&env, &contract_id);
+    nft_client.grant_role(&admin, &symbol_short!("MINTER"), &contract_id);
+
+    let impact_product_data: ImpactProductData = ImpactProductData { category: String::from_str(&env, "Tree preservation"), location: String::from_str(&env, "location"), start_date: 9999, end_date: 10000, beneficiaries: String::from_str(&env, "someone"), base_impact_value: 10000, listing_price: 100, metadata_uri: String::from_str(&env, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC") };
+    let token_id: u32 = client.create_impact_product(&admin, &impact_product_data);
+
+    let validator: Address = Address::generate(&env);
+    nft_client.add_validator(&admin, &validator);
+    nft_client.set_quorum(&admin, &1);
+
+    assert!(client.verify_impact_product(&validator, &token_id, &true, &String::from_str(&env, "ipfs://evidence")));
+    assert!(nft_client.get_impact_data(&token_id).verified);
+}
+
+#[test]
+fn test_multiple_creators() {
+    let env: Env = Env::default();
+    env.mock_all_auths();
+    let admin: Address = Address::generate(&env);
+
+    let contract_id_nft: Address = env.register(contract_nft::WASM, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
+    let nft_client: contract_nft::Client<'_> = contract_nft::Client::new(&env, &contract_id_nft);
+
+    let contract_id: Address = env.register(ImpactProductFactory, (&admin, contract_id_nft));
+    let client: ImpactProductFactoryClient<'_> = ImpactProductFactoryClient::new(&env, &contract_id);
+    nft_client.grant_role(&admin, &symbol_short!("MINTER"), &contract_id);
+
+    let creator_a: Address = Address::generate(&env);
+    let creator_b: Address = Address::generate(&env);
+    client.grant_creator_role(&admin, &creator_a);
+    client.grant_creator_role(&admin, &creator_b);
+    assert!(client.has_role(&CREATOR_ROLE, &creator_a));
+    assert!(client.has_role(&CREATOR_ROLE, &creator_b));
+
+    let impact_product_data: ImpactProductData = ImpactProductData { category: String::from_str(&env, "Tree preservation"), location: String::from_str(&env, "location"), start_date: 9999, end_date: 10000, beneficiaries: String::from_str(&env, "someone"), base_impact_value: 10000, listing_price: 100, metadata_uri: String::from_str(&env, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC") };
+    let token_a: u32 = client.create_impact_product(&creator_a, &impact_product_data);
+    let token_b: u32 = client.create_impact_product(&creator_b, &impact_product_data);
+    assert_eq!(nft_client.owner_of(&token_a), creator_a);
+    assert_eq!(nft_client.owner_of(&token_b), creator_b);
+
+    client.revoke_role(&admin, &CREATOR_ROLE, &creator_b);
+    assert_eq!(client.try_create_impact_product(&creator_b, &impact_product_data), Err(Ok(ContractError::MissingRole)));
+}
+
+#[test]
+fn test_creator_registry() {
+    let env: Env = Env::default();
+    env.mock_all_auths();
+    let admin: Address = Address::generate(&env);
+
+    let contract_id_nft: Address = env.register(contract_nft::WASM, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
+    let nft_client: contract_nft::Client<'_> = contract_nft::Client::new(&env, &contract_id_nft);
+    let contract_id: Address = env.register(ImpactProductFactory, (&admin, contract_id_nft));
+    let client: ImpactProductFactoryClient<'_> = ImpactProductFactoryClient::new(&env, &contract_id);
+    nft_client.grant_role(&admin, &symbol_short!("MINTER"), &contract_id);
+
+    let creator: Address = Address::generate(&env);
+    let name: String = String::from_str(&env, "Mangrove Trust");
+    let country: String = String::from_str(&env, "KE");
+    let website: String = String::from_str(&env, "https://mangrove.example");
+    assert_eq!(client.try_register_creator(&creator, &creator, &name, &country, &website, &None), Err(Ok(ContractError::MissingRole)));
+    assert_eq!(client.try_register_creator(&admin, &creator, &String::from_str(&env, ""), &country, &website, &None), Err(Ok(ContractError::InvalidCreatorProfile)));
+    client.register_creator(&admin, &creator, &name, &country, &website, &Some(2));
+    assert_eq!(client.try_register_creator(&admin, &creator, &name, &country, &website, &None), Err(Ok(ContractError::CreatorAlreadyRegistered)));
+    assert!(client.has_role(&CREATOR_ROLE, &creator));
+    assert_eq!(client.get_creators(), vec![&env, creator.clone()]);
+    assert_eq!(client.get_creator(&creator), Some(CreatorProfile { organisation_name: name.clone(), country: country.clone(), website: website.clone(), kyc_attested: false, suspended: false, mint_quota: Some(2), minted: 0, registered_at: env.ledger().timestamp() }));
+
+    // KYC attestation lapses when the creator edits its profile
+    client.set_creator_kyc(&admin, &creator, &true);
+    assert!(client.get_creator(&creator).unwrap().kyc_attested);
+    let renamed: String = String::from_str(&env, "Mangrove Trust Ltd");
+    client.update_creator_profile(&creator, &renamed, &country, &website);
+    let profile: CreatorProfile = client.get_creator(&creator).unwrap();
+    assert_eq!(profile.organisation_name, renamed);
+    assert!(!profile.kyc_attested);
+
+    // Tokens go to the calling creator and count against its quota
+    let impact_product_data: ImpactProductData = ImpactProductData { category: String::from_str(&env, "Tree preservation"), location: String::from_str(&env, "location"), start_date: 9999, end_date: 10000, beneficiaries: String::from_str(&env, "someone"), base_impact_value: 10000, listing_price: 100, metadata_uri: String::from_str(&env, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC") };
+    let token_id: u32 = client.create_impact_product(&creator, &impact_product_data);
+    assert_eq!(nft_client.owner_of(&token_id), creator);
+    assert_eq!(client.get_creator(&creator).unwrap().minted, 1);
+    assert_eq!(client.try_batch_create_impact_products(&creator, &vec![&env, impact_product_data.clone(), impact_product_data.clone()]), Err(Ok(ContractError::MintQuotaExceeded)));
+    client.set_creator_quota(&admin, &creator, &Some(3));
+    client.batch_create_impact_products(&creator, &vec![&env, impact_product_data.clone(), impact_product_data.clone()]);
+    assert_eq!(client.get_creator(&creator).unwrap().minted, 3);
+    client.set_creator_quota(&admin, &creator, &None);
+
+    // Suspended creators cannot mint until reactivated
+    client.suspend_creator(&admin, &creator);
+    assert_eq!(client.try_create_impact_product(&creator, &impact_product_data), Err(Ok(ContractError::CreatorSuspended)));
+    client.reactivate_creator(&admin, &creator);
+    client.create_impact_product(&creator, &impact_product_data);
+
+    let unknown: Address = Address::generate(&env);
+    assert_eq!(client.try_suspend_creator(&admin, &unknown), Err(Ok(ContractError::CreatorNotRegistered)));
+    assert_eq!(client.try_update_creator_profile(&unknown, &name, &country, &website), Err(Ok(ContractError::CreatorNotRegistered)));
+
+    // Creators granted the role without registering are held to quotas and suspension too
+    let role_only: Address = Address::generate(&env);
+    client.grant_creator_role(&admin, &role_only);
+    client.create_impact_product(&role_only, &impact_product_data);
+    assert_eq!(client.get_creator(&role_only).unwrap().minted, 1);
+    client.set_creator_quota(&admin, &role_only, &Some(1));
+    assert_eq!(client.try_create_impact_product(&role_only, &impact_product_data), Err(Ok(ContractError::MintQuotaExceeded)));
+    client.set_creator_quota(&admin, &role_only, &None);
+    client.suspend_creator(&admin, &role_only);
+    assert_eq!(client.try_create_impact_product(&role_only, &impact_product_data), Err(Ok(ContractError::CreatorSuspended)));
+    assert_eq!(client.try_update_creator_profile(&role_only, &name, &country, &website), Err(Ok(ContractError::CreatorNotRegistered)));
+    assert_eq!(client.get_creators(), vec![&env, creator.clone()]);
+
+    // Registering fills in the details and keeps the mint count and suspension
+    client.register_creator(&admin, &role_only, &name, &country, &website, &None);
+    let profile: CreatorProfile = client.get_creator(&role_only).unwrap();
+    assert_eq!((profile.minted, profile.suspended), (1, true));
+    assert_eq!(client.get_creators(), vec![&env, creator.clone(), role_only.clone()]);
+}
+
+#[test]
+fn test_project_registry() {
+    let env: Env = Env::default();
+    env.mock_all_auths();
+    let admin: Address = Address::generate(&env);
+
+    let contract_id_nft: Address = env.register(contract_nft::WASM, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
+    let nft_client: contract_nft::Client<'_> = contract_nft::Client::new(&env, &contract_id_nft);
+    let contract_id: Address = env.register(ImpactProductFactory, (&admin, contract_id_nft));
+    let client: ImpactProductFactoryClient<'_> = ImpactProductFactoryClient::new(&env, &contract_id);
+    nft_client.grant_role(&admin, &symbol_short!("MINTER"), &contract_id);
+
+    let developer: Address = Address::generate(&env);
+    let name: String = String::from_str(&env, "Gazi Bay mangroves");
+    let location: String = String::from_str(&env, "Kwale, Kenya");
+    let methodology: String = String::from_str(&env, "VM0033");
+    let documentation_uri: String = String::from_str(&env, "ipfs://project-docs");
+    assert_eq!(client.try_register_project(&developer, &name, &location, &methodology, &documentation_uri), Err(Ok(ContractError::MissingRole)));
+    client.grant_creator_role(&admin, &developer);
+    assert_eq!(client.try_register_project(&developer, &name, &location, &String::from_str(&env, ""), &documentation_uri), Err(Ok(ContractError::InvalidProject)));
+    let project_id: u32 = client.register_project(&developer, &name, &location, &methodology, &documentation_uri);
+    let project: Project = Project { id: project_id, name, developer: developer.clone(), location, methodology, documentation_uri, status: ProjectStatus::Draft, registered_at: env.ledger().timestamp(), updated_at: env.ledger().timestamp() };
+    assert_eq!(client.get_project(&project_id), Some(project.clone()));
+    assert_eq!(client.get_projects_by_developer(&developer), vec![&env, project]);
+
+    // Drafts cannot issue until an admin activates them
+    let impact_product_data: ImpactProductData = ImpactProductData { category: String::from_str(&env, "Tree preservation"), location: String::from_str(&env, "location"), start_date: 9999, end_date: 10000, beneficiaries: String::from_str(&env, "someone"), base_impact_value: 10000, listing_price: 100, metadata_uri: String::from_str(&env, "") };
+    assert_eq!(client.try_issue_project_product(&developer, &project_id, &impact_product_data, &Map::new(&env)), Err(Ok(ContractError::ProjectNotActive)));
+    assert_eq!(client.try_set_project_status(&developer, &project_id, &ProjectStatus::Active), Err(Ok(ContractError::MissingRole)));
+    assert_eq!(client.try_set_project_status(&admin, &project_id, &ProjectStatus::Completed), Err(Ok(ContractError::InvalidProjectStatus)));
+    assert_eq!(client.try_set_project_status(&admin, &99, &ProjectStatus::Active), Err(Ok(ContractError::ProjectNotFound)));
+    client.set_project_status(&admin, &project_id, &ProjectStatus::Active);
+
+    assert_eq!(client.try_issue_project_product(&admin, &project_id, &impact_product_data, &Map::new(&env)), Err(Ok(ContractError::NotProjectDeveloper)));
+    assert_eq!(client.try_issue_project_product(&developer, &99, &impact_product_data, &Map::new(&env)), Err(Ok(ContractError::ProjectNotFound)));
+    let first: u32 = client.issue_project_product(&developer, &project_id, &impact_product_data, &Map::new(&env));
+    let batch: Vec<u32> = client.batch_issue_project_products(&developer, &project_id, &vec![&env, impact_product_data.clone(), impact_product_data.clone()], &vec![&env, Map::new(&env), Map::new(&env)]);
+    let loose: u32 = client.create_impact_product(&developer, &impact_product_data);
+    assert_eq!(client.get_project_impact(&project_id), ProjectImpact { token_count: 3, issued: 7500, verified: 0, retired: 0 });
+    assert_eq!(client.get_project_tokens(&project_id, &0, &10), vec![&env, first, batch.get_unchecked(0), batch.get_unchecked(1)]);
+    assert_eq!(client.get_project_tokens(&project_id, &1, &1), vec![&env, batch.get_unchecked(0)]);
+    assert_eq!(client.get_token_project(&first), Some(project_id));
+    assert_eq!(client.get_token_project(&loose), None);
+
+    // Verifying and then retiring a token moves its impact from verified to retired straight away
+    let validator: Address = Address::generate(&env);
+    nft_client.add_validator(&admin, &validator);
+    nft_client.set_quorum(&admin, &1);
+    client.verify_impact_product(&validator, &first, &true, &String::from_str(&env, "ipfs://evidence"));
+    assert_eq!(client.get_project_impact(&project_id), ProjectImpact { token_count: 3, issued: 7500, verified: 2500, retired: 0 });
+    nft_client.retire(&developer, &first, &String::from_str(&env, "Acme Ltd"), &String::from_str(&env, "2024 offset"));
+    assert_eq!(client.get_project_impact(&project_id), ProjectImpact { token_count: 3, issued: 7500, verified: 0, retired: 2500 });
+
+    // Verifications made on the NFT directly count too; tokens outside the project do not
+    nft_client.verify_token(&validator, &batch.get_unchecked(0), &true, &String::from_str(&env, "ipfs://evidence"));
+    nft_client.verify_token(&validator, &loose, &true, &String::from_str(&env, "ipfs://evidence"));
+    assert_eq!(client.get_project_impact(&project_id), ProjectImpact { token_count: 3, issued: 7500, verified: 2500, retired: 2500 });
+    assert_eq!(nft_client.get_token_project(&batch.get_unchecked(1)), Some(project_id));
+
+    client.set_project_status(&admin, &project_id, &ProjectStatus::Suspended);
+    assert_eq!(client.try_issue_project_product(&developer, &project_id, &impact_product_data, &Map::new(&env)), Err(Ok(ContractError::ProjectNotActive)));
+    assert_eq!(client.try_set_project_status(&developer, &project_id, &ProjectStatus::Completed), Err(Ok(ContractError::MissingRole)));
+    client.set_project_status(&admin, &project_id, &ProjectStatus::Active);
+    client.set_project_status(&developer, &project_id, &ProjectStatus::Completed);
+    assert_eq!(client.get_project(&project_id).unwrap().status, ProjectStatus::Completed);
+    assert_eq!(client.try_set_project_status(&admin, &project_id, &ProjectStatus::Active), Err(Ok(ContractError::InvalidProjectStatus)));
+    assert_eq!(client.try_batch_issue_project_products(&developer, &project_id, &vec![&env, impact_product_data], &vec![&env, Map::new(&env)]), Err(Ok(ContractError::ProjectNotActive)));
+}
+
+#[test]
+fn test_batch_create_impact_products() {
+    let env: Env = Env::default();
+    env.mock_all_auths();
+    let admin: Address = Address::generate(&env);
+
+    let contract_id_nft: Address = env.register(contract_nft::WASM, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
+    let nft_client: contract_nft::Client<'_> = contract_nft::Client::new(&env, &contract_id_nft);
+
+    let contract_id: Address = env.register(ImpactProductFactory, (&admin, contract_id_nft));
+    let client: ImpactProductFactoryClient<'_> = ImpactProductFactoryClient::new(&env, &contract_id);
+    nft_client.grant_role(&admin, &symbol_short!("MINTER"), &contract_id);
+
+    let trees: ImpactProductData = ImpactProductData { category: String::from_str(&env, "Tree preservation"), location: String::from_str(&env, "location"), start_date: 9999, end_date: 10000, beneficiaries: String::from_str(&env, "someone"), base_impact_value: 10000, listing_price: 100, metadata_uri: String::from_str(&env, "") };
+    let mut waste: ImpactProductData = trees.clone();
+    waste.category = String::from_str(&env, "Waste Management");
+    waste.listing_price = 250;
+
+    let token_ids: Vec<u32> = client.batch_create_impact_products(&admin, &vec![&env, trees.clone(), waste.clone()]);
+    assert_eq!(token_ids, vec![&env, 1u32, 2u32]);
+    assert_eq!(nft_client.owner_of(&2), admin);
+    assert_eq!(nft_client.get_impact_data(&1).impact_value, 2500);
+    assert_eq!(nft_client.get_impact_data(&2).impact_value, 1200);
+    assert_eq!(nft_client.get_token_price(&2), 250);
+
+    waste.category = String::from_str(&env, "Ocean cleanup");
+    assert_eq!(client.try_batch_create_impact_products(&admin, &vec![&env, trees.clone(), waste]), Err(Ok(ContractError::UnsupportedCategory)));
+    assert_eq!(client.try_batch_create_impact_products(&admin, &Vec::new(&env)), Err(Ok(ContractError::InvalidBatchSize)));
+    assert_eq!(nft_client.token_count(), 2);
+
+    nft_client.set_max_supply(&admin, &Some(3));
+    assert!(client.try_batch_create_impact_products(&admin, &vec![&env, trees.clone(), trees]).is_err());
+    assert_eq!(nft_client.token_count(), 2);
+}
+
+#[test]
+fn test_factory_errors() {
+    let env: Env = Env::default();
+    env.mock_all_auths();
+    let admin: Address = Address::generate(&env);
+
+    let contract_id_nft: Address = env.register(contract_nft::WASM, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
+    let contract_id: Address = env.register(ImpactProductFactory, (&admin, contract_id_nft));
+    let client: ImpactProductFactoryClient<'_> = ImpactProductFactoryClient::new(&env, &contract_id);
+
+    let existing: String = String::from_str(&env, "Eco tourism");
+    let missing: String = String::from_str(&env, "Ocean cleanup");
+    let metadata: CategoryMetadata = CategoryMetadata { unit: String::from_str(&env, "kilograms"), description: String::from_str(&env, ""), required_evidence: Vec::new(&env), sdg_tags: vec![&env, 14] };
+    assert_eq!(client.try_add_impact_category(&admin, &existing, &1000, &metadata), Err(Ok(ContractError::CategoryAlreadyExists)));
+    assert_eq!(client.try_add_impact_category(&admin, &missing, &0, &metadata), Err(Ok(ContractError::InvalidMultiplier)));
+    assert_eq!(client.try_add_impact_category(&admin, &String::from_str(&env, ""), &1000, &metadata), Err(Ok(ContractError::EmptyCategory)));
+    assert_eq!(client.try_update_impact_params(&admin, &missing, &1000), Err(Ok(ContractError::UnsupportedCategory)));
+    assert_eq!(client.try_remove_impact_category(&admin, &missing), Err(Ok(ContractError::UnsupportedCategory)));
+
+    client.remove_impact_category(&admin, &existing);
+    assert!(!client.get_supported_categories().contains(&existing));
+
+    let mut impact_product_data: ImpactProductData = ImpactProductData { category: existing, location: String::from_str(&env, "location"), start_date: 9999, end_date: 10000, beneficiaries: String::from_str(&env, "someone"), base_impact_value: 10000, listing_price: 100, metadata_uri: String::from_str(&env, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC") };
+    assert_eq!(client.try_create_impact_product(&admin, &impact_product_data), Err(Ok(ContractError::UnsupportedCategory)));
+    impact_product_data.listing_price = 0;
+    assert_eq!(client.try_create_impact_product(&admin, &impact_product_data), Err(Ok(ContractError::InvalidPrice)));
+    impact_product_data.listing_price = 100;
+    impact_product_data.end_date = impact_product_data.start_date;
+    assert_eq!(client.try_create_impact_product(&admin, &impact_product_data), Err(Ok(ContractError::InvalidPeriod)));
+
+    client.pause(&admin);
+    assert_eq!(client.try_create_impact_product(&admin, &impact_product_data), Err(Ok(ContractError::ContractPaused)));
+    let not_admin: Address = Address::generate(&env);
+    assert_eq!(client.try_unpause(&not_admin), Err(Ok(ContractError::MissingRole)));
+}
+
+#[test]
+fn test_category_lifecycle() {
+    let env: Env = Env::default();
+    env.mock_all_auths();
+    let admin: Address = Address::generate(&env);
+
+    let contract_id_nft: Address = env.register(contract_nft::WASM, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
+    let nft_client: contract_nft::Client<'_> = contract_nft::Client::new(&env, &contract_id_nft);
+    let contract_id: Address = env.register(ImpactProductFactory, (&admin, contract_id_nft));
+    let client: ImpactProductFactoryClient<'_> = ImpactProductFactoryClient::new(&env, &contract_id);
+    nft_client.grant_role(&admin, &symbol_short!("MINTER"), &contract_id);
+
+    let name: String = String::from_str(&env, "Mangrove restoration");
+    let metadata: CategoryMetadata = CategoryMetadata { unit: String::from_str(&env, "hectares"), description: String::from_str(&env, "Replanting degraded mangrove forest"), required_evidence: vec![&env, String::from_str(&env, "satellite imagery"), String::from_str(&env, "planting logs")], sdg_tags: vec![&env, 13, 14, 15] };
+    let mut invalid: CategoryMetadata = metadata.clone();
+    invalid.sdg_tags = vec![&env, 18];
+    assert_eq!(client.try_add_impact_category(&admin, &name, &2000, &invalid), Err(Ok(ContractError::InvalidCategoryMetadata)));
+    invalid.sdg_tags = metadata.sdg_tags.clone();
+    invalid.unit = String::from_str(&env, "");
+    assert_eq!(client.try_add_impact_category(&admin, &name, &2000, &invalid), Err(Ok(ContractError::InvalidCategoryMetadata)));
+    client.add_impact_category(&admin, &name, &2000, &metadata);
+    assert_eq!(client.get_category(&name), Some(ImpactCategory { name: name.clone(), base_multiplier: 2000, status: CategoryStatus::Active, metadata: metadata.clone(), created_at: env.ledger().timestamp(), updated_at: env.ledger().timestamp() }));
+    assert!(client.get_supported_categories().contains(&name));
+
+    // Deprecated categories take no new mints, but their tokens are untouched
+    let impact_product_data: ImpactProductData = ImpactProductData { category: name.clone(), location: String::from_str(&env, "location"), start_date: 9999, end_date: 10000, beneficiaries: String::from_str(&env, "someone"), base_impact_value: 10000, listing_price: 100, metadata_uri: String::from_str(&env, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC") };
+    let token_id: u32 = client.create_impact_product(&admin, &impact_product_data);
+    client.deprecate_impact_category(&admin, &name);
+    assert_eq!(client.try_deprecate_impact_category(&admin, &name), Err(Ok(ContractError::InvalidCategoryStatus)));
+    assert_eq!(client.get_category(&name).unwrap().status, CategoryStatus::Deprecated);
+    assert!(!client.get_supported_categories().contains(&name));
+    assert_eq!(client.get_categories().len(), 8);
+    assert_eq!(client.try_create_impact_product(&admin, &impact_product_data), Err(Ok(ContractError::CategoryDeprecated)));
+    assert_eq!(nft_client.get_impact_data(&token_id).impact_value, 2000);
+
+    client.reactivate_impact_category(&admin, &name);
+    assert_eq!(client.try_reactivate_impact_category(&admin, &name), Err(Ok(ContractError::InvalidCategoryStatus)));
+    client.create_impact_product(&admin, &impact_product_data);
+
+    let mut updated: CategoryMetadata = metadata.clone();
+    updated.required_evidence.push_back(String::from_str(&env, "community survey"));
+    client.update_category_metadata(&admin, &name, &updated);
+    client.update_impact_params(&admin, &name, &2500);
+    let category: ImpactCategory = client.get_category(&name).unwrap();
+    assert_eq!((category.metadata, category.base_multiplier), (updated, 2500));
+
+    // Deleted names can be registered again from scratch
+    client.remove_impact_category(&admin, &name);
+    assert_eq!(client.get_category(&name), None);
+    assert_eq!(client.try_create_impact_product(&admin, &impact_product_data), Err(Ok(ContractError::UnsupportedCategory)));
+    client.add_impact_category(&admin, &name, &1000, &metadata);
+    assert_eq!(client.get_category(&name).unwrap().base_multiplier, 1000);
+
+    let not_admin: Address = Address::generate(&env);
+    assert_eq!(client.try_deprecate_impact_category(&not_admin, &name), Err(Ok(ContractError::MissingRole)));
+    assert_eq!(client.try_update_category_metadata(&not_admin, &name, &metadata), Err(Ok(ContractError::MissingRole)));
+}
+
+#[test]
+fn test_category_attribute_schema() {
+    let env: Env = Env::default();
+    env.mock_all_auths();
+    let admin: Address = Address::generate(&env);
+
+    let contract_id_nft: Address = env.register(contract_nft::WASM, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
+    let nft_client: contract_nft::Client<'_> = contract_nft::Client::new(&env, &contract_id_nft);
+    let contract_id: Address = env.register(ImpactProductFactory, (&admin, contract_id_nft));
+    let client: ImpactProductFactoryClient<'_> = ImpactProductFactoryClient::new(&env, &contract_id);
+    nft_client.grant_role(&admin, &symbol_short!("MINTER"), &contract_id);
+
+    let trees: String = String::from_str(&env, "Tree preservation");
+    let species: String = String::from_str(&env, "species");
+    let count: String = String::from_str(&env, "tree_count");
+    let native: String = String::from_str(&env, "native");
+    let schema: Vec<AttributeSpec> = vec![&env,
+        AttributeSpec { name: species.clone(), attribute_type: AttributeType::Text, required: true, min: Some(1), max: Some(64) },
+        AttributeSpec { name: count.clone(), attribute_type: AttributeType::Integer, required: true, min: Some(1), max: None },
+        AttributeSpec { name: native.clone(), attribute_type: AttributeType::Boolean, required: false, min: None, max: None }
+    ];
+    assert_eq!(client.get_category_schema(&trees), Vec::new(&env));
+    assert_eq!(client.try_set_category_schema(&admin, &String::from_str(&env, "Ocean cleanup"), &schema), Err(Ok(ContractError::UnsupportedCategory)));
+    let mut invalid: Vec<AttributeSpec> = schema.clone();
+    invalid.push_back(AttributeSpec { name: native.clone(), attribute_type: AttributeType::Boolean, required: false, min: None, max: None });
+    assert_eq!(client.try_set_category_schema(&admin, &trees, &invalid), Err(Ok(ContractError::InvalidAttributeSchema)));
+    let invalid: Vec<AttributeSpec> = vec![&env, AttributeSpec { name: count.clone(), attribute_type: AttributeType::Integer, required: true, min: Some(10), max: Some(1) }];
+    assert_eq!(client.try_set_category_schema(&admin, &trees, &invalid), Err(Ok(ContractError::InvalidAttributeSchema)));
+    let invalid: Vec<AttributeSpec> = vec![&env, AttributeSpec { name: native.clone(), attribute_type: AttributeType::Boolean, required: false, min: Some(0), max: None }];
+    assert_eq!(client.try_set_category_schema(&admin, &trees, &invalid), Err(Ok(ContractError::InvalidAttributeSchema)));
+    client.set_category_schema(&admin, &trees, &schema);
+    assert_eq!(client.get_category_schema(&trees), schema);
+
+    // Attributes must match the schema before anything is minted
+    let impact_product_data: ImpactProductData = ImpactProductData { category: trees.clone(), location: String::from_str(&env, "location"), start_date: 9999, end_date: 10000, beneficiaries: String::from_str(&env, "someone"), base_impact_value: 10000, listing_price: 100, metadata_uri: String::from_str(&env, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC") };
+    let mut attributes: Map<String, AttributeValue> = Map::new(&env);
+    attributes.set(species.clone(), AttributeValue::Text(String::from_str(&env, "Rhizophora mucronata")));
+    assert_eq!(client.try_create_with_attributes(&admin, &impact_product_data, &attributes), Err(Ok(ContractError::MissingAttribute)));
+    assert_eq!(client.try_create_impact_product(&admin, &impact_product_data), Err(Ok(ContractError::MissingAttribute)));
+    attributes.set(count.clone(), AttributeValue::Integer(0));
+    assert_eq!(client.try_create_with_attributes(&admin, &impact_product_data, &attributes), Err(Ok(ContractError::InvalidAttributeValue)));
+    attributes.set(count.clone(), AttributeValue::Text(String::from_str(&env, "120")));
+    assert_eq!(client.try_create_with_attributes(&admin, &impact_product_data, &attributes), Err(Ok(ContractError::InvalidAttributeValue)));
+    attributes.set(count.clone(), AttributeValue::Integer(120));
+    attributes.set(String::from_str(&env, "height"), AttributeValue::Integer(3));
+    assert_eq!(client.try_create_with_attributes(&admin, &impact_product_data, &attributes), Err(Ok(ContractError::UnknownAttribute)));
+    attributes.remove(String::from_str(&env, "height"));
+    assert_eq!(nft_client.token_count(), 0);
+
+    let token_id: u32 = client.create_with_attributes(&admin, &impact_product_data, &attributes);
+    assert_eq!(nft_client.get_attributes(&token_id), attributes);
+
+    let mut with_native: Map<String, AttributeValue> = attributes.clone();
+    with_native.set(native, AttributeValue::Boolean(true));
+    let token_ids: Vec<u32> = client.batch_create_with_attributes(&admin, &vec![&env, impact_product_data.clone(), impact_product_data.clone()], &vec![&env, attributes.clone(), with_native.clone()]);
+    assert_eq!(nft_client.get_attributes(&token_ids.get_unchecked(1)), with_native);
+    assert_eq!(client.try_batch_create_with_attributes(&admin, &vec![&env, impact_product_data.clone(), impact_product_data], &vec![&env, attributes]), Err(Ok(ContractError::BatchLengthMismatch)));
+
+    // Categories without a schema accept no attributes
+    let waste: ImpactProductData = ImpactProductData { category: String::from_str(&env, "Waste Management"), location: String::from_str(&env, "location"), start_date: 9999, end_date: 10000, beneficiaries: String::from_str(&env, "someone"), base_impact_value: 10000, listing_price: 100, metadata_uri: String::from_str(&env, "") };
+    assert_eq!(client.try_create_with_attributes(&admin, &waste, &with_native), Err(Ok(ContractError::UnknownAttribute)));
+    assert!(nft_client.get_attributes(&client.create_impact_product(&admin, &waste)).is_empty());
+}
+
+#[test]
+fn test_admin_transfer() {
+    let env: Env = Env::default();
+    env.mock_all_auths();
+    let admin: Address = Address::generate(&env);
+    let new_admin: Address = Address::generate(&env);
+
+    let contract_id_nft: Address = env.register(contract_nft::WASM, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
+    let contract_id: Address = env.register(ImpactProductFactory, (&admin, contract_id_nft));
+    let client: ImpactProductFactoryClient<'_> = ImpactProductFactoryClient::new(&env, &contract_id);
+
+    client.propose_admin(&admin, &new_admin, &None);
+    assert_eq!(client.pending_admin().map(|transfer| transfer.new_admin), Some(new_admin.clone()));
+    client.cancel_admin_transfer(&admin);
+    assert_eq!(client.try_accept_admin(), Err(Ok(ContractError::NoPendingAdmin)));
+
+    client.propose_admin(&admin, &new_admin, &None);
+    assert_eq!(client.accept_admin(), new_admin);
+    assert!(client.has_role(&DEFAULT_ADMIN_ROLE, &new_admin));
+    assert!(!client.has_role(&DEFAULT_ADMIN_ROLE, &admin));
+    assert_eq!(client.try_pause(&admin), Err(Ok(ContractError::MissingRole)));
+}
+
+#[test]
+fn test_deploy_collection() {
+    let env: Env = Env::default();
+    env.mock_all_auths();
+    let admin: Address = Address::generate(&env);
+    let partner: Address = Address::generate(&env);
+
+    let contract_id_nft: Address = env.register(contract_nft::WASM, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
+    let contract_id: Address = env.register(ImpactProductFactory, (&admin, contract_id_nft));
+    let client: ImpactProductFactoryClient<'_> = ImpactProductFactoryClient::new(&env, &contract_id);
+    client.grant_creator_role(&admin, &partner);
+
+    let wasm_hash: BytesN<32> = env.deployer().upload_contract_wasm(contract_nft::WASM);
+    client.set_nft_wasm_hash(&admin, &wasm_hash);
+    assert_eq!(client.nft_wasm_hash(), Some(wasm_hash.clone()));
+
+    let name: String = String::from_str(&env, "Mangrove Guardians");
+    let symbol: String = String::from_str(&env, "MANG");
+    let base_uri: String = String::from_str(&env, "ipfs://mangroves/");
+    let address: Address = client.deploy_collection(&partner, &name, &symbol, &base_uri, &BytesN::from_array(&env, &[1; 32]));
+
+    let collection_client: contract_nft::Client<'_> = contract_nft::Client::new(&env, &address);
+    assert_eq!(collection_client.name(), name);
+    assert_eq!(collection_client.symbol(), symbol);
+    assert_eq!(collection_client.base_uri(), base_uri);
+    assert!(collection_client.has_role(&DEFAULT_ADMIN_ROLE, &partner));
+    assert!(!collection_client.has_role(&DEFAULT_ADMIN_ROLE, &contract_id));
+
+    let collection: Collection = Collection { address: address.clone(), owner: partner.clone(), name: name.clone(), symbol: symbol.clone(), wasm_hash, deployed_at: env.ledger().timestamp() };
+    assert_eq!(client.get_collection(&address), Some(collection.clone()));
+    assert_eq!(client.get_collections(), vec![&env, collection.clone()]);
+    assert_eq!(client.get_collections_by_owner(&partner), vec![&env, collection]);
+    assert_eq!(client.get_collections_by_owner(&admin).len(), 0);
+
+    let second: Address = client.deploy_collection(&admin, &name, &symbol, &base_uri, &BytesN::from_array(&env, &[2; 32]));
+    assert_ne!(second, address);
+    assert_eq!(client.get_collections().len(), 2);
+    assert_eq!(client.get_collections_by_owner(&admin).get(0).map(|collection| collection.address), Some(second));
+}
+
+#[test]
+fn test_deploy_collection_errors() {
+    let env: Env = Env::default();
+    env.mock_all_auths();
+    let admin: Address = Address::generate(&env);
+    let partner: Address = Address::generate(&env);
+
+    let contract_id_nft: Address = env.register(contract_nft::WASM, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
+    let contract_id: Address = env.register(ImpactProductFactory, (&admin, contract_id_nft));
+    let client: ImpactProductFactoryClient<'_> = ImpactProductFactoryClient::new(&env, &contract_id);
+    let name: String = String::from_str(&env, "Mangrove Guardians");
+    let symbol: String = String::from_str(&env, "MANG");
+    let base_uri: String = String::from_str(&env, "ipfs://mangroves/");
+    let salt: BytesN<32> = BytesN::from_array(&env, &[1; 32]);
+
+    assert_eq!(client.try_deploy_collection(&partner, &name, &symbol, &base_uri, &salt), Err(Ok(ContractError::MissingRole)));
+    client.grant_creator_role(&admin, &partner);
+    assert_eq!(client.try_deploy_collection(&partner, &name, &symbol, &base_uri, &salt), Err(Ok(ContractError::NftWasmHashNotSet)));
+    assert_eq!(client.try_deploy_collection(&partner, &String::from_str(&env, ""), &symbol, &base_uri, &salt), Err(Ok(ContractError::InvalidCollectionMetadata)));
+
+    let wasm_hash: BytesN<32> = BytesN::from_array(&env, &[7; 32]);
+    assert_eq!(client.try_set_nft_wasm_hash(&partner, &wasm_hash), Err(Ok(ContractError::MissingRole)));
+    assert_eq!(client.nft_wasm_hash(), None);
+
+    client.pause(&admin);
+    assert_eq!(client.try_deploy_collection(&partner, &name, &symbol, &base_uri, &salt), Err(Ok(ContractError::ContractPaused)));
+    assert_eq!(client.get_collections().len(), 0);
+}
+
+#[test]
+fn test_upgrade_and_migrate() {
+    let env: Env = Env::default();
+    env.mock_all_auths();
+    let admin: Address = Address::generate(&env);
+
+    let contract_id_nft: Address = env.register(contract_nft::WASM, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
+    let contract_id: Address = env.register(ImpactProductFactory, (&admin, contract_id_nft.clone()));
+    let client: ImpactProductFactoryClient<'_> = ImpactProductFactoryClient::new(&env, &contract_id);
+    let not_admin: Address = Address::generate(&env);
+    let creator: Address = Address::generate(&env);
+    assert_eq!(client.schema_version(), 3);
+
+    // Before version 3 each role's holders were one instance list
+    env.as_contract(&contract_id, || {
+        env.storage().instance().remove(&DataKey::SchemaVersion);
+        env.storage().persistent().remove(&AccessKey::RoleMember(DEFAULT_ADMIN_ROLE, admin.clone()));
+        env.storage().instance().remove(&AccessKey::RoleMemberCount(DEFAULT_ADMIN_ROLE));
+        env.storage().instance().set(&AccessKey::RoleMembers(DEFAULT_ADMIN_ROLE), &vec![&env, admin.clone()]);
+        env.storage().instance().set(&AccessKey::RoleMembers(CREATOR_ROLE), &vec![&env, creator.clone()]);
+    });
+    assert_eq!(client.schema_version(), 0);
+    assert!(!client.has_role(&CREATOR_ROLE, &creator));
+    assert_eq!(client.try_migrate(&not_admin), Err(Ok(ContractError::MissingRole)));
+    assert_eq!(client.migrate(&admin), 3);
+    assert_eq!(client.schema_version(), 3);
+    assert!(client.has_role(&CREATOR_ROLE, &creator));
+    assert_eq!(client.get_role_member_count(&DEFAULT_ADMIN_ROLE), 1);
+
+    let new_wasm_hash: BytesN<32> = env.deployer().upload_contract_wasm(contract_factory::WASM);
+    assert_eq!(client.try_upgrade(&not_admin, &new_wasm_hash), Err(Ok(ContractError::MissingRole)));
+    client.upgrade(&admin, &new_wasm_hash);
+
+    env.as_contract(&contract_id, || {
+        assert_eq!(env.storage().instance().get::<DataKey, Address>(&DataKey::ImpactProductNft), Some(contract_id_nft));
+        assert_eq!(env.storage().instance().get::<DataKey, u32>(&DataKey::SchemaVersion), Some(3));
+        assert_eq!(env.storage().persistent().get::<DataKey, Vec<String>>(&DataKey::ImpactCategories).map(|categories| categories.len()), Some(7));
+    });
+}
+
+#[test]
+fn test_migrate_legacy_categories() {
+    let env: Env = Env::default();
+    env.mock_all_auths();
+    let admin: Address = Address::generate(&env);
+
+    let contract_id_nft: Address = env.register(contract_nft::WASM, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
+    let contract_id: Address = env.register(ImpactProductFactory, (&admin, contract_id_nft));
+    let client: ImpactProductFactoryClient<'_> = ImpactProductFactoryClient::new(&env, &contract_id);
+
+    // Recreate a version 1 layout, including a name whose parameters were already removed
+    let trees: String = String::from_str(&env, "Tree preservation");
+    let orphan: String = String::from_str(&env, "Eco tourism");
+    let defaults: Vec<String> = client.get_supported_categories();
+    env.as_contract(&contract_id, || {
+        for name in defaults.iter() {
+            env.storage().persistent().remove(&DataKey::Category(name));
+        }
+        let mut params: Map<String, LegacyImpactParams> = Map::new(&env);
+        params.set(trees.clone(), LegacyImpactParams { category: trees.clone(), base_multiplier: 2500, verified: false });
+        env.storage().persistent().set(&DataKey::ImpactParameters, &params);
+        env.storage().persistent().set(&DataKey::ImpactCategories, &vec![&env, trees.clone(), orphan.clone()]);
+        env.storage().instance().set(&DataKey::SchemaVersion, &1u32);
+    });
+
+    assert_eq!(client.migrate(&admin), 3);
+    let category: ImpactCategory = client.get_category(&trees).unwrap();
+    assert_eq!((category.base_multiplier, category.status), (2500, CategoryStatus::Active));
+    assert_eq!(client.get_supported_categories(), vec![&env, trees]);
+    assert_eq!(client.get_category(&orphan), None);
+    env.as_contract(&contract_id, || {
+        assert!(!env.storage().persistent().has(&DataKey::ImpactParameters));
+    });
+}
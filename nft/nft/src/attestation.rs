@@ -0,0 +1,70 @@
+use soroban_sdk::{contracttype, Address, Env, String, Vec};
+
+use crate::{ttl, ContractError, DataKey};
+
+pub const DEFAULT_QUORUM: u32 = 5;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Attestation {
+    pub validator: Address,
+    pub approved: bool,
+    pub evidence_uri: String,
+    pub timestamp: u64
+}
+
+pub fn get_validators(env: &Env) -> Vec<Address> {
+    env.storage().instance().get(&DataKey::Validators).unwrap_or_else(|| Vec::new(env))
+}
+
+pub fn set_validators(env: &Env, validators: &Vec<Address>) {
+    env.storage().instance().set(&DataKey::Validators, validators);
+}
+
+pub fn is_validator(env: &Env, validator: &Address) -> bool {
+    get_validators(env).contains(validator)
+}
+
+pub fn get_quorum(env: &Env) -> u32 {
+    env.storage().instance().get(&DataKey::QuorumThreshold).unwrap_or(DEFAULT_QUORUM)
+}
+
+pub fn set_quorum(env: &Env, threshold: u32) {
+    env.storage().instance().set(&DataKey::QuorumThreshold, &threshold);
+}
+
+pub fn get_attestations(env: &Env, token_id: u32) -> Vec<Attestation> {
+    env.storage().persistent().get(&DataKey::Attestations(token_id)).unwrap_or_else(|| Vec::new(env))
+}
+
+/// Stores a new attestation for `token_id`. A validator can only attest once per token.
+pub fn add_attestation(env: &Env, token_id: u32, attestation: Attestation) -> Result<(), ContractError> {
+    let mut attestations: Vec<Attestation> = get_attestations(env, token_id);
+    for existing in attestations.iter() {
+        if existing.validator == attestation.validator {
+            return Err(ContractError::AlreadyAttested);
+        }
+    }
+    attestations.push_back(attestation);
+    env.storage().persistent().set(&DataKey::Attestations(token_id), &attestations);
+    ttl::extend_persistent(env, &DataKey::Attestations(token_id));
+    Ok(())
+}
+
+/// Drops every attestation for `token_id`, so the token has to be verified again.
+pub fn clear_attestations(env: &Env, token_id: u32) {
+    env.storage().persistent().remove(&DataKey::Attestations(token_id));
+}
+
+/// Counts approvals coming from validators that are still in the registry, so
+/// removing a validator also withdraws the weight of their past votes.
+pub fn count_approvals(env: &Env, token_id: u32) -> u32 {
+    let validators: Vec<Address> = get_validators(env);
+    let mut approvals: u32 = 0;
+    for attestation in get_attestations(env, token_id).iter() {
+        if attestation.approved && validators.contains(&attestation.validator) {
+            approvals += 1;
+        }
+    }
+    approvals
+}
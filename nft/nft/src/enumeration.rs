@@ -0,0 +1,144 @@
+use soroban_sdk::{contracttype, Address, Env, String, Vec};
+
+use crate::{storage, ImpactData};
+
+/// Most token IDs a single page returns.
+pub const MAX_PAGE_SIZE: u32 = 100;
+/// Most index entries `query` inspects per call, so sparse filters stay within
+/// transaction resource limits. Callers continue from `next_cursor`.
+pub const MAX_SCAN_SIZE: u32 = 200;
+
+/// Criteria for `query_tokens`. Unset fields match every token; price bounds are
+/// inclusive.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TokenFilter {
+    pub owner: Option<Address>,
+    pub creator: Option<Address>,
+    pub category: Option<String>,
+    pub verified: Option<bool>,
+    pub min_price: Option<u128>,
+    pub max_price: Option<u128>
+}
+
+/// A page of matching token IDs. `next_cursor` is the `start` to pass for the next page,
+/// or `None` once the index has been exhausted.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TokenPage {
+    pub token_ids: Vec<u32>,
+    pub next_cursor: Option<u32>
+}
+
+/// The narrowest index a filter can be answered from.
+enum Source {
+    Owner(Address),
+    Creator(Address),
+    Category(String),
+    All
+}
+
+impl Source {
+    fn for_filter(filter: &TokenFilter) -> Source {
+        if let Some(owner) = &filter.owner {
+            return Source::Owner(owner.clone());
+        }
+        if let Some(creator) = &filter.creator {
+            return Source::Creator(creator.clone());
+        }
+        if let Some(category) = &filter.category {
+            return Source::Category(category.clone());
+        }
+        Source::All
+    }
+
+    fn len(&self, env: &Env) -> u32 {
+        match self {
+            Source::Owner(owner) => storage::get_owner_token_count(env, owner),
+            Source::Creator(creator) => storage::get_creator_token_count(env, creator),
+            Source::Category(category) => storage::get_category_token_count(env, category),
+            Source::All => storage::get_token_count(env)
+        }
+    }
+
+    fn get(&self, env: &Env, index: u32) -> Option<u32> {
+        match self {
+            Source::Owner(owner) => storage::get_owner_token(env, owner, index),
+            Source::Creator(creator) => storage::get_creator_token(env, creator, index),
+            Source::Category(category) => storage::get_category_token(env, category, index),
+            Source::All => Some(index + 1)
+        }
+    }
+}
+
+pub fn tokens_of_owner(env: &Env, owner: &Address, start: u32, limit: u32) -> Vec<u32> {
+    page(env, &Source::Owner(owner.clone()), start, limit)
+}
+
+pub fn tokens_of_creator(env: &Env, creator: &Address, start: u32, limit: u32) -> Vec<u32> {
+    page(env, &Source::Creator(creator.clone()), start, limit)
+}
+
+pub fn tokens_of_category(env: &Env, category: &String, start: u32, limit: u32) -> Vec<u32> {
+    page(env, &Source::Category(category.clone()), start, limit)
+}
+
+/// Up to `limit` entries of `source` starting at index `start`.
+fn page(env: &Env, source: &Source, start: u32, limit: u32) -> Vec<u32> {
+    let end: u32 = core::cmp::min(start.saturating_add(limit.min(MAX_PAGE_SIZE)), source.len(env));
+    let mut token_ids: Vec<u32> = Vec::new(env);
+    for index in start..end {
+        token_ids.push_back(source.get(env, index).expect("token index out of sync"));
+    }
+    token_ids
+}
+
+/// Scans the narrowest index for `filter` from `start`, returning up to `limit` live
+/// tokens that match every criterion.
+pub fn query(env: &Env, filter: &TokenFilter, start: u32, limit: u32) -> TokenPage {
+    let source: Source = Source::for_filter(filter);
+    let limit: u32 = limit.min(MAX_PAGE_SIZE);
+    let len: u32 = source.len(env);
+    let scan_end: u32 = core::cmp::min(start.saturating_add(MAX_SCAN_SIZE), len);
+
+    let mut token_ids: Vec<u32> = Vec::new(env);
+    let mut cursor: u32 = start;
+    while cursor < scan_end && token_ids.len() < limit {
+        let token_id: u32 = source.get(env, cursor).expect("token index out of sync");
+        if matches(env, filter, token_id) {
+            token_ids.push_back(token_id);
+        }
+        cursor += 1;
+    }
+    TokenPage { token_ids, next_cursor: if cursor < len { Some(cursor) } else { None } }
+}
+
+fn matches(env: &Env, filter: &TokenFilter, token_id: u32) -> bool {
+    let owner: Address = match storage::get_owner(env, token_id) {
+        Some(owner) => owner,
+        None => return false
+    };
+    if filter.owner.as_ref().is_some_and(|expected| *expected != owner) {
+        return false;
+    }
+    if filter.creator.is_some() && storage::get_token_creator(env, token_id) != filter.creator {
+        return false;
+    }
+    let impact_data: ImpactData = match storage::get_impact_data(env, token_id) {
+        Some(impact_data) => impact_data,
+        None => return false
+    };
+    if filter.category.as_ref().is_some_and(|category| *category != impact_data.category) {
+        return false;
+    }
+    if filter.verified.is_some_and(|verified| verified != impact_data.verified) {
+        return false;
+    }
+    if filter.min_price.is_some() || filter.max_price.is_some() {
+        let price: u128 = storage::get_token_price(env, token_id).unwrap_or(0);
+        if filter.min_price.is_some_and(|min_price| price < min_price) || filter.max_price.is_some_and(|max_price| price > max_price) {
+            return false;
+        }
+    }
+    true
+}
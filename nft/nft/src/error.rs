@@ -0,0 +1,94 @@
+use access_control::AccessControlError;
+use soroban_sdk::contracterror;
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum ContractError {
+    // Supply
+    SupplyCapReached = 1,
+    CategoryCapReached = 2,
+    CapBelowIssued = 3,
+
+    // Authorization
+    MissingRole = 4,
+    RoleNotHeld = 5,
+    LastAdmin = 6,
+    NotOwner = 7,
+    NotApproved = 8,
+
+    // Tokens
+    TokenNotFound = 9,
+    TokenRetired = 10,
+    TokenNotRetired = 11,
+    NoTokensFound = 12,
+    InvalidLiveUntilLedger = 13,
+
+    // Impact data
+    EmptyCategory = 14,
+    InvalidImpactValue = 15,
+    InvalidPrice = 16,
+    EmptyBeneficiary = 17,
+
+    // Attestation
+    ValidatorAlreadyRegistered = 18,
+    ValidatorNotRegistered = 19,
+    AlreadyAttested = 20,
+    AlreadyVerified = 21,
+    EmptyEvidence = 22,
+    InvalidQuorum = 23,
+
+    // General
+    ContractPaused = 24,
+    InvalidTtlConfig = 25,
+
+    // Royalties
+    InvalidRoyalty = 26,
+    NotCreator = 27,
+
+    // Metadata
+    MetadataTooLarge = 28,
+    ReservedAttributeName = 43,
+
+    // Governance
+    NoPendingUpdate = 29,
+    VerificationNotEditable = 41,
+
+    // Vintages
+    InvalidPeriod = 30,
+    InvalidExpiry = 31,
+
+    // Scoring
+    InvalidScoringConfig = 32,
+    UnknownScoringVersion = 33,
+
+    // Batches
+    InvalidBatchSize = 34,
+    BatchLengthMismatch = 35,
+
+    // Enumeration
+    IndexOutOfBounds = 36,
+
+    // Upgrades
+    UnsupportedSchemaVersion = 37,
+
+    // Admin transfer
+    NoPendingAdmin = 39,
+    AdminTransferExpired = 40,
+
+    // Projects
+    TokenAlreadyInProject = 42,
+}
+
+impl From<AccessControlError> for ContractError {
+    fn from(error: AccessControlError) -> Self {
+        match error {
+            AccessControlError::MissingRole => ContractError::MissingRole,
+            AccessControlError::RoleNotHeld => ContractError::RoleNotHeld,
+            AccessControlError::LastAdmin => ContractError::LastAdmin,
+            AccessControlError::NoPendingAdmin => ContractError::NoPendingAdmin,
+            AccessControlError::AdminTransferExpired => ContractError::AdminTransferExpired,
+            AccessControlError::InvalidLiveUntilLedger => ContractError::InvalidLiveUntilLedger,
+        }
+    }
+}
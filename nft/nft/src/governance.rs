@@ -0,0 +1,69 @@
+use soroban_sdk::{contracttype, Address, Env, Vec};
+
+use crate::{ttl, DataKey, ImpactData};
+
+/// A change to a token's impact data waiting for VERIFIER review.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ImpactUpdateProposal {
+    pub proposer: Address,
+    pub impact_data: ImpactData,
+    pub timestamp: u64
+}
+
+/// Impact data a token carried before an update replaced it.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ImpactDataVersion {
+    pub version: u32,
+    pub impact_data: ImpactData,
+    pub replaced_at: u64
+}
+
+/// Fields whose change alters the impact being claimed. Changing any of them needs review
+/// and voids a previous verification.
+pub fn changes_material_fields(current: &ImpactData, proposed: &ImpactData) -> bool {
+    current.category != proposed.category
+        || current.impact_value != proposed.impact_value
+        || current.start_date != proposed.start_date
+        || current.end_date != proposed.end_date
+}
+
+pub fn get_pending(env: &Env, token_id: u32) -> Option<ImpactUpdateProposal> {
+    env.storage().persistent().get(&DataKey::PendingUpdate(token_id))
+}
+
+pub fn set_pending(env: &Env, token_id: u32, proposal: &ImpactUpdateProposal) {
+    let key: DataKey = DataKey::PendingUpdate(token_id);
+    env.storage().persistent().set(&key, proposal);
+    ttl::extend_persistent(env, &key);
+}
+
+pub fn remove_pending(env: &Env, token_id: u32) {
+    env.storage().persistent().remove(&DataKey::PendingUpdate(token_id));
+}
+
+pub fn get_version_count(env: &Env, token_id: u32) -> u32 {
+    env.storage().persistent().get(&DataKey::ImpactVersionCount(token_id)).unwrap_or(0)
+}
+
+/// Appends `impact_data` to the token's history as the version being replaced.
+pub fn add_version(env: &Env, token_id: u32, impact_data: &ImpactData) {
+    let version: u32 = get_version_count(env, token_id);
+    let entry: ImpactDataVersion = ImpactDataVersion { version, impact_data: impact_data.clone(), replaced_at: env.ledger().timestamp() };
+    let version_key: DataKey = DataKey::ImpactVersion(token_id, version);
+    env.storage().persistent().set(&version_key, &entry);
+    ttl::extend_persistent(env, &version_key);
+    let count_key: DataKey = DataKey::ImpactVersionCount(token_id);
+    env.storage().persistent().set(&count_key, &(version + 1));
+    ttl::extend_persistent(env, &count_key);
+}
+
+pub fn get_history(env: &Env, token_id: u32) -> Vec<ImpactDataVersion> {
+    let mut history: Vec<ImpactDataVersion> = Vec::new(env);
+    for version in 0..get_version_count(env, token_id) {
+        let entry: ImpactDataVersion = env.storage().persistent().get(&DataKey::ImpactVersion(token_id, version)).expect("impact history out of sync");
+        history.push_back(entry);
+    }
+    history
+}
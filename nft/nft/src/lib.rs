@@ -1,7 +1,33 @@
 #![no_std]
+mod attestation;
 mod contract;
+mod enumeration;
+mod error;
+mod governance;
+mod metadata;
+mod migration;
+mod pause;
+mod project;
+mod retirement;
+mod royalty;
+mod scoring;
+mod storage;
+mod ttl;
+mod vintage;
 
-use soroban_sdk::{contract, contractimpl, contracttype, symbol_short, Address, Bytes, Env, Map, String, Vec};
+use access_control::{AdminTransfer, DEFAULT_ADMIN_ROLE};
+use soroban_sdk::{contract, contractimpl, contracttype, symbol_short, Address, BytesN, Env, Map, String, Symbol, Vec};
+
+pub use attestation::Attestation;
+pub use enumeration::{TokenFilter, TokenPage};
+pub use error::ContractError;
+pub use governance::{ImpactDataVersion, ImpactUpdateProposal};
+pub use pause::PauseScope;
+pub use project::ProjectTotals;
+pub use retirement::RetirementCertificate;
+pub use royalty::RoyaltyInfo;
+pub use scoring::{DurationTier, ScoringConfig};
+pub use ttl::TtlConfig;
 
 #[contract]
 pub struct ImpactProductNFT;
@@ -19,78 +45,196 @@ pub struct ImpactData {
     pub metadata_uri: String
 }
 
+/// Value of a category-specific attribute recorded on a token at mint.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AttributeValue {
+    Text(String),
+    Integer(i128),
+    Boolean(bool)
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ApprovalData {
+    pub approved: Address,
+    pub live_until_ledger: u32
+}
+
 #[contracttype]
 pub enum DataKey {
     IsPaused,
-    Owner(u128),
+    Owner(u32),
     TokenCount,
-    Approvals(u128),
+    MaxSupply,
+    Balance(Address),
+    Approval(u32),
+    ApprovalForAll(Address, Address),
     BaseURI,
-    ADMIN,
-    MINTER,
-    VERIFIER,
-    PAUSER,
-    TokenURIs,
-    ImpactData,
-    TokenPrices,
-    CreatorTokens,
-    CategoryTokens
+    TokenURI(u32),
+    ImpactData(u32),
+    Attributes(u32),
+    TokenPrice(u32),
+    CreatorTokenCount(Address),
+    CreatorToken(Address, u32),
+    CreatorIndex(u32),
+    TokenCreator(u32),
+    CategoryTokenCount(String),
+    CategoryToken(String, u32),
+    CategoryIndex(u32),
+    CategoryCap(String),
+    CategoryIssued(String),
+    Validators,
+    QuorumThreshold,
+    Attestations(u32),
+    TtlConfig,
+    Retirement(u32),
+    RetireeTokenCount(Address),
+    RetireeToken(Address, u32),
+    RetiredImpact(String),
+    RetiredCount,
+    DefaultRoyalty,
+    TokenRoyalty(u32),
+    PendingUpdate(u32),
+    ImpactVersionCount(u32),
+    ImpactVersion(u32, u32),
+    VintageTokenCount(u32),
+    VintageToken(u32, u32),
+    VintageIndex(u32),
+    Expiry(u32),
+    ScoringVersion,
+    ScoringConfig(u32),
+    TokenScoringVersion(u32),
+    OwnerTokenCount(Address),
+    OwnerToken(Address, u32),
+    OwnerIndex(u32),
+    OwnerIndexCursor,
+    PausedScopes,
+    SchemaVersion,
+    Name,
+    Symbol,
+    TokenProject(u32),
+    ProjectTotals(u32)
 }
 
+pub const MINTER_ROLE: Symbol = symbol_short!("MINTER");
+pub const VERIFIER_ROLE: Symbol = symbol_short!("VERIFIER");
+pub const PAUSER_ROLE: Symbol = symbol_short!("PAUSER");
+
+/// Most tokens `batch_create_impact_products` mints in one call, keeping a batch within
+/// transaction resource limits.
+pub const MAX_BATCH_SIZE: u32 = 50;
+
 #[contractimpl]
 impl ImpactProductNFT {
-    const SUPPLY: u128 = 1000;
     const NAME: &'static str = "Regen Bazaar Impact Product";
     const SYMBOL: &'static str = "RIP";
 
-    pub fn __constructor(env: Env, admin: Address, base_token_uri: String) {
-        env.storage().instance().set(&DataKey::IsPaused, &false);
-        env.storage().instance().set(&DataKey::ADMIN, &admin);
-        env.storage().instance().set(&DataKey::MINTER, &admin);
-        env.storage().instance().set(&DataKey::VERIFIER, &admin);
-        env.storage().instance().set(&DataKey::PAUSER, &admin);
+    /// `max_supply` caps the number of tokens that can ever be minted; `None` leaves the
+    /// collection unlimited. `name` and `symbol` brand the collection.
+    pub fn __constructor(env: Env, admin: Address, base_token_uri: String, max_supply: Option<u32>, name: String, symbol: String) {
+        pause::set_paused(&env, false);
+        access_control::_grant_role(&env, &DEFAULT_ADMIN_ROLE, &admin, &admin);
+        access_control::_grant_role(&env, &MINTER_ROLE, &admin, &admin);
+        access_control::_grant_role(&env, &VERIFIER_ROLE, &admin, &admin);
+        access_control::_grant_role(&env, &PAUSER_ROLE, &admin, &admin);
 
         env.storage().instance().set(&DataKey::BaseURI, &base_token_uri);
+        env.storage().instance().set(&DataKey::Name, &name);
+        env.storage().instance().set(&DataKey::Symbol, &symbol);
+        storage::set_max_supply(&env, max_supply);
 
-        let token_uris: Map<u128, String> = Map::new(&env);
-        env.storage().persistent().set(&DataKey::TokenURIs, &token_uris);
+        attestation::set_validators(&env, &Vec::new(&env));
+        attestation::set_quorum(&env, attestation::DEFAULT_QUORUM);
+        migration::set_schema_version(&env, migration::SCHEMA_VERSION);
+        ttl::extend_instance(&env);
+    }
 
-        let impact_data: Map<u128, ImpactData> = Map::new(&env);
-        env.storage().persistent().set(&DataKey::ImpactData, &impact_data);
+    pub fn token_count(env: Env) -> u32 {
+        storage::get_token_count(&env)
+    }
 
-        let token_prices: Map<u128, u128> = Map::new(&env);
-        env.storage().persistent().set(&DataKey::TokenPrices, &token_prices);
+    pub fn max_supply(env: Env) -> Option<u32> {
+        storage::get_max_supply(&env)
+    }
 
-        let creator_tokens: Map<Address, Vec<u128>> = Map::new(&env);
-        env.storage().persistent().set(&DataKey::CreatorTokens, &creator_tokens);
+    /// Changes the collection supply cap. `None` makes the collection unlimited. The cap
+    /// cannot be set below the number of tokens already minted.
+    pub fn set_max_supply(env: Env, admin: Address, max_supply: Option<u32>) -> Result<(), ContractError> {
+        access_control::require_role(&env, &DEFAULT_ADMIN_ROLE, &admin)?;
+        if let Some(cap) = max_supply && cap < storage::get_token_count(&env) {
+            return Err(ContractError::CapBelowIssued);
+        }
+        storage::set_max_supply(&env, max_supply);
+        ttl::extend_instance(&env);
+        env.events().publish((symbol_short!("MaxSupply"),), max_supply);
+        Ok(())
+    }
 
-        let category_tokens: Map<String, Vec<u128>> = Map::new(&env);
-        env.storage().persistent().set(&DataKey::CategoryTokens, &category_tokens);
+    /// Tokens that can still be minted, or `None` if the collection is unlimited.
+    pub fn remaining_supply(env: Env) -> Option<u32> {
+        storage::get_max_supply(&env).map(|max_supply| max_supply - storage::get_token_count(&env))
     }
 
-    pub fn token_count(env: Env) -> u128 {
-        let data: u128 = env.storage().persistent().get(&DataKey::TokenCount).unwrap_or_else(|| {0});
-        data
+    pub fn category_cap(env: Env, category: String) -> Option<u32> {
+        storage::get_category_cap(&env, &category)
+    }
+
+    /// Caps how many tokens can be issued in `category`. `None` removes the cap. The cap
+    /// cannot be set below the number of tokens already issued in the category.
+    pub fn set_category_cap(env: Env, admin: Address, category: String, cap: Option<u32>) -> Result<(), ContractError> {
+        access_control::require_role(&env, &DEFAULT_ADMIN_ROLE, &admin)?;
+        if let Some(cap) = cap && cap < storage::get_category_issued(&env, &category) {
+            return Err(ContractError::CapBelowIssued);
+        }
+        storage::set_category_cap(&env, &category, cap);
+        ttl::extend_instance(&env);
+        env.events().publish((symbol_short!("CatCap"), category), cap);
+        Ok(())
+    }
+
+    /// Tokens that can still be issued in `category`, or `None` if it has no cap.
+    pub fn remaining_category_supply(env: Env, category: String) -> Option<u32> {
+        storage::get_category_cap(&env, &category).map(|cap| cap - storage::get_category_issued(&env, &category))
+    }
+
+    /// Number of minted tokens that have not been retired.
+    pub fn circulating_supply(env: Env) -> u32 {
+        storage::get_token_count(&env) - retirement::get_retired_count(&env)
     }
 
-    pub fn owner_of(env: Env, token_id: u128) -> Address {
-        env.storage().persistent().get(&DataKey::Owner(token_id)).unwrap_or_else(|| {
-            Address::from_string_bytes(&Bytes::from_slice(&env, &[0; 32]))
-        })
+    pub fn owner_of(env: Env, token_id: u32) -> Result<Address, ContractError> {
+        storage::get_owner(&env, token_id).ok_or(ContractError::TokenNotFound)
     }
 
+    /// Collections deployed before names were configurable report the original branding.
     pub fn name(env: Env) -> String {
-        String::from_str(&env, Self::NAME)
+        env.storage().instance().get(&DataKey::Name).unwrap_or_else(|| String::from_str(&env, Self::NAME))
     }
 
     pub fn symbol(env: Env) -> String {
-        String::from_str(&env, Self::SYMBOL)
+        env.storage().instance().get(&DataKey::Symbol).unwrap_or_else(|| String::from_str(&env, Self::SYMBOL))
     }
 
-    pub fn token_uri(env: Env, token_id: u128) -> String {
-        let token_uris: Map<u128, String> = env.storage().persistent().get(&DataKey::TokenURIs).expect("should contain token uris");
-        let token_uri: String = token_uris.get(token_id).expect("should contain token uri");
-        token_uri
+    /// The token's own metadata URI, or `base_uri` followed by the token id when it was
+    /// minted without one.
+    pub fn token_uri(env: Env, token_id: u32) -> Result<String, ContractError> {
+        if let Some(uri) = storage::get_token_uri(&env, token_id) {
+            return Ok(uri);
+        }
+        if storage::get_impact_data(&env, token_id).is_none() {
+            return Err(ContractError::TokenNotFound);
+        }
+        metadata::fallback_uri(&env, &Self::base_uri(env.clone()), token_id)
+    }
+
+    /// Metadata JSON rendered on-chain from the token's impact data, so wallets can show
+    /// its impact attributes without fetching anything off-chain.
+    pub fn token_metadata_json(env: Env, token_id: u32) -> Result<String, ContractError> {
+        let item: ImpactData = storage::get_impact_data(&env, token_id).ok_or(ContractError::TokenNotFound)?;
+        let score: u128 = Self::calculate_impact_score(env.clone(), token_id)?;
+        let attributes: Map<String, AttributeValue> = storage::get_attributes(&env, token_id).unwrap_or_else(|| Map::new(&env));
+        metadata::render_json(&env, &Self::name(env.clone()), token_id, &item, &attributes, score)
     }
 
     pub fn base_uri(env: Env) -> String {
@@ -98,265 +242,910 @@ impl ImpactProductNFT {
         data
     }
 
-    pub fn pause(env: Env) {
-        let pauser: Address = env.storage().instance().get(&DataKey::PAUSER).expect("PAUSER not found");
-        pauser.require_auth();
-        env.storage().instance().set(&DataKey::IsPaused, &true);
+    pub fn grant_role(env: Env, caller: Address, role: Symbol, account: Address) -> Result<(), ContractError> {
+        ttl::extend_instance(&env);
+        access_control::grant_role(&env, &caller, &role, &account)?;
+        Ok(())
     }
 
-    pub fn unpause(env: Env) {
-        let pauser: Address = env.storage().instance().get(&DataKey::PAUSER).expect("PAUSER not found");
-        pauser.require_auth();
-        env.storage().instance().set(&DataKey::IsPaused, &false);
+    pub fn revoke_role(env: Env, caller: Address, role: Symbol, account: Address) -> Result<(), ContractError> {
+        ttl::extend_instance(&env);
+        access_control::revoke_role(&env, &caller, &role, &account)?;
+        Ok(())
     }
 
-    pub fn create_impact_product(env: Env, to: Address, impact_data: ImpactData, price: u128) -> u128 {
-        let minter: Address = env.storage().instance().get(&DataKey::MINTER).expect("MINTER not found");
-        minter.require_auth();
-        let is_paused: bool = env.storage().instance().get(&DataKey::IsPaused).expect("contains value");
-        if is_paused {
-            panic!("contract paused")
+    pub fn renounce_role(env: Env, account: Address, role: Symbol) -> Result<(), ContractError> {
+        ttl::extend_instance(&env);
+        access_control::renounce_role(&env, &account, &role)?;
+        Ok(())
+    }
+
+    pub fn set_role_admin(env: Env, caller: Address, role: Symbol, admin_role: Symbol) -> Result<(), ContractError> {
+        ttl::extend_instance(&env);
+        access_control::set_role_admin(&env, &caller, &role, &admin_role)?;
+        Ok(())
+    }
+
+    pub fn has_role(env: Env, role: Symbol, account: Address) -> bool {
+        access_control::has_role(&env, &role, &account)
+    }
+
+    pub fn get_role_member_count(env: Env, role: Symbol) -> u32 {
+        access_control::get_role_member_count(&env, &role)
+    }
+
+    pub fn get_role_admin(env: Env, role: Symbol) -> Symbol {
+        access_control::get_role_admin(&env, &role)
+    }
+
+    /// Proposes `new_admin` to take over `admin`'s `ADMIN` role. Nothing changes until
+    /// `new_admin` calls `accept_admin`, which must happen by `live_until_ledger` if set.
+    pub fn propose_admin(env: Env, admin: Address, new_admin: Address, live_until_ledger: Option<u32>) -> Result<(), ContractError> {
+        ttl::extend_instance(&env);
+        access_control::propose_admin(&env, &admin, &new_admin, live_until_ledger)?;
+        Ok(())
+    }
+
+    /// Moves the `ADMIN` role from the proposer to the pending admin, who must authorize.
+    pub fn accept_admin(env: Env) -> Result<Address, ContractError> {
+        ttl::extend_instance(&env);
+        Ok(access_control::accept_admin(&env)?)
+    }
+
+    /// Withdraws the pending admin transfer. Callable by any `ADMIN` or the pending admin.
+    pub fn cancel_admin_transfer(env: Env, caller: Address) -> Result<(), ContractError> {
+        ttl::extend_instance(&env);
+        access_control::cancel_admin_transfer(&env, &caller)?;
+        Ok(())
+    }
+
+    pub fn pending_admin(env: Env) -> Option<AdminTransfer> {
+        access_control::get_pending_admin(&env)
+    }
+
+    /// Halts every state-changing entrypoint except role management, pause controls,
+    /// approval revocations and admin configuration.
+    pub fn pause(env: Env, pauser: Address) -> Result<(), ContractError> {
+        access_control::require_role(&env, &PAUSER_ROLE, &pauser)?;
+        pause::set_paused(&env, true);
+        ttl::extend_instance(&env);
+        env.events().publish((symbol_short!("paused"),), pauser);
+        Ok(())
+    }
+
+    /// Lifts the global pause. Scopes paused individually stay paused.
+    pub fn unpause(env: Env, pauser: Address) -> Result<(), ContractError> {
+        access_control::require_role(&env, &PAUSER_ROLE, &pauser)?;
+        pause::set_paused(&env, false);
+        ttl::extend_instance(&env);
+        env.events().publish((symbol_short!("unpaused"),), pauser);
+        Ok(())
+    }
+
+    pub fn pause_scope(env: Env, pauser: Address, scope: PauseScope) -> Result<(), ContractError> {
+        access_control::require_role(&env, &PAUSER_ROLE, &pauser)?;
+        ttl::extend_instance(&env);
+        if pause::set_scope(&env, scope, true) {
+            env.events().publish((symbol_short!("paused"), scope), pauser);
         }
-        if !(String::len(&impact_data.category) > 0) {
-            panic!("Category cannot be empty")
+        Ok(())
+    }
+
+    pub fn unpause_scope(env: Env, pauser: Address, scope: PauseScope) -> Result<(), ContractError> {
+        access_control::require_role(&env, &PAUSER_ROLE, &pauser)?;
+        ttl::extend_instance(&env);
+        if pause::set_scope(&env, scope, false) {
+            env.events().publish((symbol_short!("unpaused"), scope), pauser);
         }
-        if !(impact_data.impact_value > 0) {
-            panic!("Impact value must be positive")
+        Ok(())
+    }
+
+    pub fn is_paused(env: Env) -> bool {
+        pause::is_paused(&env)
+    }
+
+    /// Scopes currently halted, either individually or because the whole contract is paused.
+    pub fn paused_scopes(env: Env) -> Vec<PauseScope> {
+        pause::paused_scopes(&env)
+    }
+
+    pub fn create_impact_product(env: Env, minter: Address, to: Address, impact_data: ImpactData, price: u128) -> Result<u32, ContractError> {
+        Self::create_with_attributes(env.clone(), minter, to, impact_data, price, Map::new(&env))
+    }
+
+    /// Mints like `create_impact_product` and records `attributes` on the token. The minter
+    /// is responsible for validating them, as the factory does against its category schemas.
+    pub fn create_with_attributes(env: Env, minter: Address, to: Address, impact_data: ImpactData, price: u128, attributes: Map<String, AttributeValue>) -> Result<u32, ContractError> {
+        access_control::require_role(&env, &MINTER_ROLE, &minter)?;
+        pause::require_not_paused(&env, PauseScope::Minting)?;
+        ttl::extend_instance(&env);
+        Self::_create_impact_product(&env, &to, &impact_data, price, &attributes)
+    }
+
+    /// Mints one token to `to` for each entry of `impact_data`, priced at the matching
+    /// entry of `prices`. The batch either mints completely or not at all, so it fails
+    /// up front if it would exceed the collection's supply cap. Returns the new token IDs.
+    pub fn batch_create_impact_products(env: Env, minter: Address, to: Address, impact_data: Vec<ImpactData>, prices: Vec<u128>) -> Result<Vec<u32>, ContractError> {
+        let mut attributes: Vec<Map<String, AttributeValue>> = Vec::new(&env);
+        for _ in 0..impact_data.len().min(MAX_BATCH_SIZE) {
+            attributes.push_back(Map::new(&env));
+        }
+        Self::batch_create_with_attributes(env, minter, to, impact_data, prices, attributes)
+    }
+
+    /// Batch form of `create_with_attributes`; `attributes` matches
+    /// `impact_data` entry for entry.
+    pub fn batch_create_with_attributes(env: Env, minter: Address, to: Address, impact_data: Vec<ImpactData>, prices: Vec<u128>, attributes: Vec<Map<String, AttributeValue>>) -> Result<Vec<u32>, ContractError> {
+        access_control::require_role(&env, &MINTER_ROLE, &minter)?;
+        pause::require_not_paused(&env, PauseScope::Minting)?;
+        if impact_data.is_empty() || impact_data.len() > MAX_BATCH_SIZE {
+            return Err(ContractError::InvalidBatchSize);
         }
-        if !(price > 0) {
-            panic!("Price must be positive")
+        if impact_data.len() != prices.len() || impact_data.len() != attributes.len() {
+            return Err(ContractError::BatchLengthMismatch);
+        }
+        if let Some(remaining) = Self::remaining_supply(env.clone()) && remaining < impact_data.len() {
+            return Err(ContractError::SupplyCapReached);
         }
 
-        Self::mint(env.clone(), to.clone());
+        ttl::extend_instance(&env);
+        let mut token_ids: Vec<u32> = Vec::new(&env);
+        for ((item, price), token_attributes) in impact_data.iter().zip(prices.iter()).zip(attributes.iter()) {
+            token_ids.push_back(Self::_create_impact_product(&env, &to, &item, price, &token_attributes)?);
+        }
+        env.events().publish((symbol_short!("BatchMint"), to), token_ids.clone());
+        Ok(token_ids)
+    }
 
-        let mut current_id: u128 = env.storage().persistent().get(&DataKey::TokenCount).expect("Current ID not found");
-        current_id += 1;
+    pub fn get_impact_data(env: Env, token_id: u32) -> Result<ImpactData, ContractError> {
+        storage::get_impact_data(&env, token_id).ok_or(ContractError::TokenNotFound)
+    }
 
-        if String::len(&impact_data.metadata_uri) > 0 {
-            Self::_set_token_uri(env.clone(), current_id, impact_data.metadata_uri.clone());
+    /// Category-specific attributes recorded at mint; empty for tokens minted without any.
+    pub fn get_attributes(env: Env, token_id: u32) -> Result<Map<String, AttributeValue>, ContractError> {
+        if storage::get_impact_data(&env, token_id).is_none() {
+            return Err(ContractError::TokenNotFound);
         }
+        Ok(storage::get_attributes(&env, token_id).unwrap_or_else(|| Map::new(&env)))
+    }
 
-        let mut impact_data_all: Map<u128, ImpactData> = env.storage().persistent().get(&DataKey::ImpactData).expect("Impact Data Map not found");
-        impact_data_all.set(current_id, impact_data.clone());
-        env.storage().persistent().set(&DataKey::ImpactData, &impact_data_all);
+    /// Submits `new_impact_data` on behalf of the token owner. Changes limited to the
+    /// location, beneficiaries or metadata URI apply immediately; changes to the category,
+    /// impact value or dates wait for VERIFIER approval. The verified flag must match the
+    /// token's, as only the validator quorum in `verify_token` may set it. Returns true if
+    /// the update was applied, false if it is pending review.
+    pub fn update_impact_data(env: Env, token_id: u32, new_impact_data: ImpactData) -> Result<bool, ContractError> {
+        pause::require_not_paused(&env, PauseScope::Metadata)?;
+        Self::_require_not_retired(&env, token_id)?;
+        let owner: Address = Self::owner_of(env.clone(), token_id)?;
+        owner.require_auth();
+        ttl::extend_instance(&env);
+        let current: ImpactData = storage::get_impact_data(&env, token_id).ok_or(ContractError::TokenNotFound)?;
+        vintage::validate_period(&new_impact_data)?;
+        if new_impact_data.verified != current.verified {
+            return Err(ContractError::VerificationNotEditable);
+        }
 
-        let mut token_prices_all: Map<u128, u128> = env.storage().persistent().get(&DataKey::TokenPrices).expect("Token Prices Map not found");
-        token_prices_all.set(current_id, price);
-        env.storage().persistent().set(&DataKey::TokenPrices, &token_prices_all);
+        if governance::changes_material_fields(&current, &new_impact_data) {
+            let proposal: ImpactUpdateProposal = ImpactUpdateProposal { proposer: owner, impact_data: new_impact_data, timestamp: env.ledger().timestamp() };
+            governance::set_pending(&env, token_id, &proposal);
+            env.events().publish((symbol_short!("Proposed"), token_id), proposal.proposer);
+            return Ok(false);
+        }
 
-        let mut creator_tokens_all: Map<Address, Vec<u128>> = env.storage().persistent().get(&DataKey::CreatorTokens).expect("Creator Tokens Map not found");
-        let creators_tokens_user: bool = creator_tokens_all.contains_key(to.clone());
+        Self::_apply_impact_update(&env, token_id, &current, new_impact_data)?;
+        Ok(true)
+    }
 
-        if creators_tokens_user {
-            let creators_tokens_user_array: Option<Vec<u128>> = creator_tokens_all.get(to.clone());
-            let mut user_tokens_array: Vec<u128> = creators_tokens_user_array.unwrap();
-            user_tokens_array.push_back(current_id);
-            creator_tokens_all.set(to, user_tokens_array);
-            env.storage().persistent().set(&DataKey::CreatorTokens, &creator_tokens_all);
-        } else {
-            let mut user_tokens_array: Vec<u128> = Vec::new(&env);
-            user_tokens_array.push_back(current_id);
-            creator_tokens_all.set(to, user_tokens_array);
-            env.storage().persistent().set(&DataKey::CreatorTokens, &creator_tokens_all);
-        }
-
-        let mut category_tokens_all: Map<String, Vec<u128>> = env.storage().persistent().get(&DataKey::CategoryTokens).expect("Category Tokens Map not found");
-        let category_tokens: bool = category_tokens_all.contains_key(impact_data.category.clone());
-        if category_tokens {
-            let category_tokens_category: Option<Vec<u128>> = category_tokens_all.get(impact_data.category.clone());
-            let mut category_tokens_category_array: Vec<u128> = category_tokens_category.unwrap();
-            category_tokens_category_array.push_back(current_id);
-            category_tokens_all.set(impact_data.category, category_tokens_category_array);
-            env.storage().persistent().set(&DataKey::CreatorTokens, &category_tokens_all);
-        } else {
-            let mut category_tokens_array: Vec<u128> = Vec::new(&env);
-            category_tokens_array.push_back(current_id);
-            category_tokens_all.set(impact_data.category, category_tokens_array);
-            env.storage().persistent().set(&DataKey::CreatorTokens, &category_tokens_all);
+    pub fn get_pending_update(env: Env, token_id: u32) -> Option<ImpactUpdateProposal> {
+        governance::get_pending(&env, token_id)
+    }
+
+    /// Applies the pending update for `token_id`. The token keeps its current verified flag,
+    /// unless it was verified and its category, impact value or dates change, in which case
+    /// it loses its verification and its attestations.
+    pub fn approve_impact_update(env: Env, verifier: Address, token_id: u32) -> Result<(), ContractError> {
+        pause::require_not_paused(&env, PauseScope::Metadata)?;
+        access_control::require_role(&env, &VERIFIER_ROLE, &verifier)?;
+        Self::_require_not_retired(&env, token_id)?;
+        let proposal: ImpactUpdateProposal = governance::get_pending(&env, token_id).ok_or(ContractError::NoPendingUpdate)?;
+        if Self::owner_of(env.clone(), token_id)? != proposal.proposer {
+            return Err(ContractError::NotOwner);
         }
-        current_id
+        let current: ImpactData = storage::get_impact_data(&env, token_id).ok_or(ContractError::TokenNotFound)?;
+
+        ttl::extend_instance(&env);
+        governance::remove_pending(&env, token_id);
+        Self::_apply_impact_update(&env, token_id, &current, proposal.impact_data)?;
+        env.events().publish((symbol_short!("Approved"), token_id), verifier);
+        Ok(())
     }
 
-    pub fn get_impact_data(env: Env, token_id: u128) -> ImpactData {
-        let data: Map<u128, ImpactData> = env.storage().persistent().get(&DataKey::ImpactData).expect("should contain TokenPrices");
-        data.get(token_id).expect("No Token Id Found")
+    pub fn reject_impact_update(env: Env, verifier: Address, token_id: u32) -> Result<(), ContractError> {
+        pause::require_not_paused(&env, PauseScope::Metadata)?;
+        access_control::require_role(&env, &VERIFIER_ROLE, &verifier)?;
+        if governance::get_pending(&env, token_id).is_none() {
+            return Err(ContractError::NoPendingUpdate);
+        }
+        ttl::extend_instance(&env);
+        governance::remove_pending(&env, token_id);
+        env.events().publish((symbol_short!("Rejected"), token_id), verifier);
+        Ok(())
     }
 
-    pub fn update_impact_data(env: Env, token_id: u128, new_impact_data: ImpactData) -> bool{
-        let owner: Address = Self::owner_of(env.clone(), token_id);
-        owner.require_auth();
-        let mut impact_data_all: Map<u128, ImpactData> = env.storage().persistent().get(&DataKey::ImpactData).expect("should contain TokenPrices");
-        let impact_data_token: ImpactData = impact_data_all.get(token_id).expect("No ImpactData Found");
-        
-        let old_category: String = impact_data_token.category;
-        let new_category: String = new_impact_data.category.clone();
-        
-        if old_category != new_category {
-            Self::remove_from_category(env.clone(), token_id, old_category);
-            let mut category_tokens_all: Map<String, Vec<u128>> = env.storage().persistent().get(&DataKey::CategoryTokens).expect("should contain CategoryTokens");
-            let mut category_tokens: Vec<u128> = category_tokens_all.get(new_category.clone()).expect("should be an existing category");
-            category_tokens.push_back(token_id);
+    /// Every version of the token's impact data that has been replaced, oldest first.
+    pub fn get_impact_history(env: Env, token_id: u32) -> Vec<ImpactDataVersion> {
+        governance::get_history(&env, token_id)
+    }
 
-            category_tokens_all.set(new_category, category_tokens);
-            env.storage().persistent().set(&DataKey::CategoryTokens, &category_tokens_all);
+    pub fn add_validator(env: Env, verifier: Address, validator: Address) -> Result<(), ContractError> {
+        access_control::require_role(&env, &VERIFIER_ROLE, &verifier)?;
+        let mut validators: Vec<Address> = attestation::get_validators(&env);
+        if validators.contains(&validator) {
+            return Err(ContractError::ValidatorAlreadyRegistered);
         }
+        validators.push_back(validator.clone());
+        attestation::set_validators(&env, &validators);
+        ttl::extend_instance(&env);
+        env.events().publish((symbol_short!("ValAdded"),), validator);
+        Ok(())
+    }
 
-        impact_data_all.set(token_id, new_impact_data.clone());
-        env.storage().persistent().set(&DataKey::ImpactData, &impact_data_all);
+    pub fn remove_validator(env: Env, verifier: Address, validator: Address) -> Result<(), ContractError> {
+        access_control::require_role(&env, &VERIFIER_ROLE, &verifier)?;
+        let mut validators: Vec<Address> = attestation::get_validators(&env);
+        let index: u32 = validators.first_index_of(&validator).ok_or(ContractError::ValidatorNotRegistered)?;
+        validators.remove(index);
+        attestation::set_validators(&env, &validators);
+        ttl::extend_instance(&env);
+        env.events().publish((symbol_short!("ValRemove"),), validator);
+        Ok(())
+    }
 
-        if String::len(&new_impact_data.metadata_uri) > 0 {
-            Self::_set_token_uri(env, token_id, new_impact_data.metadata_uri);
+    pub fn get_validators(env: Env) -> Vec<Address> {
+        attestation::get_validators(&env)
+    }
+
+    pub fn is_validator(env: Env, validator: Address) -> bool {
+        attestation::is_validator(&env, &validator)
+    }
+
+    pub fn set_quorum(env: Env, verifier: Address, threshold: u32) -> Result<(), ContractError> {
+        access_control::require_role(&env, &VERIFIER_ROLE, &verifier)?;
+        if threshold == 0 {
+            return Err(ContractError::InvalidQuorum);
+        }
+        attestation::set_quorum(&env, threshold);
+        ttl::extend_instance(&env);
+        env.events().publish((symbol_short!("Quorum"),), threshold);
+        Ok(())
+    }
+
+    pub fn get_quorum(env: Env) -> u32 {
+        attestation::get_quorum(&env)
+    }
+
+    pub fn get_attestations(env: Env, token_id: u32) -> Vec<Attestation> {
+        attestation::get_attestations(&env, token_id)
+    }
+
+    /// Records a registered validator's approve/reject vote for `token_id`, backed by
+    /// `evidence_uri`. Once approvals reach the quorum the token is marked verified.
+    /// Returns whether the token is verified after this vote.
+    pub fn verify_token(env: Env, validator: Address, token_id: u32, approved: bool, evidence_uri: String) -> Result<bool, ContractError> {
+        pause::require_not_paused(&env, PauseScope::Metadata)?;
+        validator.require_auth();
+        if !attestation::is_validator(&env, &validator) {
+            return Err(ContractError::ValidatorNotRegistered);
+        }
+        if String::len(&evidence_uri) == 0 {
+            return Err(ContractError::EmptyEvidence);
+        }
+        let mut item: ImpactData = storage::get_impact_data(&env, token_id).ok_or(ContractError::TokenNotFound)?;
+        if item.verified {
+            return Err(ContractError::AlreadyVerified);
+        }
+
+        ttl::extend_instance(&env);
+        let record: Attestation = Attestation { validator: validator.clone(), approved, evidence_uri, timestamp: env.ledger().timestamp() };
+        attestation::add_attestation(&env, token_id, record)?;
+        env.events().publish((symbol_short!("Attested"), token_id), (validator, approved));
+
+        let approvals: u32 = attestation::count_approvals(&env, token_id);
+        if approvals >= attestation::get_quorum(&env) {
+            let before: Option<ProjectTotals> = project::snapshot(&env, token_id);
+            item.verified = true;
+            storage::set_impact_data(&env, token_id, &item);
+            project::record(&env, token_id, before);
+            env.events().publish((symbol_short!("Verified"),), (token_id, approvals));
+            return Ok(true);
+        }
+        Ok(false)
+    }
+
+    /// Scores `token_id` under the current scoring configuration.
+    pub fn calculate_impact_score(env: Env, token_id: u32) -> Result<u128, ContractError> {
+        Self::calculate_impact_score_at(env.clone(), token_id, scoring::get_version(&env))
+    }
+
+    /// Scores `token_id` under scoring configuration `version`. Pass the token's
+    /// `get_token_scoring_version` to recompute the score it had when it was minted.
+    pub fn calculate_impact_score_at(env: Env, token_id: u32, version: u32) -> Result<u128, ContractError> {
+        let item: ImpactData = storage::get_impact_data(&env, token_id).ok_or(ContractError::TokenNotFound)?;
+        let config: ScoringConfig = scoring::get_config_at(&env, version).ok_or(ContractError::UnknownScoringVersion)?;
+        Ok(scoring::score(&config, &item, env.ledger().timestamp()))
+    }
+
+    pub fn get_token_scoring_version(env: Env, token_id: u32) -> Result<u32, ContractError> {
+        storage::get_impact_data(&env, token_id).ok_or(ContractError::TokenNotFound)?;
+        Ok(scoring::get_token_version(&env, token_id))
+    }
+
+    pub fn get_scoring_config(env: Env) -> ScoringConfig {
+        scoring::get_config(&env)
+    }
+
+    pub fn get_scoring_version(env: Env) -> u32 {
+        scoring::get_version(&env)
+    }
+
+    pub fn get_scoring_config_at(env: Env, version: u32) -> Result<ScoringConfig, ContractError> {
+        scoring::get_config_at(&env, version).ok_or(ContractError::UnknownScoringVersion)
+    }
+
+    /// Publishes `config` as a new scoring version that applies to every score computed
+    /// from now on. Previous versions stay readable. Returns the new version.
+    pub fn set_scoring_config(env: Env, admin: Address, config: ScoringConfig) -> Result<u32, ContractError> {
+        access_control::require_role(&env, &DEFAULT_ADMIN_ROLE, &admin)?;
+        scoring::validate(&config)?;
+        let version: u32 = scoring::add_config(&env, &config);
+        ttl::extend_instance(&env);
+        env.events().publish((symbol_short!("Scoring"),), version);
+        Ok(version)
+    }
+
+    pub fn get_token_price(env: Env, token_id: u32) -> Result<u128, ContractError> {
+        storage::get_token_price(&env, token_id).ok_or(ContractError::TokenNotFound)
+    }
+
+    pub fn update_token_price(env: Env, token_id: u32, price: u128) -> Result<(), ContractError> {
+        pause::require_not_paused(&env, PauseScope::Pricing)?;
+        Self::_require_not_retired(&env, token_id)?;
+        let owner: Address = Self::owner_of(env.clone(), token_id)?;
+        owner.require_auth();
+        storage::set_token_price(&env, token_id, price);
+        Ok(())
+    }
+
+    pub fn update_token_price_admin(env: Env, admin: Address, token_id: u32, price: u128) -> Result<(), ContractError> {
+        pause::require_not_paused(&env, PauseScope::Pricing)?;
+        access_control::require_role(&env, &DEFAULT_ADMIN_ROLE, &admin)?;
+        Self::owner_of(env.clone(), token_id)?;
+        storage::set_token_price(&env, token_id, price);
+        Ok(())
+    }
+
+    /// Permanently retires `token_id` on behalf of `beneficiary_name`, so the impact claim
+    /// it represents can no longer be transferred or resold. The token is burned and a
+    /// retirement certificate snapshotting its impact is stored in its place.
+    pub fn retire(env: Env, owner: Address, token_id: u32, beneficiary_name: String, reason: String) -> Result<RetirementCertificate, ContractError> {
+        pause::require_not_paused(&env, PauseScope::Transfers)?;
+        owner.require_auth();
+        Self::_require_not_retired(&env, token_id)?;
+        let actual_owner: Address = Self::owner_of(env.clone(), token_id)?;
+        if owner != actual_owner {
+            return Err(ContractError::NotOwner);
+        }
+        if String::len(&beneficiary_name) == 0 {
+            return Err(ContractError::EmptyBeneficiary);
         }
+        let item: ImpactData = storage::get_impact_data(&env, token_id).ok_or(ContractError::TokenNotFound)?;
 
-        true
+        ttl::extend_instance(&env);
+        let certificate: RetirementCertificate = RetirementCertificate {
+            token_id,
+            retiree: owner.clone(),
+            beneficiary_name: beneficiary_name.clone(),
+            reason,
+            timestamp: env.ledger().timestamp(),
+            impact_value: item.impact_value,
+            category: item.category.clone()
+        };
+        let before: Option<ProjectTotals> = project::snapshot(&env, token_id);
+        retirement::add_certificate(&env, &certificate);
+        project::record(&env, token_id, before);
+
+        storage::remove_owner(&env, token_id);
+        storage::remove_approval(&env, token_id);
+        storage::remove_creator_token(&env, token_id);
+        storage::remove_category_token(&env, &item.category, token_id);
+        storage::remove_vintage_token(&env, vintage::vintage_year(&item), token_id);
+        storage::remove_owner_token(&env, &owner, token_id);
+        Self::_decrease_balance(env.clone(), owner.clone());
+
+        env.events().publish((symbol_short!("Retired"), owner, token_id), (beneficiary_name, item.impact_value));
+        Ok(certificate)
+    }
+
+    pub fn is_retired(env: Env, token_id: u32) -> bool {
+        retirement::is_retired(&env, token_id)
     }
 
-    fn remove_from_category(env: Env, token_id:u128, category: String) {
-        let data: Map<String, Vec<u128>> = env.storage().persistent().get(&DataKey::CategoryTokens).expect("should contain CategoryTokens");
-        let mut category_list: Vec<u128> = data.get(category).expect("No tokens by category found");
+    pub fn get_retirement(env: Env, token_id: u32) -> Result<RetirementCertificate, ContractError> {
+        retirement::get_certificate(&env, token_id).ok_or(ContractError::TokenNotRetired)
+    }
+
+    pub fn get_retirements_by_retiree(env: Env, retiree: Address) -> Vec<RetirementCertificate> {
+        retirement::get_certificates_by_retiree(&env, &retiree)
+    }
 
-        let mut item: u32 = 0;
-        for value in category_list.iter(){
-            if value == token_id {
-                category_list.remove(item);
-                item -= 1;
+    pub fn get_retired_impact_by_category(env: Env, category: String) -> u128 {
+        retirement::get_retired_impact(&env, &category)
+    }
+
+    /// Assigns each of `token_ids` to `project_id`, so their impact counts towards
+    /// `get_project_totals` from then on. A token belongs to at most one project.
+    pub fn assign_project(env: Env, minter: Address, token_ids: Vec<u32>, project_id: u32) -> Result<(), ContractError> {
+        access_control::require_role(&env, &MINTER_ROLE, &minter)?;
+        for token_id in token_ids.iter() {
+            storage::get_impact_data(&env, token_id).ok_or(ContractError::TokenNotFound)?;
+            if project::get_project(&env, token_id).is_some() {
+                return Err(ContractError::TokenAlreadyInProject);
             }
-            item +=1;
+            project::assign(&env, token_id, project_id);
         }
+        ttl::extend_instance(&env);
+        Ok(())
+    }
+
+    pub fn get_token_project(env: Env, token_id: u32) -> Option<u32> {
+        project::get_project(&env, token_id)
+    }
+
+    /// Verified and retired impact of the tokens assigned to `project_id`, kept current as
+    /// they are verified, updated and retired.
+    pub fn get_project_totals(env: Env, project_id: u32) -> ProjectTotals {
+        project::get_totals(&env, project_id)
+    }
+
+    pub fn get_default_royalty(env: Env) -> Option<RoyaltyInfo> {
+        royalty::get_default(&env)
     }
 
-    pub fn verify_token(env: Env, token_id: u128, validators: Vec<Address>) -> bool {
-        let verifier: Address = env.storage().instance().get(&DataKey::VERIFIER).expect("VERIFIER not found");
-        verifier.require_auth();
-        let length: u32 = validators.len();
-        if length < 5 {
-            panic!("From not owner");
+    /// Sets the royalty paid on tokens that have no royalty of their own.
+    pub fn set_default_royalty(env: Env, admin: Address, receiver: Address, basis_points: u32) -> Result<(), ContractError> {
+        pause::require_not_paused(&env, PauseScope::Pricing)?;
+        access_control::require_role(&env, &DEFAULT_ADMIN_ROLE, &admin)?;
+        let info: RoyaltyInfo = RoyaltyInfo { receiver, basis_points };
+        royalty::validate(&info)?;
+        royalty::set_default(&env, &info);
+        ttl::extend_instance(&env);
+        env.events().publish((symbol_short!("DefRoyal"),), info);
+        Ok(())
+    }
+
+    /// Overrides the collection default royalty for `token_id`.
+    pub fn set_token_royalty(env: Env, admin: Address, token_id: u32, receiver: Address, basis_points: u32) -> Result<(), ContractError> {
+        pause::require_not_paused(&env, PauseScope::Pricing)?;
+        access_control::require_role(&env, &DEFAULT_ADMIN_ROLE, &admin)?;
+        Self::owner_of(env.clone(), token_id)?;
+        let info: RoyaltyInfo = RoyaltyInfo { receiver, basis_points };
+        royalty::validate(&info)?;
+        royalty::set_token_royalty(&env, token_id, &info);
+        ttl::extend_instance(&env);
+        env.events().publish((symbol_short!("Royalty"), token_id), info);
+        Ok(())
+    }
+
+    /// Lets the creator of `token_id` redirect its royalty to `receiver`. The rate stays
+    /// the token's own, or the collection default if it has none.
+    pub fn set_royalty_receiver(env: Env, creator: Address, token_id: u32, receiver: Address) -> Result<(), ContractError> {
+        pause::require_not_paused(&env, PauseScope::Pricing)?;
+        creator.require_auth();
+        Self::owner_of(env.clone(), token_id)?;
+        if storage::get_token_creator(&env, token_id) != Some(creator) {
+            return Err(ContractError::NotCreator);
         }
-        let data: Map<u128, ImpactData> = env.storage().persistent().get(&DataKey::ImpactData).expect("should contain ImpactData");
-        let item: ImpactData = data.get(token_id).expect("No item for ImpactData found");
-        if item.verified {
-            panic!("item already verified");
+        let basis_points: u32 = royalty::get_royalty(&env, token_id).map(|info| info.basis_points).unwrap_or(0);
+        let info: RoyaltyInfo = RoyaltyInfo { receiver, basis_points };
+        royalty::set_token_royalty(&env, token_id, &info);
+        ttl::extend_instance(&env);
+        env.events().publish((symbol_short!("Royalty"), token_id), info);
+        Ok(())
+    }
+
+    /// Returns who should receive a royalty when `token_id` sells for `sale_price`, and
+    /// how much. Tokens without any royalty report their creator and a zero amount.
+    pub fn royalty_info(env: Env, token_id: u32, sale_price: i128) -> Result<(Address, i128), ContractError> {
+        if sale_price < 0 {
+            return Err(ContractError::InvalidPrice);
+        }
+        let owner: Address = Self::owner_of(env.clone(), token_id)?;
+        match royalty::get_royalty(&env, token_id) {
+            Some(info) => Ok((info.receiver, royalty::royalty_amount(sale_price, info.basis_points))),
+            None => Ok((storage::get_token_creator(&env, token_id).unwrap_or(owner), 0))
         }
-        true
     }
 
-    pub fn calculate_impact_score(env: Env, token_id: u128) -> u128 {
-        let data: Map<u128, ImpactData> = env.storage().persistent().get(&DataKey::ImpactData).expect("should contain ImpactData");
-        let item: ImpactData = data.get(token_id).expect("No item for ImpactData found");
-        let mut score: u128 = item.impact_value;
+    /// Calendar year in which the token's impact period ends.
+    pub fn vintage_year(env: Env, token_id: u32) -> Result<u32, ContractError> {
+        let item: ImpactData = storage::get_impact_data(&env, token_id).ok_or(ContractError::TokenNotFound)?;
+        Ok(vintage::vintage_year(&item))
+    }
 
-        if item.verified {
-            score = score * 5 / 10;
+    pub fn get_tokens_by_vintage(env: Env, year: u32) -> Result<Vec<u32>, ContractError> {
+        if storage::get_vintage_token_count(&env, year) == 0 {
+            return Err(ContractError::NoTokensFound);
         }
+        Ok(storage::get_vintage_tokens(&env, year))
+    }
 
-        if item.end_date > item.start_date {
-            let duration: u128 = item.end_date - item.start_date;
-            if duration > (86400 * 30) {
-                score = (score * 11) / 10;
-            }
-            if duration > (86400 * 180) {
-                score = (score * 12) / 10;
-            }
+    pub fn get_token_expiry(env: Env, token_id: u32) -> Option<u64> {
+        vintage::get_expiry(&env, token_id)
+    }
+
+    /// Sets the ledger timestamp from which `token_id` can no longer be sold or staked.
+    /// `None` removes the expiry. Expired tokens can still be retired.
+    pub fn set_token_expiry(env: Env, minter: Address, token_id: u32, expires_at: Option<u64>) -> Result<(), ContractError> {
+        pause::require_not_paused(&env, PauseScope::Metadata)?;
+        access_control::require_role(&env, &MINTER_ROLE, &minter)?;
+        Self::_require_not_retired(&env, token_id)?;
+        Self::owner_of(env.clone(), token_id)?;
+        if let Some(timestamp) = expires_at && timestamp <= env.ledger().timestamp() {
+            return Err(ContractError::InvalidExpiry);
         }
+        vintage::set_expiry(&env, token_id, expires_at);
+        ttl::extend_instance(&env);
+        env.events().publish((symbol_short!("Expiry"), token_id), expires_at);
+        Ok(())
+    }
 
-        score
+    pub fn is_expired(env: Env, token_id: u32) -> bool {
+        vintage::is_expired(&env, token_id)
     }
 
-    pub fn get_token_price(env: Env, token_id: u128) -> u128 {
-        let data: Map<u128, u128> = env.storage().persistent().get(&DataKey::TokenPrices).expect("should contain TokenPrices");
-        data.get(token_id).expect("No Token Id Found")
+    /// Whether `token_id` exists, has not been retired and has not expired. Marketplaces
+    /// and staking check this before selling or staking a token.
+    pub fn is_active(env: Env, token_id: u32) -> bool {
+        storage::get_owner(&env, token_id).is_some() && !vintage::is_expired(&env, token_id)
     }
 
-    pub fn update_token_price(env: Env, token_id: u128, price: u128){
-        let owner: Address = Self::owner_of(env.clone(), token_id);
-        owner.require_auth();
-        let mut data: Map<u128, u128> = env.storage().persistent().get(&DataKey::TokenPrices).expect("should contain TokenPrices");
-        data.set(token_id, price);
-        env.storage().persistent().set(&DataKey::TokenPrices, &data);
+    pub fn get_tokens_by_creator(env: Env, creator: Address) -> Result<Vec<u32>, ContractError> {
+        if storage::get_creator_token_count(&env, &creator) == 0 {
+            return Err(ContractError::NoTokensFound);
+        }
+        Ok(storage::get_creator_tokens(&env, &creator))
     }
 
-    pub fn update_token_price_admin(env: Env, token_id: u128, price: u128){
-        let admin: Address = env.storage().instance().get(&DataKey::ADMIN).expect("should contain ADMIN");
-        admin.require_auth();
-        let mut data: Map<u128, u128> = env.storage().persistent().get(&DataKey::TokenPrices).expect("should contain TokenPrices");
-        data.set(token_id, price);
-        env.storage().persistent().set(&DataKey::TokenPrices, &data);
+    pub fn get_tokens_by_category(env: Env, category: String) -> Result<Vec<u32>, ContractError> {
+        if storage::get_category_token_count(&env, &category) == 0 {
+            return Err(ContractError::NoTokensFound);
+        }
+        Ok(storage::get_category_tokens(&env, &category))
     }
 
-    pub fn get_tokens_by_creator(env: Env, creator: Address) -> Vec<u128> {
-        let data: Map<Address, Vec<u128>> = env.storage().persistent().get(&DataKey::CreatorTokens).expect("should contain CreatorTokens");
-        data.get(creator).expect("No tokens by creator found")
+    /// Up to `limit` of `owner`'s tokens, starting at position `start` of their index.
+    pub fn tokens_of_owner(env: Env, owner: Address, start: u32, limit: u32) -> Vec<u32> {
+        enumeration::tokens_of_owner(&env, &owner, start, limit)
     }
 
-    pub fn get_tokens_by_category(env: Env, category: String) -> Vec<u128> {
-        let data: Map<String, Vec<u128>> = env.storage().persistent().get(&DataKey::CategoryTokens).expect("should contain CategoryTokens");
-        data.get(category).expect("No tokens by category found")
+    /// Token minted at position `index`, counting from 0 in mint order. Retired tokens
+    /// keep their position.
+    pub fn token_by_index(env: Env, index: u32) -> Result<u32, ContractError> {
+        if index >= storage::get_token_count(&env) {
+            return Err(ContractError::IndexOutOfBounds);
+        }
+        Ok(index + 1)
     }
 
-    fn _set_token_uri(env: Env, token_id: u128, token_uri: String) {
-        let mut token_uris: Map<u128, String> = env.storage().persistent().get(&DataKey::TokenURIs).expect("should contain TokenURIs");
-        token_uris.set(token_id, token_uri);
-        env.storage().persistent().set(&DataKey::TokenURIs, &token_uris);
+    pub fn tokens_of_creator(env: Env, creator: Address, start: u32, limit: u32) -> Vec<u32> {
+        enumeration::tokens_of_creator(&env, &creator, start, limit)
     }
 
-    pub fn is_approved(env: Env, operator: Address, token_id: u128) -> bool {
-        let key: DataKey = DataKey::Approvals(token_id);
-        let approvals: Vec<Address> = env.storage().persistent().get::<DataKey, Vec<Address>>(&key).unwrap_or_else(|| Vec::new(&env));
-        approvals.contains(&operator)
+    pub fn tokens_of_category(env: Env, category: String, start: u32, limit: u32) -> Vec<u32> {
+        enumeration::tokens_of_category(&env, &category, start, limit)
     }
 
-    pub fn transfer(env: Env, owner: Address, to: Address, token_id: u128) {
-        owner.require_auth();
-        let actual_owner: Address = Self::owner_of(env.clone(), token_id);
-        if owner == actual_owner {
-            env.storage().persistent().set(&DataKey::Owner(token_id), &to);
-            env.storage().persistent().remove(&DataKey::Approvals(token_id));
-            env.events().publish((symbol_short!("Transfer"),), (owner, to, token_id));
-        } else {
-            panic!("Not the token owner");
+    /// Lists live tokens matching `filter`, scanning from cursor `start`. A page may hold
+    /// fewer than `limit` tokens while `next_cursor` is still set when matches are sparse.
+    pub fn query_tokens(env: Env, filter: TokenFilter, start: u32, limit: u32) -> TokenPage {
+        enumeration::query(&env, &filter, start, limit)
+    }
+
+    /// Replaces the contract code with the uploaded wasm `new_wasm_hash`. Storage is kept;
+    /// call `migrate` afterwards to bring it up to the new code's schema version.
+    pub fn upgrade(env: Env, admin: Address, new_wasm_hash: BytesN<32>) -> Result<(), ContractError> {
+        access_control::require_role(&env, &DEFAULT_ADMIN_ROLE, &admin)?;
+        ttl::extend_instance(&env);
+        env.deployer().update_current_contract_wasm(new_wasm_hash.clone());
+        env.events().publish((symbol_short!("upgrade"),), new_wasm_hash);
+        Ok(())
+    }
+
+    pub fn schema_version(env: Env) -> u32 {
+        migration::get_schema_version(&env)
+    }
+
+    /// Runs the data migrations between the stored schema version and this build's. Returns
+    /// the schema version now in force.
+    pub fn migrate(env: Env, admin: Address) -> Result<u32, ContractError> {
+        // Admins of an earlier layout are only visible to the role check once moved.
+        access_control::migrate_role_members(&env, &DEFAULT_ADMIN_ROLE);
+        access_control::require_role(&env, &DEFAULT_ADMIN_ROLE, &admin)?;
+        let from: u32 = migration::migrate_schema(&env)?;
+        ttl::extend_instance(&env);
+        env.events().publish((symbol_short!("migrate"),), (from, migration::SCHEMA_VERSION));
+        Ok(migration::SCHEMA_VERSION)
+    }
+
+    /// Builds the owner index for tokens minted before it existed, visiting at most
+    /// `limit` tokens per call. Returns true once every token has been indexed.
+    pub fn index_owners(env: Env, admin: Address, limit: u32) -> Result<bool, ContractError> {
+        access_control::require_role(&env, &DEFAULT_ADMIN_ROLE, &admin)?;
+        Ok(migration::index_owners(&env, limit))
+    }
+
+    /// Extends the TTL of every entry stored for `token_ids`, along with the contract
+    /// instance. Anyone may call this to keep tokens from being archived.
+    pub fn extend_token_ttl(env: Env, token_ids: Vec<u32>) -> Result<(), ContractError> {
+        ttl::extend_instance(&env);
+        for token_id in token_ids.iter() {
+            if storage::get_impact_data(&env, token_id).is_none() {
+                return Err(ContractError::TokenNotFound);
+            }
+            ttl::extend_token(&env, token_id);
         }
+        Ok(())
+    }
+
+    pub fn get_ttl_config(env: Env) -> TtlConfig {
+        ttl::get_config(&env)
     }
 
-    fn mint(env: Env, to: Address) {
-        let mut token_count: u128 = env.storage().persistent().get(&DataKey::TokenCount).unwrap_or(0);
-        assert!(token_count < Self::SUPPLY, "Maximum token supply reached");
+    /// Sets the TTL policy: entries with fewer than `threshold` ledgers left are extended
+    /// to live for `extend_to` ledgers.
+    pub fn set_ttl_config(env: Env, admin: Address, threshold: u32, extend_to: u32) -> Result<(), ContractError> {
+        access_control::require_role(&env, &DEFAULT_ADMIN_ROLE, &admin)?;
+        ttl::set_config(&env, &TtlConfig { threshold, extend_to })?;
+        ttl::extend_instance(&env);
+        env.events().publish((symbol_short!("TtlConfig"),), (threshold, extend_to));
+        Ok(())
+    }
+
+    pub fn balance(env: Env, owner: Address) -> u32 {
+        storage::get_balance(&env, &owner)
+    }
+
+    pub fn get_approved(env: Env, token_id: u32) -> Option<Address> {
+        let approval: Option<ApprovalData> = storage::get_approval(&env, token_id);
+        match approval {
+            Some(data) if data.live_until_ledger >= env.ledger().sequence() => Some(data.approved),
+            _ => None
+        }
+    }
+
+    pub fn is_approved_for_all(env: Env, owner: Address, operator: Address) -> bool {
+        let live_until_ledger: Option<u32> = storage::get_approval_for_all(&env, &owner, &operator);
+        match live_until_ledger {
+            Some(ledger) => ledger >= env.ledger().sequence(),
+            None => false
+        }
+    }
+
+    pub fn transfer(env: Env, from: Address, to: Address, token_id: u32) -> Result<(), ContractError> {
+        pause::require_not_paused(&env, PauseScope::Transfers)?;
+        from.require_auth();
+        Self::_require_not_retired(&env, token_id)?;
+        let actual_owner: Address = Self::owner_of(env.clone(), token_id)?;
+        if from != actual_owner {
+            return Err(ContractError::NotOwner);
+        }
+        Self::_transfer(env, from, to, token_id);
+        Ok(())
+    }
+
+    fn mint(env: Env, to: Address) -> Result<u32, ContractError> {
+        let mut token_count: u32 = storage::get_token_count(&env);
+        if let Some(max_supply) = storage::get_max_supply(&env) && token_count >= max_supply {
+            return Err(ContractError::SupplyCapReached);
+        }
         token_count += 1;
-        env.storage().persistent().set(&DataKey::TokenCount, &token_count);
-        env.storage().persistent().set(&DataKey::Owner(token_count), &to);
-        env.events().publish((symbol_short!("Mint"),), (to, token_count));
+        storage::set_token_count(&env, token_count);
+        storage::set_owner(&env, token_count, &to);
+        storage::add_owner_token(&env, &to, token_count);
+        Self::_increase_balance(env.clone(), to.clone());
+        env.events().publish((symbol_short!("mint"), to), token_count);
+        Ok(token_count)
     }
 
-    pub fn approve(env: Env, owner: Address, to: Address, token_id: u128) {
-        owner.require_auth();
-        let actual_owner: Address = Self::owner_of(env.clone(), token_id);
-        if owner == actual_owner {
-            let key: DataKey = DataKey::Approvals(token_id);
-            let mut approvals: Vec<Address> = env.storage().persistent().get::<DataKey, Vec<Address>>(&key).unwrap_or_else(|| Vec::new(&env));
-            if !approvals.contains(&to) {
-                approvals.push_back(to.clone());
-                env.storage().persistent().set(&key, &approvals);
-                env.events().publish((symbol_short!("Approval"),), (owner, to, token_id));
+    /// Approves `approved` to transfer `token_id` until `live_until_ledger`. The approver must
+    /// be the owner or one of the owner's operators. A `live_until_ledger` of 0 clears the approval.
+    /// Only granting is blocked while transfers are paused, so holders can still withdraw
+    /// approvals during an incident.
+    pub fn approve(env: Env, approver: Address, approved: Address, token_id: u32, live_until_ledger: u32) -> Result<(), ContractError> {
+        approver.require_auth();
+        Self::_require_not_retired(&env, token_id)?;
+        let owner: Address = Self::owner_of(env.clone(), token_id)?;
+        if approver != owner && !Self::is_approved_for_all(env.clone(), owner, approver.clone()) {
+            return Err(ContractError::NotOwner);
+        }
+        if live_until_ledger == 0 {
+            storage::remove_approval(&env, token_id);
+        } else {
+            pause::require_not_paused(&env, PauseScope::Transfers)?;
+            if live_until_ledger < env.ledger().sequence() {
+                return Err(ContractError::InvalidLiveUntilLedger);
             }
+            storage::set_approval(&env, token_id, &ApprovalData { approved: approved.clone(), live_until_ledger });
+        }
+        env.events().publish((symbol_short!("approve"), approver, token_id), (approved, live_until_ledger));
+        Ok(())
+    }
+
+    /// Clears the approval on `token_id`. The caller must be the owner or one of the
+    /// owner's operators.
+    pub fn revoke(env: Env, approver: Address, token_id: u32) -> Result<(), ContractError> {
+        approver.require_auth();
+        let owner: Address = Self::owner_of(env.clone(), token_id)?;
+        if approver != owner && !Self::is_approved_for_all(env.clone(), owner, approver.clone()) {
+            return Err(ContractError::NotOwner);
+        }
+        storage::remove_approval(&env, token_id);
+        env.events().publish((symbol_short!("revoke"), approver), token_id);
+        Ok(())
+    }
+
+    /// Lets `operator` manage every token of `owner` until `live_until_ledger`. A
+    /// `live_until_ledger` of 0 revokes the operator.
+    pub fn approve_for_all(env: Env, owner: Address, operator: Address, live_until_ledger: u32) -> Result<(), ContractError> {
+        Self::set_approval_for_all(env, owner, operator, live_until_ledger != 0, live_until_ledger)
+    }
+
+    /// Grants or revokes `operator` for every token `owner` holds now or later. Grants
+    /// expire after `live_until_ledger`, which must not be in the past. As with `approve`,
+    /// revoking stays available while transfers are paused.
+    pub fn set_approval_for_all(env: Env, owner: Address, operator: Address, approved: bool, live_until_ledger: u32) -> Result<(), ContractError> {
+        owner.require_auth();
+        if !approved {
+            storage::remove_approval_for_all(&env, &owner, &operator);
         } else {
-            panic!("Not the token owner");
+            pause::require_not_paused(&env, PauseScope::Transfers)?;
+            if live_until_ledger < env.ledger().sequence() {
+                return Err(ContractError::InvalidLiveUntilLedger);
+            }
+            storage::set_approval_for_all(&env, &owner, &operator, live_until_ledger);
         }
+        let live_until_ledger: u32 = if approved { live_until_ledger } else { 0 };
+        env.events().publish((Symbol::new(&env, "approve_for_all"), owner), (operator, live_until_ledger));
+        Ok(())
     }
 
-    pub fn transfer_from(env: Env, spender: Address, from: Address, to: Address, token_id: u128) {
+    pub fn transfer_from(env: Env, spender: Address, from: Address, to: Address, token_id: u32) -> Result<(), ContractError> {
+        pause::require_not_paused(&env, PauseScope::Transfers)?;
         spender.require_auth();
-        let actual_owner: Address = Self::owner_of(env.clone(), token_id);
+        Self::_require_not_retired(&env, token_id)?;
+        let actual_owner: Address = Self::owner_of(env.clone(), token_id)?;
         if from != actual_owner {
-            panic!("From not owner");
+            return Err(ContractError::NotOwner);
+        }
+        let is_approved: bool = Self::get_approved(env.clone(), token_id) == Some(spender.clone());
+        if spender != from && !is_approved && !Self::is_approved_for_all(env.clone(), from.clone(), spender) {
+            return Err(ContractError::NotApproved);
+        }
+        Self::_transfer(env, from, to, token_id);
+        Ok(())
+    }
+
+    fn _transfer(env: Env, from: Address, to: Address, token_id: u32) {
+        ttl::extend_instance(&env);
+        storage::set_owner(&env, token_id, &to);
+        storage::remove_approval(&env, token_id);
+        storage::remove_owner_token(&env, &from, token_id);
+        storage::add_owner_token(&env, &to, token_id);
+        Self::_decrease_balance(env.clone(), from.clone());
+        Self::_increase_balance(env.clone(), to.clone());
+        env.events().publish((symbol_short!("transfer"), from, to), token_id);
+    }
+
+    /// Validates `impact_data` and mints it as a new token owned by `to`.
+    fn _create_impact_product(env: &Env, to: &Address, impact_data: &ImpactData, price: u128, attributes: &Map<String, AttributeValue>) -> Result<u32, ContractError> {
+        if impact_data.category.is_empty() {
+            return Err(ContractError::EmptyCategory);
+        }
+        if impact_data.impact_value == 0 {
+            return Err(ContractError::InvalidImpactValue);
+        }
+        if price == 0 {
+            return Err(ContractError::InvalidPrice);
+        }
+        vintage::validate_period(impact_data)?;
+        metadata::validate_attributes(env, attributes)?;
+
+        Self::_issue_in_category(env, &impact_data.category)?;
+        let current_id: u32 = Self::mint(env.clone(), to.clone())?;
+
+        if String::len(&impact_data.metadata_uri) > 0 {
+            storage::set_token_uri(env, current_id, &impact_data.metadata_uri);
+        }
+
+        storage::set_impact_data(env, current_id, impact_data);
+        if !attributes.is_empty() {
+            storage::set_attributes(env, current_id, attributes);
+        }
+        storage::set_token_price(env, current_id, price);
+        storage::add_creator_token(env, to, current_id);
+        storage::add_category_token(env, &impact_data.category, current_id);
+        storage::add_vintage_token(env, vintage::vintage_year(impact_data), current_id);
+        scoring::set_token_version(env, current_id, scoring::get_version(env));
+        Ok(current_id)
+    }
+
+    /// Counts a new token against `category`'s issuance cap.
+    fn _issue_in_category(env: &Env, category: &String) -> Result<(), ContractError> {
+        let issued: u32 = storage::get_category_issued(env, category);
+        if let Some(cap) = storage::get_category_cap(env, category) && issued >= cap {
+            return Err(ContractError::CategoryCapReached);
+        }
+        storage::set_category_issued(env, category, issued + 1);
+        Ok(())
+    }
+
+    /// Replaces the token's impact data with `new_impact_data`, recording the current data
+    /// in its history and moving it between category indexes when the category changes.
+    fn _apply_impact_update(env: &Env, token_id: u32, current: &ImpactData, mut new_impact_data: ImpactData) -> Result<(), ContractError> {
+        // Verification changes only through `verify_token`, whatever the update carries.
+        new_impact_data.verified = current.verified;
+        if current.category != new_impact_data.category {
+            Self::_issue_in_category(env, &new_impact_data.category)?;
+            let issued: u32 = storage::get_category_issued(env, &current.category);
+            storage::set_category_issued(env, &current.category, issued - 1);
+            storage::remove_category_token(env, &current.category, token_id);
+            storage::add_category_token(env, &new_impact_data.category, token_id);
         }
-        let key: DataKey = DataKey::Approvals(token_id);
-        let approvals: Vec<Address> = env.storage().persistent().get::<DataKey, Vec<Address>>(&key).unwrap_or_else(|| Vec::new(&env));
-        if !approvals.contains(&spender) {
-            panic!("Spender is not approved for this token");
+
+        let current_vintage: u32 = vintage::vintage_year(current);
+        let new_vintage: u32 = vintage::vintage_year(&new_impact_data);
+        if current_vintage != new_vintage {
+            storage::remove_vintage_token(env, current_vintage, token_id);
+            storage::add_vintage_token(env, new_vintage, token_id);
+        }
+
+        if current.verified && governance::changes_material_fields(current, &new_impact_data) {
+            new_impact_data.verified = false;
+            attestation::clear_attestations(env, token_id);
+            env.events().publish((symbol_short!("Unverify"),), token_id);
+        }
+
+        let before: Option<ProjectTotals> = project::snapshot(env, token_id);
+        governance::add_version(env, token_id, current);
+        storage::set_impact_data(env, token_id, &new_impact_data);
+        project::record(env, token_id, before);
+
+        // An empty metadata URI falls back to the base URI, like it does at mint.
+        if String::len(&new_impact_data.metadata_uri) > 0 {
+            storage::set_token_uri(env, token_id, &new_impact_data.metadata_uri);
+        } else {
+            storage::remove_token_uri(env, token_id);
         }
-        env.storage().persistent().set(&DataKey::Owner(token_id), &to);
-        env.storage().persistent().remove(&DataKey::Approvals(token_id));
-        env.events().publish((symbol_short!("Transfer"),), (from, to, token_id));
+        Ok(())
+    }
+
+    fn _require_not_retired(env: &Env, token_id: u32) -> Result<(), ContractError> {
+        if retirement::is_retired(env, token_id) {
+            return Err(ContractError::TokenRetired);
+        }
+        Ok(())
+    }
+
+    fn _increase_balance(env: Env, owner: Address) {
+        let balance: u32 = storage::get_balance(&env, &owner);
+        storage::set_balance(&env, &owner, balance + 1);
+    }
+
+    fn _decrease_balance(env: Env, owner: Address) {
+        let balance: u32 = storage::get_balance(&env, &owner);
+        storage::set_balance(&env, &owner, balance - 1);
     }
 }
 
-mod test;
\ No newline at end of file
+mod test;
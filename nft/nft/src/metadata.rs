@@ -0,0 +1,225 @@
+use soroban_sdk::{Env, Map, String};
+
+use crate::{AttributeValue, ContractError, ImpactData};
+
+/// Upper bound on a rendered URI or JSON document. Anything larger is rejected rather
+/// than truncated.
+pub const MAX_METADATA_LEN: usize = 4096;
+
+/// Trait names `render_json` writes for every token, in order.
+const STANDARD_TRAITS: [&str; 8] = ["category", "impact_value", "location", "start_date", "end_date", "beneficiaries", "verified", "impact_score"];
+
+/// Fixed-capacity byte buffer used to assemble strings without an allocator.
+struct Writer {
+    buf: [u8; MAX_METADATA_LEN],
+    len: usize
+}
+
+impl Writer {
+    fn new() -> Self {
+        Writer { buf: [0; MAX_METADATA_LEN], len: 0 }
+    }
+
+    fn push_bytes(&mut self, bytes: &[u8]) -> Result<(), ContractError> {
+        let end: usize = self.len + bytes.len();
+        if end > MAX_METADATA_LEN {
+            return Err(ContractError::MetadataTooLarge);
+        }
+        self.buf[self.len..end].copy_from_slice(bytes);
+        self.len = end;
+        Ok(())
+    }
+
+    fn push_str(&mut self, value: &str) -> Result<(), ContractError> {
+        self.push_bytes(value.as_bytes())
+    }
+
+    /// Appends `value` as-is.
+    fn push_string(&mut self, value: &String) -> Result<(), ContractError> {
+        let size: usize = value.len() as usize;
+        let end: usize = self.len + size;
+        if end > MAX_METADATA_LEN {
+            return Err(ContractError::MetadataTooLarge);
+        }
+        value.copy_into_slice(&mut self.buf[self.len..end]);
+        self.len = end;
+        Ok(())
+    }
+
+    /// Appends `value` as a quoted JSON string.
+    fn push_json_string(&mut self, value: &String) -> Result<(), ContractError> {
+        self.push_str("\"")?;
+        self.push_escaped(value)?;
+        self.push_str("\"")
+    }
+
+    /// Appends `value` for use inside a JSON string, escaping quotes, backslashes and
+    /// control characters. The raw bytes are staged at the end of the buffer and escaped
+    /// forwards into place.
+    fn push_escaped(&mut self, value: &String) -> Result<(), ContractError> {
+        const HEX: &[u8; 16] = b"0123456789abcdef";
+        let size: usize = value.len() as usize;
+        if self.len + size > MAX_METADATA_LEN {
+            return Err(ContractError::MetadataTooLarge);
+        }
+        let mut read: usize = MAX_METADATA_LEN - size;
+        value.copy_into_slice(&mut self.buf[read..]);
+        while read < MAX_METADATA_LEN {
+            let byte: u8 = self.buf[read];
+            read += 1;
+            let (escaped, count): ([u8; 6], usize) = match byte {
+                b'"' | b'\\' => ([b'\\', byte, 0, 0, 0, 0], 2),
+                b'\n' => ([b'\\', b'n', 0, 0, 0, 0], 2),
+                b'\r' => ([b'\\', b'r', 0, 0, 0, 0], 2),
+                b'\t' => ([b'\\', b't', 0, 0, 0, 0], 2),
+                control if control < 0x20 => ([b'\\', b'u', b'0', b'0', HEX[(control >> 4) as usize], HEX[(control & 0x0f) as usize]], 6),
+                other => ([other, 0, 0, 0, 0, 0], 1)
+            };
+            // Catching up with the unread bytes means the escaped value cannot fit.
+            if self.len + count > read {
+                return Err(ContractError::MetadataTooLarge);
+            }
+            self.buf[self.len..self.len + count].copy_from_slice(&escaped[..count]);
+            self.len += count;
+        }
+        Ok(())
+    }
+
+    fn push_u128(&mut self, value: u128) -> Result<(), ContractError> {
+        let mut digits: [u8; 39] = [0; 39];
+        let mut start: usize = digits.len();
+        let mut rest: u128 = value;
+        loop {
+            start -= 1;
+            digits[start] = b'0' + (rest % 10) as u8;
+            rest /= 10;
+            if rest == 0 {
+                break;
+            }
+        }
+        self.push_bytes(&digits[start..])
+    }
+
+    fn push_i128(&mut self, value: i128) -> Result<(), ContractError> {
+        if value < 0 {
+            self.push_str("-")?;
+        }
+        self.push_u128(value.unsigned_abs())
+    }
+
+    fn push_bool(&mut self, value: bool) -> Result<(), ContractError> {
+        self.push_str(if value { "true" } else { "false" })
+    }
+
+    fn push_attribute_name(&mut self, first: bool, trait_type: &str) -> Result<(), ContractError> {
+        if !first {
+            self.push_str(",")?;
+        }
+        self.push_str("{\"trait_type\":\"")?;
+        self.push_str(trait_type)?;
+        self.push_str("\",")
+    }
+
+    /// `push_attribute_name` for caller-supplied trait names, which need escaping.
+    fn push_custom_attribute_name(&mut self, trait_type: &String) -> Result<(), ContractError> {
+        self.push_str(",{\"trait_type\":")?;
+        self.push_json_string(trait_type)?;
+        self.push_str(",")
+    }
+
+    fn finish(&self, env: &Env) -> String {
+        String::from_bytes(env, &self.buf[..self.len])
+    }
+}
+
+/// `base_uri` followed by the decimal `token_id`, used for tokens minted without a
+/// metadata URI of their own.
+pub fn fallback_uri(env: &Env, base_uri: &String, token_id: u32) -> Result<String, ContractError> {
+    let mut writer: Writer = Writer::new();
+    writer.push_string(base_uri)?;
+    writer.push_u128(token_id as u128)?;
+    Ok(writer.finish(env))
+}
+
+/// Rejects custom attributes named like a standard trait, which would appear twice in
+/// the rendered `attributes`.
+pub fn validate_attributes(env: &Env, attributes: &Map<String, AttributeValue>) -> Result<(), ContractError> {
+    for trait_type in STANDARD_TRAITS {
+        if attributes.contains_key(String::from_str(env, trait_type)) {
+            return Err(ContractError::ReservedAttributeName);
+        }
+    }
+    Ok(())
+}
+
+/// Renders `impact_data` as an ERC-721 style metadata document whose `attributes`
+/// wallets can display directly. The token's category-specific `attributes` follow the
+/// standard ones.
+pub fn render_json(env: &Env, name: &String, token_id: u32, impact_data: &ImpactData, attributes: &Map<String, AttributeValue>, score: u128) -> Result<String, ContractError> {
+    let mut writer: Writer = Writer::new();
+    writer.push_str("{\"name\":\"")?;
+    writer.push_escaped(name)?;
+    writer.push_str(" #")?;
+    writer.push_u128(token_id as u128)?;
+    writer.push_str("\",\"token_id\":")?;
+    writer.push_u128(token_id as u128)?;
+    if !impact_data.metadata_uri.is_empty() {
+        writer.push_str(",\"external_url\":")?;
+        writer.push_json_string(&impact_data.metadata_uri)?;
+    }
+    writer.push_str(",\"attributes\":[")?;
+
+    writer.push_attribute_name(true, "category")?;
+    writer.push_str("\"value\":")?;
+    writer.push_json_string(&impact_data.category)?;
+    writer.push_str("}")?;
+
+    writer.push_attribute_name(false, "impact_value")?;
+    writer.push_str("\"value\":")?;
+    writer.push_u128(impact_data.impact_value)?;
+    writer.push_str("}")?;
+
+    writer.push_attribute_name(false, "location")?;
+    writer.push_str("\"value\":")?;
+    writer.push_json_string(&impact_data.location)?;
+    writer.push_str("}")?;
+
+    writer.push_attribute_name(false, "start_date")?;
+    writer.push_str("\"display_type\":\"date\",\"value\":")?;
+    writer.push_u128(impact_data.start_date)?;
+    writer.push_str("}")?;
+
+    writer.push_attribute_name(false, "end_date")?;
+    writer.push_str("\"display_type\":\"date\",\"value\":")?;
+    writer.push_u128(impact_data.end_date)?;
+    writer.push_str("}")?;
+
+    writer.push_attribute_name(false, "beneficiaries")?;
+    writer.push_str("\"value\":")?;
+    writer.push_json_string(&impact_data.beneficiaries)?;
+    writer.push_str("}")?;
+
+    writer.push_attribute_name(false, "verified")?;
+    writer.push_str("\"value\":")?;
+    writer.push_bool(impact_data.verified)?;
+    writer.push_str("}")?;
+
+    writer.push_attribute_name(false, "impact_score")?;
+    writer.push_str("\"value\":")?;
+    writer.push_u128(score)?;
+    writer.push_str("}")?;
+
+    for (trait_type, value) in attributes.iter() {
+        writer.push_custom_attribute_name(&trait_type)?;
+        writer.push_str("\"value\":")?;
+        match value {
+            AttributeValue::Text(text) => writer.push_json_string(&text)?,
+            AttributeValue::Integer(number) => writer.push_i128(number)?,
+            AttributeValue::Boolean(flag) => writer.push_bool(flag)?
+        }
+        writer.push_str("}")?;
+    }
+    writer.push_str("]}")?;
+
+    Ok(writer.finish(env))
+}
@@ -0,0 +1,65 @@
+use access_control::DEFAULT_ADMIN_ROLE;
+use soroban_sdk::Env;
+
+use crate::{storage, ContractError, DataKey, MINTER_ROLE, PAUSER_ROLE, VERIFIER_ROLE};
+
+/// Version of the storage layout this build reads and writes.
+pub const SCHEMA_VERSION: u32 = 2;
+
+/// Stored layout version. Deployments that predate schema versioning report 0.
+pub fn get_schema_version(env: &Env) -> u32 {
+    env.storage().instance().get(&DataKey::SchemaVersion).unwrap_or(0)
+}
+
+pub fn set_schema_version(env: &Env, version: u32) {
+    env.storage().instance().set(&DataKey::SchemaVersion, &version);
+}
+
+/// Runs every migration step between the stored version and `SCHEMA_VERSION` in order and
+/// records the new version. Returns the version migrated from.
+pub fn migrate_schema(env: &Env) -> Result<u32, ContractError> {
+    let from: u32 = get_schema_version(env);
+    if from > SCHEMA_VERSION {
+        return Err(ContractError::UnsupportedSchemaVersion);
+    }
+    for version in from..SCHEMA_VERSION {
+        match version {
+            // Version 0 storage already uses the per-token layout, so there is nothing to convert.
+            0 => {}
+            // Role holders move from instance lists to their own entries.
+            1 => {
+                for role in [DEFAULT_ADMIN_ROLE, MINTER_ROLE, VERIFIER_ROLE, PAUSER_ROLE] {
+                    access_control::migrate_role_members(env, &role);
+                }
+            }
+            _ => unreachable!("no migration step for schema version {}", version)
+        }
+    }
+    set_schema_version(env, SCHEMA_VERSION);
+    Ok(from)
+}
+
+/// Adds up to `limit` tokens minted before the owner index existed to their owner's
+/// index, resuming from the stored cursor. Retired and already indexed tokens are
+/// skipped. Returns true once every token has been visited.
+pub fn index_owners(env: &Env, limit: u32) -> bool {
+    let token_count: u32 = storage::get_token_count(env);
+    let cursor: u32 = env.storage().instance().get(&DataKey::OwnerIndexCursor).unwrap_or(0);
+    let end: u32 = core::cmp::min(cursor.saturating_add(limit), token_count);
+
+    for token_id in (cursor + 1)..=end {
+        if storage::is_owner_indexed(env, token_id) {
+            continue;
+        }
+        if let Some(owner) = storage::get_owner(env, token_id) {
+            storage::add_owner_token(env, &owner, token_id);
+        }
+    }
+
+    if end < token_count {
+        env.storage().instance().set(&DataKey::OwnerIndexCursor, &end);
+        return false;
+    }
+    env.storage().instance().remove(&DataKey::OwnerIndexCursor);
+    true
+}
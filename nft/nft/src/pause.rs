@@ -0,0 +1,62 @@
+use soroban_sdk::{contracttype, Env, Vec};
+
+use crate::{ContractError, DataKey};
+
+/// Groups of state-changing entrypoints the PAUSER can halt independently.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PauseScope {
+    /// `create_impact_product` and `batch_create_impact_products`.
+    Minting,
+    /// Transfers, approvals and retirements.
+    Transfers,
+    /// Impact data updates, their review and verification.
+    Metadata,
+    /// Token prices and royalties.
+    Pricing
+}
+
+pub const ALL_SCOPES: [PauseScope; 4] = [PauseScope::Minting, PauseScope::Transfers, PauseScope::Metadata, PauseScope::Pricing];
+
+/// Whether the global pause, which covers every scope, is on.
+pub fn is_paused(env: &Env) -> bool {
+    env.storage().instance().get(&DataKey::IsPaused).unwrap_or(false)
+}
+
+pub fn set_paused(env: &Env, paused: bool) {
+    env.storage().instance().set(&DataKey::IsPaused, &paused);
+}
+
+/// Scopes paused individually, regardless of the global pause.
+pub fn get_scopes(env: &Env) -> Vec<PauseScope> {
+    env.storage().instance().get(&DataKey::PausedScopes).unwrap_or_else(|| Vec::new(env))
+}
+
+/// Pauses or unpauses `scope` on its own. Returns false if it already was.
+pub fn set_scope(env: &Env, scope: PauseScope, paused: bool) -> bool {
+    let mut scopes: Vec<PauseScope> = get_scopes(env);
+    match (scopes.first_index_of(scope), paused) {
+        (None, true) => scopes.push_back(scope),
+        (Some(index), false) => {
+            scopes.remove(index);
+        }
+        _ => return false
+    }
+    env.storage().instance().set(&DataKey::PausedScopes, &scopes);
+    true
+}
+
+/// Every scope currently halted, either individually or by the global pause.
+pub fn paused_scopes(env: &Env) -> Vec<PauseScope> {
+    if is_paused(env) {
+        return Vec::from_array(env, ALL_SCOPES);
+    }
+    get_scopes(env)
+}
+
+pub fn require_not_paused(env: &Env, scope: PauseScope) -> Result<(), ContractError> {
+    if is_paused(env) || get_scopes(env).contains(scope) {
+        return Err(ContractError::ContractPaused);
+    }
+    Ok(())
+}
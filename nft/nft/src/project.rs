@@ -0,0 +1,69 @@
+use soroban_sdk::{contracttype, Env};
+
+use crate::{retirement, storage, ttl, DataKey};
+
+/// Verified and retired impact of the tokens assigned to a project. A retired token counts
+/// towards `retired` only, whether or not it was verified.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProjectTotals {
+    pub verified: u128,
+    pub retired: u128
+}
+
+pub fn get_project(env: &Env, token_id: u32) -> Option<u32> {
+    env.storage().persistent().get(&DataKey::TokenProject(token_id))
+}
+
+pub fn get_totals(env: &Env, project_id: u32) -> ProjectTotals {
+    env.storage().persistent().get(&DataKey::ProjectTotals(project_id)).unwrap_or(ProjectTotals { verified: 0, retired: 0 })
+}
+
+fn set_totals(env: &Env, project_id: u32, totals: &ProjectTotals) {
+    let key: DataKey = DataKey::ProjectTotals(project_id);
+    env.storage().persistent().set(&key, totals);
+    ttl::extend_persistent(env, &key);
+}
+
+/// What `token_id` currently adds to its project's totals.
+fn contribution(env: &Env, token_id: u32) -> ProjectTotals {
+    if let Some(certificate) = retirement::get_certificate(env, token_id) {
+        return ProjectTotals { verified: 0, retired: certificate.impact_value };
+    }
+    match storage::get_impact_data(env, token_id) {
+        Some(impact_data) if impact_data.verified => ProjectTotals { verified: impact_data.impact_value, retired: 0 },
+        _ => ProjectTotals { verified: 0, retired: 0 }
+    }
+}
+
+/// Assigns `token_id` to `project_id` and adds what it already contributes to the totals.
+pub fn assign(env: &Env, token_id: u32, project_id: u32) {
+    let key: DataKey = DataKey::TokenProject(token_id);
+    env.storage().persistent().set(&key, &project_id);
+    ttl::extend_persistent(env, &key);
+    apply_change(env, project_id, &ProjectTotals { verified: 0, retired: 0 }, &contribution(env, token_id));
+}
+
+/// `token_id`'s contribution ahead of a change, or `None` if it belongs to no project. Pass
+/// it to `record` once the change is stored.
+pub fn snapshot(env: &Env, token_id: u32) -> Option<ProjectTotals> {
+    get_project(env, token_id).map(|_| contribution(env, token_id))
+}
+
+/// Replaces `token_id`'s contribution `before` with its current one.
+pub fn record(env: &Env, token_id: u32, before: Option<ProjectTotals>) {
+    let (Some(project_id), Some(before)) = (get_project(env, token_id), before) else {
+        return;
+    };
+    apply_change(env, project_id, &before, &contribution(env, token_id));
+}
+
+fn apply_change(env: &Env, project_id: u32, before: &ProjectTotals, after: &ProjectTotals) {
+    if before == after {
+        return;
+    }
+    let mut totals: ProjectTotals = get_totals(env, project_id);
+    totals.verified = totals.verified - before.verified + after.verified;
+    totals.retired = totals.retired - before.retired + after.retired;
+    set_totals(env, project_id, &totals);
+}
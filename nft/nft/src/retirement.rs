@@ -0,0 +1,68 @@
+use soroban_sdk::{contracttype, Address, Env, String, Vec};
+
+use crate::{ttl, DataKey};
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RetirementCertificate {
+    pub token_id: u32,
+    pub retiree: Address,
+    pub beneficiary_name: String,
+    pub reason: String,
+    pub timestamp: u64,
+    pub impact_value: u128,
+    pub category: String
+}
+
+pub fn is_retired(env: &Env, token_id: u32) -> bool {
+    env.storage().persistent().has(&DataKey::Retirement(token_id))
+}
+
+pub fn get_certificate(env: &Env, token_id: u32) -> Option<RetirementCertificate> {
+    env.storage().persistent().get(&DataKey::Retirement(token_id))
+}
+
+/// Stores `certificate` and adds it to the retiree's list and the category total.
+pub fn add_certificate(env: &Env, certificate: &RetirementCertificate) {
+    let token_key: DataKey = DataKey::Retirement(certificate.token_id);
+    env.storage().persistent().set(&token_key, certificate);
+    ttl::extend_persistent(env, &token_key);
+
+    let count: u32 = get_retiree_count(env, &certificate.retiree);
+    let retiree_key: DataKey = DataKey::RetireeToken(certificate.retiree.clone(), count);
+    env.storage().persistent().set(&retiree_key, &certificate.token_id);
+    ttl::extend_persistent(env, &retiree_key);
+    let count_key: DataKey = DataKey::RetireeTokenCount(certificate.retiree.clone());
+    env.storage().persistent().set(&count_key, &(count + 1));
+    ttl::extend_persistent(env, &count_key);
+
+    let total_key: DataKey = DataKey::RetiredImpact(certificate.category.clone());
+    let total: u128 = get_retired_impact(env, &certificate.category);
+    env.storage().persistent().set(&total_key, &(total + certificate.impact_value));
+    ttl::extend_persistent(env, &total_key);
+
+    let retired: u32 = get_retired_count(env);
+    env.storage().persistent().set(&DataKey::RetiredCount, &(retired + 1));
+    ttl::extend_persistent(env, &DataKey::RetiredCount);
+}
+
+pub fn get_retiree_count(env: &Env, retiree: &Address) -> u32 {
+    env.storage().persistent().get(&DataKey::RetireeTokenCount(retiree.clone())).unwrap_or(0)
+}
+
+pub fn get_certificates_by_retiree(env: &Env, retiree: &Address) -> Vec<RetirementCertificate> {
+    let mut certificates: Vec<RetirementCertificate> = Vec::new(env);
+    for index in 0..get_retiree_count(env, retiree) {
+        let token_id: u32 = env.storage().persistent().get(&DataKey::RetireeToken(retiree.clone(), index)).expect("retiree index out of sync");
+        certificates.push_back(get_certificate(env, token_id).expect("retiree index out of sync"));
+    }
+    certificates
+}
+
+pub fn get_retired_impact(env: &Env, category: &String) -> u128 {
+    env.storage().persistent().get(&DataKey::RetiredImpact(category.clone())).unwrap_or(0)
+}
+
+pub fn get_retired_count(env: &Env) -> u32 {
+    env.storage().persistent().get(&DataKey::RetiredCount).unwrap_or(0)
+}
@@ -0,0 +1,52 @@
+use soroban_sdk::{contracttype, Address, Env};
+
+use crate::{ttl, ContractError, DataKey};
+
+/// Royalties are expressed in basis points of the sale price, so 10_000 is the whole price.
+pub const MAX_BASIS_POINTS: u32 = 10_000;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RoyaltyInfo {
+    pub receiver: Address,
+    pub basis_points: u32
+}
+
+pub fn validate(royalty: &RoyaltyInfo) -> Result<(), ContractError> {
+    if royalty.basis_points > MAX_BASIS_POINTS {
+        return Err(ContractError::InvalidRoyalty);
+    }
+    Ok(())
+}
+
+pub fn get_default(env: &Env) -> Option<RoyaltyInfo> {
+    env.storage().instance().get(&DataKey::DefaultRoyalty)
+}
+
+pub fn set_default(env: &Env, royalty: &RoyaltyInfo) {
+    env.storage().instance().set(&DataKey::DefaultRoyalty, royalty);
+}
+
+pub fn get_token_royalty(env: &Env, token_id: u32) -> Option<RoyaltyInfo> {
+    env.storage().persistent().get(&DataKey::TokenRoyalty(token_id))
+}
+
+pub fn set_token_royalty(env: &Env, token_id: u32, royalty: &RoyaltyInfo) {
+    let key: DataKey = DataKey::TokenRoyalty(token_id);
+    env.storage().persistent().set(&key, royalty);
+    ttl::extend_persistent(env, &key);
+}
+
+/// The royalty that applies to `token_id`: its own if one was set, otherwise the
+/// collection default.
+pub fn get_royalty(env: &Env, token_id: u32) -> Option<RoyaltyInfo> {
+    get_token_royalty(env, token_id).or_else(|| get_default(env))
+}
+
+/// `basis_points` of `sale_price`, rounded down. Splits the price first so large sale
+/// prices cannot overflow.
+pub fn royalty_amount(sale_price: i128, basis_points: u32) -> i128 {
+    let bps: i128 = basis_points as i128;
+    let max: i128 = MAX_BASIS_POINTS as i128;
+    (sale_price / max) * bps + (sale_price % max) * bps / max
+}
@@ -0,0 +1,148 @@
+use soroban_sdk::{contracttype, vec, Env, Map, String, Vec};
+
+use crate::{ttl, ContractError, DataKey, ImpactData};
+
+/// Weights and multipliers are expressed in basis points, so 10_000 leaves the score
+/// unchanged.
+pub const BASIS_POINTS: u32 = 10_000;
+/// Upper bound for any single weight or multiplier (10x).
+pub const MAX_MULTIPLIER_BPS: u32 = 100_000;
+pub const MAX_DURATION_TIERS: u32 = 10;
+
+const SECONDS_PER_DAY: u128 = 86400;
+const SECONDS_PER_YEAR: u128 = 365 * SECONDS_PER_DAY;
+
+/// Multiplier applied to tokens whose impact period lasts longer than `min_duration`
+/// seconds.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DurationTier {
+    pub min_duration: u128,
+    pub multiplier_bps: u32
+}
+
+/// Parameters `calculate_impact_score` applies to a token's impact value, in order:
+/// category weight, verification bonus, the highest duration tier reached, the region
+/// multiplier for its location and linear time decay since the impact period ended.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ScoringConfig {
+    pub default_category_weight_bps: u32,
+    pub category_weights: Map<String, u32>,
+    pub verification_bonus_bps: u32,
+    pub duration_tiers: Vec<DurationTier>,
+    pub region_multipliers: Map<String, u32>,
+    pub decay_bps_per_year: u32
+}
+
+/// Configuration in force before any was stored: verified tokens earn a 20% bonus and
+/// periods longer than 30 and 180 days earn 1.1x and 1.32x respectively.
+pub fn default_config(env: &Env) -> ScoringConfig {
+    ScoringConfig {
+        default_category_weight_bps: BASIS_POINTS,
+        category_weights: Map::new(env),
+        verification_bonus_bps: 2000,
+        duration_tiers: vec![
+            env,
+            DurationTier { min_duration: 30 * SECONDS_PER_DAY, multiplier_bps: 11000 },
+            DurationTier { min_duration: 180 * SECONDS_PER_DAY, multiplier_bps: 13200 }
+        ],
+        region_multipliers: Map::new(env),
+        decay_bps_per_year: 0
+    }
+}
+
+/// Weights and multipliers must be positive and at most `MAX_MULTIPLIER_BPS`, and
+/// duration tiers must be sorted by strictly increasing `min_duration`.
+pub fn validate(config: &ScoringConfig) -> Result<(), ContractError> {
+    let valid_multiplier = |bps: u32| bps > 0 && bps <= MAX_MULTIPLIER_BPS;
+    if !valid_multiplier(config.default_category_weight_bps)
+        || config.verification_bonus_bps > MAX_MULTIPLIER_BPS
+        || config.decay_bps_per_year > BASIS_POINTS
+        || config.duration_tiers.len() > MAX_DURATION_TIERS
+    {
+        return Err(ContractError::InvalidScoringConfig);
+    }
+    if config.category_weights.values().iter().any(|bps| !valid_multiplier(bps)) || config.region_multipliers.values().iter().any(|bps| !valid_multiplier(bps)) {
+        return Err(ContractError::InvalidScoringConfig);
+    }
+    let mut previous: Option<u128> = None;
+    for tier in config.duration_tiers.iter() {
+        if !valid_multiplier(tier.multiplier_bps) || previous.is_some_and(|min_duration| tier.min_duration <= min_duration) {
+            return Err(ContractError::InvalidScoringConfig);
+        }
+        previous = Some(tier.min_duration);
+    }
+    Ok(())
+}
+
+/// Version of the configuration currently in force. Version 0 is `default_config`.
+pub fn get_version(env: &Env) -> u32 {
+    env.storage().instance().get(&DataKey::ScoringVersion).unwrap_or(0)
+}
+
+pub fn get_config_at(env: &Env, version: u32) -> Option<ScoringConfig> {
+    if version == 0 {
+        return Some(default_config(env));
+    }
+    env.storage().persistent().get(&DataKey::ScoringConfig(version))
+}
+
+pub fn get_config(env: &Env) -> ScoringConfig {
+    get_config_at(env, get_version(env)).expect("current scoring config should exist")
+}
+
+/// Stores `config` as a new version and makes it current. Earlier versions are kept so
+/// scores can be recomputed under them. Returns the new version.
+pub fn add_config(env: &Env, config: &ScoringConfig) -> u32 {
+    let version: u32 = get_version(env) + 1;
+    let key: DataKey = DataKey::ScoringConfig(version);
+    env.storage().persistent().set(&key, config);
+    ttl::extend_persistent(env, &key);
+    env.storage().instance().set(&DataKey::ScoringVersion, &version);
+    version
+}
+
+/// Scoring version in force when `token_id` was minted. Tokens minted before scoring was
+/// configurable report version 0.
+pub fn get_token_version(env: &Env, token_id: u32) -> u32 {
+    env.storage().persistent().get(&DataKey::TokenScoringVersion(token_id)).unwrap_or(0)
+}
+
+pub fn set_token_version(env: &Env, token_id: u32, version: u32) {
+    let key: DataKey = DataKey::TokenScoringVersion(token_id);
+    env.storage().persistent().set(&key, &version);
+    ttl::extend_persistent(env, &key);
+}
+
+/// Scores `impact_data` under `config` as of ledger time `now`.
+pub fn score(config: &ScoringConfig, impact_data: &ImpactData, now: u64) -> u128 {
+    let category_weight: u32 = config.category_weights.get(impact_data.category.clone()).unwrap_or(config.default_category_weight_bps);
+    let mut score: u128 = apply_bps(impact_data.impact_value, category_weight);
+
+    if impact_data.verified {
+        score = apply_bps(score, BASIS_POINTS + config.verification_bonus_bps);
+    }
+
+    let duration: u128 = impact_data.end_date.saturating_sub(impact_data.start_date);
+    if let Some(tier) = config.duration_tiers.iter().rev().find(|tier| duration > tier.min_duration) {
+        score = apply_bps(score, tier.multiplier_bps);
+    }
+
+    if let Some(multiplier) = config.region_multipliers.get(impact_data.location.clone()) {
+        score = apply_bps(score, multiplier);
+    }
+
+    let elapsed: u128 = (now as u128).saturating_sub(impact_data.end_date);
+    let decay: u128 = (config.decay_bps_per_year as u128).saturating_mul(elapsed) / SECONDS_PER_YEAR;
+    let remaining: u32 = BASIS_POINTS.saturating_sub(u32::try_from(decay).unwrap_or(u32::MAX));
+    apply_bps(score, remaining)
+}
+
+/// `value * bps / BASIS_POINTS`, rounded down. Splits `value` first so it cannot overflow
+/// before the division.
+fn apply_bps(value: u128, bps: u32) -> u128 {
+    let bps: u128 = bps as u128;
+    let max: u128 = BASIS_POINTS as u128;
+    (value / max).saturating_mul(bps).saturating_add((value % max) * bps / max)
+}
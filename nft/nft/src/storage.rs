@@ -0,0 +1,316 @@
+use soroban_sdk::{Address, Env, IntoVal, Map, String, TryFromVal, Val, Vec};
+
+use crate::{ttl, ApprovalData, AttributeValue, DataKey, ImpactData};
+
+/// Reads a persistent entry and extends its TTL when present.
+fn read<V: TryFromVal<Env, Val>>(env: &Env, key: &DataKey) -> Option<V> {
+    let value: Option<V> = env.storage().persistent().get(key);
+    if value.is_some() {
+        ttl::extend_persistent(env, key);
+    }
+    value
+}
+
+/// Writes a persistent entry and extends its TTL.
+fn write<V: IntoVal<Env, Val>>(env: &Env, key: &DataKey, value: &V) {
+    env.storage().persistent().set(key, value);
+    ttl::extend_persistent(env, key);
+}
+
+// --- Ownership ---
+
+pub fn get_token_count(env: &Env) -> u32 {
+    read(env, &DataKey::TokenCount).unwrap_or(0)
+}
+
+pub fn set_token_count(env: &Env, count: u32) {
+    write(env, &DataKey::TokenCount, &count);
+}
+
+pub fn get_owner(env: &Env, token_id: u32) -> Option<Address> {
+    read(env, &DataKey::Owner(token_id))
+}
+
+pub fn set_owner(env: &Env, token_id: u32, owner: &Address) {
+    write(env, &DataKey::Owner(token_id), owner);
+}
+
+pub fn get_balance(env: &Env, owner: &Address) -> u32 {
+    read(env, &DataKey::Balance(owner.clone())).unwrap_or(0)
+}
+
+pub fn set_balance(env: &Env, owner: &Address, balance: u32) {
+    write(env, &DataKey::Balance(owner.clone()), &balance);
+}
+
+// --- Supply ---
+
+pub fn get_max_supply(env: &Env) -> Option<u32> {
+    env.storage().instance().get(&DataKey::MaxSupply)
+}
+
+pub fn set_max_supply(env: &Env, max_supply: Option<u32>) {
+    match max_supply {
+        Some(max_supply) => env.storage().instance().set(&DataKey::MaxSupply, &max_supply),
+        None => env.storage().instance().remove(&DataKey::MaxSupply)
+    }
+}
+
+pub fn get_category_cap(env: &Env, category: &String) -> Option<u32> {
+    read(env, &DataKey::CategoryCap(category.clone()))
+}
+
+pub fn set_category_cap(env: &Env, category: &String, cap: Option<u32>) {
+    match cap {
+        Some(cap) => write(env, &DataKey::CategoryCap(category.clone()), &cap),
+        None => env.storage().persistent().remove(&DataKey::CategoryCap(category.clone()))
+    }
+}
+
+/// Number of tokens ever issued in `category`, including retired ones.
+pub fn get_category_issued(env: &Env, category: &String) -> u32 {
+    read(env, &DataKey::CategoryIssued(category.clone())).unwrap_or(0)
+}
+
+pub fn set_category_issued(env: &Env, category: &String, issued: u32) {
+    write(env, &DataKey::CategoryIssued(category.clone()), &issued);
+}
+
+// --- Approvals ---
+
+pub fn get_approval(env: &Env, token_id: u32) -> Option<ApprovalData> {
+    read(env, &DataKey::Approval(token_id))
+}
+
+pub fn set_approval(env: &Env, token_id: u32, approval: &ApprovalData) {
+    write(env, &DataKey::Approval(token_id), approval);
+}
+
+pub fn remove_owner(env: &Env, token_id: u32) {
+    env.storage().persistent().remove(&DataKey::Owner(token_id));
+}
+
+pub fn remove_approval(env: &Env, token_id: u32) {
+    env.storage().persistent().remove(&DataKey::Approval(token_id));
+}
+
+pub fn get_approval_for_all(env: &Env, owner: &Address, operator: &Address) -> Option<u32> {
+    read(env, &DataKey::ApprovalForAll(owner.clone(), operator.clone()))
+}
+
+pub fn set_approval_for_all(env: &Env, owner: &Address, operator: &Address, live_until_ledger: u32) {
+    write(env, &DataKey::ApprovalForAll(owner.clone(), operator.clone()), &live_until_ledger);
+}
+
+pub fn remove_approval_for_all(env: &Env, owner: &Address, operator: &Address) {
+    env.storage().persistent().remove(&DataKey::ApprovalForAll(owner.clone(), operator.clone()));
+}
+
+// --- Owner Index ---
+
+pub fn get_owner_token_count(env: &Env, owner: &Address) -> u32 {
+    read(env, &DataKey::OwnerTokenCount(owner.clone())).unwrap_or(0)
+}
+
+pub fn get_owner_token(env: &Env, owner: &Address, index: u32) -> Option<u32> {
+    read(env, &DataKey::OwnerToken(owner.clone(), index))
+}
+
+pub fn is_owner_indexed(env: &Env, token_id: u32) -> bool {
+    env.storage().persistent().has(&DataKey::OwnerIndex(token_id))
+}
+
+pub fn add_owner_token(env: &Env, owner: &Address, token_id: u32) {
+    let count: u32 = get_owner_token_count(env, owner);
+    write(env, &DataKey::OwnerToken(owner.clone(), count), &token_id);
+    write(env, &DataKey::OwnerIndex(token_id), &count);
+    write(env, &DataKey::OwnerTokenCount(owner.clone()), &(count + 1));
+}
+
+/// Removes `token_id` from `owner`'s index by moving the owner's last token into its slot.
+/// Tokens minted before the owner index existed are skipped until `index_owners` reaches them.
+pub fn remove_owner_token(env: &Env, owner: &Address, token_id: u32) {
+    let index: u32 = match env.storage().persistent().get(&DataKey::OwnerIndex(token_id)) {
+        Some(index) => index,
+        None => return
+    };
+    let last_index: u32 = get_owner_token_count(env, owner) - 1;
+    if index != last_index {
+        let last_token: u32 = get_owner_token(env, owner, last_index).expect("owner index out of sync");
+        write(env, &DataKey::OwnerToken(owner.clone(), index), &last_token);
+        write(env, &DataKey::OwnerIndex(last_token), &index);
+    }
+    env.storage().persistent().remove(&DataKey::OwnerToken(owner.clone(), last_index));
+    env.storage().persistent().remove(&DataKey::OwnerIndex(token_id));
+    write(env, &DataKey::OwnerTokenCount(owner.clone()), &last_index);
+}
+
+// --- Token Data ---
+
+pub fn get_impact_data(env: &Env, token_id: u32) -> Option<ImpactData> {
+    read(env, &DataKey::ImpactData(token_id))
+}
+
+pub fn set_impact_data(env: &Env, token_id: u32, impact_data: &ImpactData) {
+    write(env, &DataKey::ImpactData(token_id), impact_data);
+}
+
+pub fn get_attributes(env: &Env, token_id: u32) -> Option<Map<String, AttributeValue>> {
+    read(env, &DataKey::Attributes(token_id))
+}
+
+pub fn set_attributes(env: &Env, token_id: u32, attributes: &Map<String, AttributeValue>) {
+    write(env, &DataKey::Attributes(token_id), attributes);
+}
+
+pub fn get_token_uri(env: &Env, token_id: u32) -> Option<String> {
+    read(env, &DataKey::TokenURI(token_id))
+}
+
+pub fn set_token_uri(env: &Env, token_id: u32, token_uri: &String) {
+    write(env, &DataKey::TokenURI(token_id), token_uri);
+}
+
+pub fn remove_token_uri(env: &Env, token_id: u32) {
+    env.storage().persistent().remove(&DataKey::TokenURI(token_id));
+}
+
+pub fn get_token_price(env: &Env, token_id: u32) -> Option<u128> {
+    read(env, &DataKey::TokenPrice(token_id))
+}
+
+pub fn set_token_price(env: &Env, token_id: u32, price: u128) {
+    write(env, &DataKey::TokenPrice(token_id), &price);
+}
+
+// --- Creator Index ---
+
+pub fn get_creator_token_count(env: &Env, creator: &Address) -> u32 {
+    read(env, &DataKey::CreatorTokenCount(creator.clone())).unwrap_or(0)
+}
+
+pub fn get_token_creator(env: &Env, token_id: u32) -> Option<Address> {
+    read(env, &DataKey::TokenCreator(token_id))
+}
+
+pub fn add_creator_token(env: &Env, creator: &Address, token_id: u32) {
+    let count: u32 = get_creator_token_count(env, creator);
+    write(env, &DataKey::CreatorToken(creator.clone(), count), &token_id);
+    write(env, &DataKey::CreatorIndex(token_id), &count);
+    write(env, &DataKey::TokenCreator(token_id), creator);
+    write(env, &DataKey::CreatorTokenCount(creator.clone()), &(count + 1));
+}
+
+/// Removes `token_id` from its creator's index by moving the creator's last token into its slot.
+/// Tokens without a recorded creator, such as migrated ones, are left alone.
+pub fn remove_creator_token(env: &Env, token_id: u32) {
+    let (Some(creator), Some(index)) = (get_token_creator(env, token_id), env.storage().persistent().get::<DataKey, u32>(&DataKey::CreatorIndex(token_id))) else {
+        return;
+    };
+    let last_index: u32 = get_creator_token_count(env, &creator) - 1;
+    if index != last_index {
+        let last_token: u32 = env.storage().persistent().get(&DataKey::CreatorToken(creator.clone(), last_index)).expect("creator index out of sync");
+        write(env, &DataKey::CreatorToken(creator.clone(), index), &last_token);
+        write(env, &DataKey::CreatorIndex(last_token), &index);
+    }
+    env.storage().persistent().remove(&DataKey::CreatorToken(creator.clone(), last_index));
+    env.storage().persistent().remove(&DataKey::CreatorIndex(token_id));
+    write(env, &DataKey::CreatorTokenCount(creator), &last_index);
+}
+
+pub fn get_creator_token(env: &Env, creator: &Address, index: u32) -> Option<u32> {
+    read(env, &DataKey::CreatorToken(creator.clone(), index))
+}
+
+pub fn get_creator_tokens(env: &Env, creator: &Address) -> Vec<u32> {
+    let mut tokens: Vec<u32> = Vec::new(env);
+    for index in 0..get_creator_token_count(env, creator) {
+        let token_id: u32 = read(env, &DataKey::CreatorToken(creator.clone(), index)).expect("creator index out of sync");
+        tokens.push_back(token_id);
+    }
+    tokens
+}
+
+// --- Category Index ---
+
+pub fn get_category_token_count(env: &Env, category: &String) -> u32 {
+    read(env, &DataKey::CategoryTokenCount(category.clone())).unwrap_or(0)
+}
+
+pub fn add_category_token(env: &Env, category: &String, token_id: u32) {
+    let count: u32 = get_category_token_count(env, category);
+    write(env, &DataKey::CategoryToken(category.clone(), count), &token_id);
+    write(env, &DataKey::CategoryIndex(token_id), &count);
+    write(env, &DataKey::CategoryTokenCount(category.clone()), &(count + 1));
+}
+
+/// Removes `token_id` from `category` by moving the category's last token into its slot.
+/// Tokens missing from the index are left alone.
+pub fn remove_category_token(env: &Env, category: &String, token_id: u32) {
+    let index: u32 = match env.storage().persistent().get(&DataKey::CategoryIndex(token_id)) {
+        Some(index) => index,
+        None => return
+    };
+    let last_index: u32 = get_category_token_count(env, category) - 1;
+    if index != last_index {
+        let last_token: u32 = env.storage().persistent().get(&DataKey::CategoryToken(category.clone(), last_index)).expect("category index out of sync");
+        write(env, &DataKey::CategoryToken(category.clone(), index), &last_token);
+        write(env, &DataKey::CategoryIndex(last_token), &index);
+    }
+    env.storage().persistent().remove(&DataKey::CategoryToken(category.clone(), last_index));
+    env.storage().persistent().remove(&DataKey::CategoryIndex(token_id));
+    write(env, &DataKey::CategoryTokenCount(category.clone()), &last_index);
+}
+
+pub fn get_category_token(env: &Env, category: &String, index: u32) -> Option<u32> {
+    read(env, &DataKey::CategoryToken(category.clone(), index))
+}
+
+pub fn get_category_tokens(env: &Env, category: &String) -> Vec<u32> {
+    let mut tokens: Vec<u32> = Vec::new(env);
+    for index in 0..get_category_token_count(env, category) {
+        let token_id: u32 = read(env, &DataKey::CategoryToken(category.clone(), index)).expect("category index out of sync");
+        tokens.push_back(token_id);
+    }
+    tokens
+}
+
+// --- Vintage Index ---
+
+pub fn get_vintage_token_count(env: &Env, year: u32) -> u32 {
+    read(env, &DataKey::VintageTokenCount(year)).unwrap_or(0)
+}
+
+pub fn add_vintage_token(env: &Env, year: u32, token_id: u32) {
+    let count: u32 = get_vintage_token_count(env, year);
+    write(env, &DataKey::VintageToken(year, count), &token_id);
+    write(env, &DataKey::VintageIndex(token_id), &count);
+    write(env, &DataKey::VintageTokenCount(year), &(count + 1));
+}
+
+/// Removes `token_id` from `year` by moving the vintage's last token into its slot.
+/// Tokens missing from the index are left alone.
+pub fn remove_vintage_token(env: &Env, year: u32, token_id: u32) {
+    let index: u32 = match env.storage().persistent().get(&DataKey::VintageIndex(token_id)) {
+        Some(index) => index,
+        None => return
+    };
+    let last_index: u32 = get_vintage_token_count(env, year) - 1;
+    if index != last_index {
+        let last_token: u32 = env.storage().persistent().get(&DataKey::VintageToken(year, last_index)).expect("vintage index out of sync");
+        write(env, &DataKey::VintageToken(year, index), &last_token);
+        write(env, &DataKey::VintageIndex(last_token), &index);
+    }
+    env.storage().persistent().remove(&DataKey::VintageToken(year, last_index));
+    env.storage().persistent().remove(&DataKey::VintageIndex(token_id));
+    write(env, &DataKey::VintageTokenCount(year), &last_index);
+}
+
+pub fn get_vintage_tokens(env: &Env, year: u32) -> Vec<u32> {
+    let mut tokens: Vec<u32> = Vec::new(env);
+    for index in 0..get_vintage_token_count(env, year) {
+        let token_id: u32 = read(env, &DataKey::VintageToken(year, index)).expect("vintage index out of sync");
+        tokens.push_back(token_id);
+    }
+    tokens
+}
@@ -1,15 +1,24 @@
 #[cfg(test)]
-use crate::{ImpactProductNFT, ImpactProductNFTClient, ImpactData};
+use crate::{AttributeValue, ImpactProductNFT, ImpactProductNFTClient, ImpactData, ImpactDataVersion, ImpactUpdateProposal, ContractError, DataKey, RetirementCertificate, RoyaltyInfo, ScoringConfig, DurationTier, TokenFilter, TokenPage, TtlConfig, PauseScope, ProjectTotals, MINTER_ROLE};
 #[cfg(test)]
-use soroban_sdk::{Env, String, Address};
+use access_control::{AccessKey, AdminTransfer, DEFAULT_ADMIN_ROLE};
 #[cfg(test)]
-use soroban_sdk::testutils::{Address as _};
+use soroban_sdk::{Env, String, Address, BytesN, IntoVal, Map, Symbol, Val, Vec, symbol_short, vec};
+#[cfg(test)]
+use soroban_sdk::testutils::{storage::Persistent as _, Address as _, Events as _, Ledger};
+
+#[cfg(test)]
+mod contract_nft {
+    soroban_sdk::contractimport!(
+        file = "../../target/wasm32v1-none/release/nft.wasm"
+    );
+}
 
 #[test]
 fn test_name() {
     let env: Env = Env::default();
     let admin: Address = Address::generate(&env);
-    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC"));
+    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
     let client: ImpactProductNFTClient<'_> = ImpactProductNFTClient::new(&env, &contract_id);
     assert_eq!(client.name(), String::from_str(&env, "Regen Bazaar Impact Product"));
 }
@@ -18,8 +27,42 @@ fn test_name() {
 fn test_symbol() {
     let env: Env = Env::default();
     let admin: Address = Address::generate(&env);
-    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC"));
+    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
+    let client: ImpactProductNFTClient<'_> = ImpactProductNFTClient::new(&env, &contract_id);
+    assert_eq!(client.symbol(), String::from_str(&env, "RIP"));
+}
+
+#[test]
+fn test_collection_branding() {
+    let env: Env = Env::default();
+    env.mock_all_auths();
+    let admin: Address = Address::generate(&env);
+    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "ipfs://mangroves/", None::<u32>, "Mangrove \"Guardians\"", "MANG"));
     let client: ImpactProductNFTClient<'_> = ImpactProductNFTClient::new(&env, &contract_id);
+    assert_eq!(client.name(), String::from_str(&env, "Mangrove \"Guardians\""));
+    assert_eq!(client.symbol(), String::from_str(&env, "MANG"));
+
+    let impact_data: ImpactData = ImpactData { category: String::from_str(&env, "Reforestation"), impact_value: 1000, location: String::from_str(&env, "Kenya"), start_date: 1700000000, end_date: 1700086400, beneficiaries: String::from_str(&env, "Local communities"), verified: false, metadata_uri: String::from_str(&env, "") };
+    client.create_impact_product(&admin, &admin, &impact_data, &100);
+    let expected: String = String::from_str(&env, concat!(
+        "{\"name\":\"Mangrove \\\"Guardians\\\" #1\",\"token_id\":1,\"attributes\":[",
+        "{\"trait_type\":\"category\",\"value\":\"Reforestation\"},",
+        "{\"trait_type\":\"impact_value\",\"value\":1000},",
+        "{\"trait_type\":\"location\",\"value\":\"Kenya\"},",
+        "{\"trait_type\":\"start_date\",\"display_type\":\"date\",\"value\":1700000000},",
+        "{\"trait_type\":\"end_date\",\"display_type\":\"date\",\"value\":1700086400},",
+        "{\"trait_type\":\"beneficiaries\",\"value\":\"Local communities\"},",
+        "{\"trait_type\":\"verified\",\"value\":false},",
+        "{\"trait_type\":\"impact_score\",\"value\":1000}]}"
+    ));
+    assert_eq!(client.token_metadata_json(&1), expected);
+
+    // Collections deployed before branding was configurable keep the original name
+    env.as_contract(&contract_id, || {
+        env.storage().instance().remove(&DataKey::Name);
+        env.storage().instance().remove(&DataKey::Symbol);
+    });
+    assert_eq!(client.name(), String::from_str(&env, "Regen Bazaar Impact Product"));
     assert_eq!(client.symbol(), String::from_str(&env, "RIP"));
 }
 
@@ -27,7 +70,7 @@ fn test_symbol() {
 fn test_token_uri() {
     let env: Env = Env::default();
     let admin: Address = Address::generate(&env);
-    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC"));
+    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
     let client: ImpactProductNFTClient<'_> = ImpactProductNFTClient::new(&env, &contract_id);
     assert_eq!(
         client.base_uri(),
@@ -42,7 +85,7 @@ fn test_token_uri() {
 fn test_token_count() {
     let env: Env = Env::default();
     let admin: Address = Address::generate(&env);
-    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC"));
+    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
     let client: ImpactProductNFTClient<'_> = ImpactProductNFTClient::new(&env, &contract_id);
     assert_eq!(
         client.token_count(),
@@ -55,12 +98,12 @@ fn test_mint() {
     let env: Env = Env::default();
     env.mock_all_auths();
     let admin: Address = Address::generate(&env);
-    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC"));
+    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
     let client: ImpactProductNFTClient<'_> = ImpactProductNFTClient::new(&env, &contract_id);
     let to: Address = Address::generate(&env);
     let impact_data: ImpactData = ImpactData { category: String::from_str(&env, "category"), impact_value: 99, location: String::from_str(&env, "location"), start_date: 9999, end_date: 10000, beneficiaries: String::from_str(&env, "someone"), verified: true, metadata_uri: String::from_str(&env, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC") };
-    client.create_impact_product(&to, &impact_data, &100);
-    let token_id: u128 = 1;
+    client.create_impact_product(&admin, &to, &impact_data, &100);
+    let token_id: u32 = 1;
     assert_eq!(client.owner_of(&token_id), to);
     assert_eq!(client.token_count(), 1);
 }
@@ -70,12 +113,12 @@ fn test_owner_of() {
     let env: Env = Env::default();
     env.mock_all_auths();
     let admin: Address = Address::generate(&env);
-    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC"));
+    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
     let client: ImpactProductNFTClient<'_> = ImpactProductNFTClient::new(&env, &contract_id);
     let owner: Address = Address::generate(&env);
     let impact_data: ImpactData = ImpactData { category: String::from_str(&env, "category"), impact_value: 99, location: String::from_str(&env, "location"), start_date: 9999, end_date: 10000, beneficiaries: String::from_str(&env, "someone"), verified: true, metadata_uri: String::from_str(&env, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC") };
-    client.create_impact_product(&owner, &impact_data, &100);
-    let token_id: u128 = 1;
+    client.create_impact_product(&admin, &owner, &impact_data, &100);
+    let token_id: u32 = 1;
     assert_eq!(client.owner_of(&token_id), owner);
 }
 
@@ -84,107 +127,105 @@ fn test_transfer() {
     let env: Env = Env::default();
     env.mock_all_auths();
     let admin: Address = Address::generate(&env);
-    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC"));
+    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
     let client: ImpactProductNFTClient<'_> = ImpactProductNFTClient::new(&env, &contract_id);
     let owner: Address = Address::generate(&env);
     let to: Address = Address::generate(&env);
     let impact_data: ImpactData = ImpactData { category: String::from_str(&env, "category"), impact_value: 99, location: String::from_str(&env, "location"), start_date: 9999, end_date: 10000, beneficiaries: String::from_str(&env, "someone"), verified: true, metadata_uri: String::from_str(&env, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC") };
-    client.create_impact_product(&owner, &impact_data, &100);
-    let token_id: u128 = 1;
+    client.create_impact_product(&admin, &owner, &impact_data, &100);
+    let token_id: u32 = 1;
     client.transfer(&owner, &to, &token_id);
     assert_eq!(client.owner_of(&token_id), to);
 }
 
 
 #[test]
-fn test_approve_and_is_approved() {
+fn test_approve_and_get_approved() {
     let env: Env = Env::default();
     env.mock_all_auths();
     let admin: Address = Address::generate(&env);
-    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC"));
+    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
     let client: ImpactProductNFTClient<'_> = ImpactProductNFTClient::new(&env, &contract_id);
     let owner: Address = Address::generate(&env);
     let operator: Address = Address::generate(&env);
     let impact_data: ImpactData = ImpactData { category: String::from_str(&env, "category"), impact_value: 99, location: String::from_str(&env, "location"), start_date: 9999, end_date: 10000, beneficiaries: String::from_str(&env, "someone"), verified: true, metadata_uri: String::from_str(&env, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC") };
-    client.create_impact_product(&owner, &impact_data, &100);
-    let token_id: u128 = 1;
-    client.approve(&owner, &operator, &token_id);
-    assert!(client.is_approved(&operator, &token_id));
+    client.create_impact_product(&admin, &owner, &impact_data, &100);
+    let token_id: u32 = 1;
+    client.approve(&owner, &operator, &token_id, &1000);
+    assert_eq!(client.get_approved(&token_id), Some(operator));
 }
 
 #[test]
-fn test_is_approved_false() {
+fn test_get_approved_none() {
     let env: Env = Env::default();
     env.mock_all_auths();
     let admin: Address = Address::generate(&env);
-    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC"));
+    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
     let client: ImpactProductNFTClient<'_> = ImpactProductNFTClient::new(&env, &contract_id);
     let owner: Address = Address::generate(&env);
     let operator: Address = Address::generate(&env);
     let impact_data: ImpactData = ImpactData { category: String::from_str(&env, "category"), impact_value: 99, location: String::from_str(&env, "location"), start_date: 9999, end_date: 10000, beneficiaries: String::from_str(&env, "someone"), verified: true, metadata_uri: String::from_str(&env, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC") };
-    client.create_impact_product(&owner, &impact_data, &100);
-    let token_id: u128 = 1;
-    assert!(!client.is_approved(&operator, &token_id));
+    client.create_impact_product(&admin, &owner, &impact_data, &100);
+    let token_id: u32 = 1;
+    assert_eq!(client.get_approved(&token_id), None);
+    assert!(!client.is_approved_for_all(&owner, &operator));
 }
 
 #[test]
 fn test_transfer_from() {
     let env: Env = Env::default();
     let admin: Address = Address::generate(&env);
-    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC"));
+    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
     let client: ImpactProductNFTClient<'_> = ImpactProductNFTClient::new(&env, &contract_id);
     let owner: Address = Address::generate(&env);
     let operator: Address = Address::generate(&env);
     let to: Address = Address::generate(&env);
     env.mock_all_auths();
     let impact_data: ImpactData = ImpactData { category: String::from_str(&env, "category"), impact_value: 99, location: String::from_str(&env, "location"), start_date: 9999, end_date: 10000, beneficiaries: String::from_str(&env, "someone"), verified: true, metadata_uri: String::from_str(&env, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC") };
-    client.create_impact_product(&owner, &impact_data, &100);
-    let token_id: u128 = 1;
-    client.approve(&owner, &operator, &token_id);
+    client.create_impact_product(&admin, &owner, &impact_data, &100);
+    let token_id: u32 = 1;
+    client.approve(&owner, &operator, &token_id, &1000);
     client.transfer_from(&operator, &owner, &to, &token_id);
     assert_eq!(client.owner_of(&token_id), to);
 }
 
 #[test]
-#[should_panic(expected = "Not the token owner")]
 fn test_transfer_not_owner() {
     let env: Env = Env::default();
     let admin: Address = Address::generate(&env);
-    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC"));
+    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
     let client: ImpactProductNFTClient<'_> = ImpactProductNFTClient::new(&env, &contract_id);
     let owner: Address = Address::generate(&env);
     let not_owner: Address = Address::generate(&env);
     let to: Address = Address::generate(&env);
     env.mock_all_auths();
     let impact_data: ImpactData = ImpactData { category: String::from_str(&env, "category"), impact_value: 99, location: String::from_str(&env, "location"), start_date: 9999, end_date: 10000, beneficiaries: String::from_str(&env, "someone"), verified: true, metadata_uri: String::from_str(&env, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC") };
-    client.create_impact_product(&owner, &impact_data, &100);
-    let token_id: u128 = 1;
-    client.transfer(&not_owner, &to, &token_id);
+    client.create_impact_product(&admin, &owner, &impact_data, &100);
+    let token_id: u32 = 1;
+    assert_eq!(client.try_transfer(&not_owner, &to, &token_id), Err(Ok(ContractError::NotOwner)));
 }
 
 #[test]
-#[should_panic(expected = "Spender is not approved for this token")]
 fn test_transfer_from_not_approved() {
     let env: Env = Env::default();
     let admin: Address = Address::generate(&env);
-    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC"));
+    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
     let client: ImpactProductNFTClient<'_> = ImpactProductNFTClient::new(&env, &contract_id);
     let owner: Address = Address::generate(&env);
     let operator: Address = Address::generate(&env);
     let to: Address = Address::generate(&env);
     env.mock_all_auths();
     let impact_data: ImpactData = ImpactData { category: String::from_str(&env, "category"), impact_value: 99, location: String::from_str(&env, "location"), start_date: 9999, end_date: 10000, beneficiaries: String::from_str(&env, "someone"), verified: true, metadata_uri: String::from_str(&env, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC") };
-    client.create_impact_product(&owner, &impact_data, &100);
-    let token_id: u128 = 1;
-    client.transfer_from(&operator, &owner, &to, &token_id);
+    client.create_impact_product(&admin, &owner, &impact_data, &100);
+    let token_id: u32 = 1;
+    assert_eq!(client.try_transfer_from(&operator, &owner, &to, &token_id), Err(Ok(ContractError::NotApproved)));
 }
 
 #[test]
-#[should_panic(expected = "From not owner")]
 fn test_transfer_from_wrong_owner() {
     let env: Env = Env::default();
     let admin: Address = Address::generate(&env);
-    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC"));
+    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
     let client: ImpactProductNFTClient<'_> = ImpactProductNFTClient::new(&env, &contract_id);
     let owner: Address = Address::generate(&env);
     let wrong_owner: Address = Address::generate(&env);
@@ -192,8 +233,1324 @@ fn test_transfer_from_wrong_owner() {
     let to: Address = Address::generate(&env);
     env.mock_all_auths();
     let impact_data: ImpactData = ImpactData { category: String::from_str(&env, "category"), impact_value: 99, location: String::from_str(&env, "location"), start_date: 9999, end_date: 10000, beneficiaries: String::from_str(&env, "someone"), verified: true, metadata_uri: String::from_str(&env, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC") };
-    client.create_impact_product(&owner, &impact_data, &100);
-    let token_id: u128 = 1;
-    client.approve(&owner, &operator, &token_id);
-    client.transfer_from(&operator, &wrong_owner, &to, &token_id);
-}
\ No newline at end of file
+    client.create_impact_product(&admin, &owner, &impact_data, &100);
+    let token_id: u32 = 1;
+    client.approve(&owner, &operator, &token_id, &1000);
+    assert_eq!(client.try_transfer_from(&operator, &wrong_owner, &to, &token_id), Err(Ok(ContractError::NotOwner)));
+}
+
+#[cfg(test)]
+fn setup_validators(env: &Env, client: &ImpactProductNFTClient<'_>, admin: &Address, count: u32) -> Vec<Address> {
+    let mut validators: Vec<Address> = Vec::new(env);
+    for _ in 0..count {
+        let validator: Address = Address::generate(env);
+        client.add_validator(admin, &validator);
+        validators.push_back(validator);
+    }
+    validators
+}
+
+#[test]
+fn test_verify_token_reaches_quorum() {
+    let env: Env = Env::default();
+    env.mock_all_auths();
+    let admin: Address = Address::generate(&env);
+    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
+    let client: ImpactProductNFTClient<'_> = ImpactProductNFTClient::new(&env, &contract_id);
+    let owner: Address = Address::generate(&env);
+    let impact_data: ImpactData = ImpactData { category: String::from_str(&env, "category"), impact_value: 99, location: String::from_str(&env, "location"), start_date: 9999, end_date: 10000, beneficiaries: String::from_str(&env, "someone"), verified: false, metadata_uri: String::from_str(&env, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC") };
+    let token_id: u32 = client.create_impact_product(&admin, &owner, &impact_data, &100);
+    let validators: Vec<Address> = setup_validators(&env, &client, &admin, 3);
+    client.set_quorum(&admin, &2);
+    let evidence: String = String::from_str(&env, "ipfs://evidence");
+
+    assert!(!client.verify_token(&validators.get(0).unwrap(), &token_id, &true, &evidence));
+    assert!(!client.verify_token(&validators.get(1).unwrap(), &token_id, &false, &evidence));
+    assert!(!client.get_impact_data(&token_id).verified);
+    assert!(client.verify_token(&validators.get(2).unwrap(), &token_id, &true, &evidence));
+    assert!(client.get_impact_data(&token_id).verified);
+    assert_eq!(client.get_attestations(&token_id).len(), 3);
+}
+
+#[test]
+fn test_removed_validator_vote_not_counted() {
+    let env: Env = Env::default();
+    env.mock_all_auths();
+    let admin: Address = Address::generate(&env);
+    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
+    let client: ImpactProductNFTClient<'_> = ImpactProductNFTClient::new(&env, &contract_id);
+    let owner: Address = Address::generate(&env);
+    let impact_data: ImpactData = ImpactData { category: String::from_str(&env, "category"), impact_value: 99, location: String::from_str(&env, "location"), start_date: 9999, end_date: 10000, beneficiaries: String::from_str(&env, "someone"), verified: false, metadata_uri: String::from_str(&env, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC") };
+    let token_id: u32 = client.create_impact_product(&admin, &owner, &impact_data, &100);
+    let validators: Vec<Address> = setup_validators(&env, &client, &admin, 3);
+    client.set_quorum(&admin, &2);
+    let evidence: String = String::from_str(&env, "ipfs://evidence");
+
+    client.verify_token(&validators.get(0).unwrap(), &token_id, &true, &evidence);
+    client.remove_validator(&admin, &validators.get(0).unwrap());
+    assert!(!client.is_validator(&validators.get(0).unwrap()));
+    assert!(!client.verify_token(&validators.get(1).unwrap(), &token_id, &true, &evidence));
+    assert!(client.verify_token(&validators.get(2).unwrap(), &token_id, &true, &evidence));
+}
+
+#[test]
+fn test_verify_token_unregistered_validator() {
+    let env: Env = Env::default();
+    env.mock_all_auths();
+    let admin: Address = Address::generate(&env);
+    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
+    let client: ImpactProductNFTClient<'_> = ImpactProductNFTClient::new(&env, &contract_id);
+    let owner: Address = Address::generate(&env);
+    let impact_data: ImpactData = ImpactData { category: String::from_str(&env, "category"), impact_value: 99, location: String::from_str(&env, "location"), start_date: 9999, end_date: 10000, beneficiaries: String::from_str(&env, "someone"), verified: false, metadata_uri: String::from_str(&env, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC") };
+    let token_id: u32 = client.create_impact_product(&admin, &owner, &impact_data, &100);
+    assert_eq!(client.try_verify_token(&Address::generate(&env), &token_id, &true, &String::from_str(&env, "ipfs://evidence")), Err(Ok(ContractError::ValidatorNotRegistered)));
+}
+
+#[test]
+fn test_verify_token_twice() {
+    let env: Env = Env::default();
+    env.mock_all_auths();
+    let admin: Address = Address::generate(&env);
+    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
+    let client: ImpactProductNFTClient<'_> = ImpactProductNFTClient::new(&env, &contract_id);
+    let owner: Address = Address::generate(&env);
+    let impact_data: ImpactData = ImpactData { category: String::from_str(&env, "category"), impact_value: 99, location: String::from_str(&env, "location"), start_date: 9999, end_date: 10000, beneficiaries: String::from_str(&env, "someone"), verified: false, metadata_uri: String::from_str(&env, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC") };
+    let token_id: u32 = client.create_impact_product(&admin, &owner, &impact_data, &100);
+    let validators: Vec<Address> = setup_validators(&env, &client, &admin, 1);
+    let evidence: String = String::from_str(&env, "ipfs://evidence");
+    client.verify_token(&validators.get(0).unwrap(), &token_id, &true, &evidence);
+    assert_eq!(client.try_verify_token(&validators.get(0).unwrap(), &token_id, &true, &evidence), Err(Ok(ContractError::AlreadyAttested)));
+}
+
+#[test]
+fn test_grant_and_revoke_minter_role() {
+    let env: Env = Env::default();
+    env.mock_all_auths();
+    let admin: Address = Address::generate(&env);
+    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
+    let client: ImpactProductNFTClient<'_> = ImpactProductNFTClient::new(&env, &contract_id);
+    let minter: Address = Address::generate(&env);
+    let owner: Address = Address::generate(&env);
+    let impact_data: ImpactData = ImpactData { category: String::from_str(&env, "category"), impact_value: 99, location: String::from_str(&env, "location"), start_date: 9999, end_date: 10000, beneficiaries: String::from_str(&env, "someone"), verified: false, metadata_uri: String::from_str(&env, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC") };
+
+    client.grant_role(&admin, &MINTER_ROLE, &minter);
+    assert!(client.has_role(&MINTER_ROLE, &minter));
+    assert_eq!(client.get_role_member_count(&MINTER_ROLE), 2);
+    client.create_impact_product(&minter, &owner, &impact_data, &100);
+    assert_eq!(client.owner_of(&1), owner);
+
+    client.revoke_role(&admin, &MINTER_ROLE, &minter);
+    assert!(!client.has_role(&MINTER_ROLE, &minter));
+    assert_eq!(client.try_create_impact_product(&minter, &owner, &impact_data, &100), Err(Ok(ContractError::MissingRole)));
+}
+
+#[test]
+fn test_grant_role_not_admin() {
+    let env: Env = Env::default();
+    env.mock_all_auths();
+    let admin: Address = Address::generate(&env);
+    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
+    let client: ImpactProductNFTClient<'_> = ImpactProductNFTClient::new(&env, &contract_id);
+    let not_admin: Address = Address::generate(&env);
+    assert_eq!(client.try_grant_role(&not_admin, &MINTER_ROLE, &not_admin), Err(Ok(ContractError::MissingRole)));
+}
+
+#[test]
+fn test_role_admin_delegation() {
+    let env: Env = Env::default();
+    env.mock_all_auths();
+    let admin: Address = Address::generate(&env);
+    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
+    let client: ImpactProductNFTClient<'_> = ImpactProductNFTClient::new(&env, &contract_id);
+    let minter_admin: Address = Address::generate(&env);
+    let minter: Address = Address::generate(&env);
+    let minter_admin_role = symbol_short!("MINTADMIN");
+
+    client.set_role_admin(&admin, &MINTER_ROLE, &minter_admin_role);
+    client.grant_role(&admin, &minter_admin_role, &minter_admin);
+    assert_eq!(client.get_role_admin(&MINTER_ROLE), minter_admin_role);
+    client.grant_role(&minter_admin, &MINTER_ROLE, &minter);
+    assert!(client.has_role(&MINTER_ROLE, &minter));
+
+    client.renounce_role(&minter, &MINTER_ROLE);
+    assert!(!client.has_role(&MINTER_ROLE, &minter));
+}
+
+#[test]
+fn test_renounce_last_admin() {
+    let env: Env = Env::default();
+    env.mock_all_auths();
+    let admin: Address = Address::generate(&env);
+    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
+    let client: ImpactProductNFTClient<'_> = ImpactProductNFTClient::new(&env, &contract_id);
+    assert_eq!(client.try_renounce_role(&admin, &symbol_short!("ADMIN")), Err(Ok(ContractError::LastAdmin)));
+}
+
+#[test]
+fn test_two_step_admin_transfer() {
+    let env: Env = Env::default();
+    env.mock_all_auths();
+    let admin: Address = Address::generate(&env);
+    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
+    let client: ImpactProductNFTClient<'_> = ImpactProductNFTClient::new(&env, &contract_id);
+    let new_admin: Address = Address::generate(&env);
+    let admin_role: Symbol = symbol_short!("ADMIN");
+
+    assert_eq!(client.try_accept_admin(), Err(Ok(ContractError::NoPendingAdmin)));
+    assert_eq!(client.try_propose_admin(&new_admin, &new_admin, &None), Err(Ok(ContractError::MissingRole)));
+
+    client.propose_admin(&admin, &new_admin, &None);
+    assert_eq!(client.pending_admin(), Some(AdminTransfer { proposer: admin.clone(), new_admin: new_admin.clone(), live_until_ledger: None }));
+    assert!(!client.has_role(&admin_role, &new_admin));
+
+    // Declined by the proposed admin
+    client.cancel_admin_transfer(&new_admin);
+    assert_eq!(client.pending_admin(), None);
+    assert_eq!(client.try_cancel_admin_transfer(&admin), Err(Ok(ContractError::NoPendingAdmin)));
+
+    // Proposals lapse after their expiry ledger
+    env.ledger().set_sequence_number(100);
+    assert_eq!(client.try_propose_admin(&admin, &new_admin, &Some(99)), Err(Ok(ContractError::InvalidLiveUntilLedger)));
+    client.propose_admin(&admin, &new_admin, &Some(110));
+    env.ledger().set_sequence_number(111);
+    assert_eq!(client.try_accept_admin(), Err(Ok(ContractError::AdminTransferExpired)));
+
+    client.propose_admin(&admin, &new_admin, &Some(120));
+    assert_eq!(client.accept_admin(), new_admin);
+    assert_eq!(env.auths()[0].0, new_admin);
+    assert!(client.has_role(&admin_role, &new_admin));
+    assert!(!client.has_role(&admin_role, &admin));
+    assert_eq!(client.get_role_member_count(&admin_role), 1);
+    assert_eq!(client.pending_admin(), None);
+    assert_eq!(client.try_set_max_supply(&admin, &Some(10)), Err(Ok(ContractError::MissingRole)));
+    client.set_max_supply(&new_admin, &Some(10));
+}
+
+#[test]
+fn test_balance() {
+    let env: Env = Env::default();
+    env.mock_all_auths();
+    let admin: Address = Address::generate(&env);
+    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
+    let client: ImpactProductNFTClient<'_> = ImpactProductNFTClient::new(&env, &contract_id);
+    let owner: Address = Address::generate(&env);
+    let to: Address = Address::generate(&env);
+    let impact_data: ImpactData = ImpactData { category: String::from_str(&env, "category"), impact_value: 99, location: String::from_str(&env, "location"), start_date: 9999, end_date: 10000, beneficiaries: String::from_str(&env, "someone"), verified: false, metadata_uri: String::from_str(&env, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC") };
+    client.create_impact_product(&admin, &owner, &impact_data, &100);
+    client.create_impact_product(&admin, &owner, &impact_data, &100);
+    assert_eq!(client.balance(&owner), 2);
+    client.transfer(&owner, &to, &1);
+    assert_eq!(client.balance(&owner), 1);
+    assert_eq!(client.balance(&to), 1);
+}
+
+#[test]
+fn test_approve_for_all_transfer_from() {
+    let env: Env = Env::default();
+    env.mock_all_auths();
+    let admin: Address = Address::generate(&env);
+    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
+    let client: ImpactProductNFTClient<'_> = ImpactProductNFTClient::new(&env, &contract_id);
+    let owner: Address = Address::generate(&env);
+    let operator: Address = Address::generate(&env);
+    let to: Address = Address::generate(&env);
+    let impact_data: ImpactData = ImpactData { category: String::from_str(&env, "category"), impact_value: 99, location: String::from_str(&env, "location"), start_date: 9999, end_date: 10000, beneficiaries: String::from_str(&env, "someone"), verified: false, metadata_uri: String::from_str(&env, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC") };
+    client.create_impact_product(&admin, &owner, &impact_data, &100);
+    client.create_impact_product(&admin, &owner, &impact_data, &100);
+
+    client.approve_for_all(&owner, &operator, &1000);
+    assert!(client.is_approved_for_all(&owner, &operator));
+    client.transfer_from(&operator, &owner, &to, &1);
+    client.transfer_from(&operator, &owner, &to, &2);
+    assert_eq!(client.balance(&to), 2);
+
+    client.approve_for_all(&owner, &operator, &0);
+    assert!(!client.is_approved_for_all(&owner, &operator));
+}
+
+#[test]
+fn test_set_approval_for_all_and_revoke() {
+    let env: Env = Env::default();
+    env.mock_all_auths();
+    let admin: Address = Address::generate(&env);
+    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
+    let client: ImpactProductNFTClient<'_> = ImpactProductNFTClient::new(&env, &contract_id);
+    let owner: Address = Address::generate(&env);
+    let operator: Address = Address::generate(&env);
+    let spender: Address = Address::generate(&env);
+    let impact_data: ImpactData = ImpactData { category: String::from_str(&env, "category"), impact_value: 99, location: String::from_str(&env, "location"), start_date: 9999, end_date: 10000, beneficiaries: String::from_str(&env, "someone"), verified: false, metadata_uri: String::from_str(&env, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC") };
+    let token_id: u32 = client.create_impact_product(&admin, &owner, &impact_data, &100);
+    env.ledger().with_mut(|li| li.sequence_number = 100);
+
+    client.set_approval_for_all(&owner, &operator, &true, &200);
+    assert!(client.is_approved_for_all(&owner, &operator));
+    client.approve(&operator, &spender, &token_id, &150);
+    assert_eq!(client.get_approved(&token_id), Some(spender.clone()));
+    assert_eq!(client.try_revoke(&spender, &token_id), Err(Ok(ContractError::NotOwner)));
+    client.revoke(&operator, &token_id);
+    assert_eq!(client.get_approved(&token_id), None);
+    assert_eq!(client.try_transfer_from(&spender, &owner, &spender, &token_id), Err(Ok(ContractError::NotApproved)));
+
+    env.ledger().with_mut(|li| li.sequence_number = 201);
+    assert!(!client.is_approved_for_all(&owner, &operator));
+    assert_eq!(client.try_transfer_from(&operator, &owner, &operator, &token_id), Err(Ok(ContractError::NotApproved)));
+    assert_eq!(client.try_set_approval_for_all(&owner, &operator, &true, &200), Err(Ok(ContractError::InvalidLiveUntilLedger)));
+
+    client.set_approval_for_all(&owner, &operator, &true, &300);
+    client.transfer_from(&operator, &owner, &spender, &token_id);
+    assert_eq!(client.owner_of(&token_id), spender);
+    client.set_approval_for_all(&owner, &operator, &false, &0);
+    assert!(!client.is_approved_for_all(&owner, &operator));
+}
+
+#[test]
+fn test_transfer_from_expired_approval() {
+    let env: Env = Env::default();
+    env.mock_all_auths();
+    let admin: Address = Address::generate(&env);
+    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
+    let client: ImpactProductNFTClient<'_> = ImpactProductNFTClient::new(&env, &contract_id);
+    let owner: Address = Address::generate(&env);
+    let operator: Address = Address::generate(&env);
+    let to: Address = Address::generate(&env);
+    let impact_data: ImpactData = ImpactData { category: String::from_str(&env, "category"), impact_value: 99, location: String::from_str(&env, "location"), start_date: 9999, end_date: 10000, beneficiaries: String::from_str(&env, "someone"), verified: false, metadata_uri: String::from_str(&env, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC") };
+    let token_id: u32 = client.create_impact_product(&admin, &owner, &impact_data, &100);
+    client.approve(&owner, &operator, &token_id, &100);
+    env.ledger().with_mut(|li| li.sequence_number = 101);
+    assert_eq!(client.get_approved(&token_id), None);
+    assert_eq!(client.try_transfer_from(&operator, &owner, &to, &token_id), Err(Ok(ContractError::NotApproved)));
+}
+
+#[test]
+fn test_update_impact_data_moves_category() {
+    let env: Env = Env::default();
+    env.mock_all_auths();
+    let admin: Address = Address::generate(&env);
+    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
+    let client: ImpactProductNFTClient<'_> = ImpactProductNFTClient::new(&env, &contract_id);
+    let owner: Address = Address::generate(&env);
+    let mut impact_data: ImpactData = ImpactData { category: String::from_str(&env, "category"), impact_value: 99, location: String::from_str(&env, "location"), start_date: 9999, end_date: 10000, beneficiaries: String::from_str(&env, "someone"), verified: false, metadata_uri: String::from_str(&env, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC") };
+    for _ in 0..3 {
+        client.create_impact_product(&admin, &owner, &impact_data, &100);
+    }
+    assert_eq!(client.get_tokens_by_creator(&owner), vec![&env, 1, 2, 3]);
+
+    impact_data.category = String::from_str(&env, "other");
+    assert!(!client.update_impact_data(&1, &impact_data));
+    client.approve_impact_update(&admin, &1);
+    assert_eq!(client.get_tokens_by_category(&String::from_str(&env, "category")), vec![&env, 3, 2]);
+    assert_eq!(client.get_tokens_by_category(&String::from_str(&env, "other")), vec![&env, 1]);
+    assert_eq!(client.get_impact_data(&1).category, String::from_str(&env, "other"));
+}
+
+#[test]
+fn test_mint_cost_is_flat() {
+    let env: Env = Env::default();
+    env.mock_all_auths();
+    let admin: Address = Address::generate(&env);
+    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
+    let client: ImpactProductNFTClient<'_> = ImpactProductNFTClient::new(&env, &contract_id);
+    let owner: Address = Address::generate(&env);
+    let impact_data: ImpactData = ImpactData { category: String::from_str(&env, "category"), impact_value: 99, location: String::from_str(&env, "location"), start_date: 9999, end_date: 10000, beneficiaries: String::from_str(&env, "someone"), verified: false, metadata_uri: String::from_str(&env, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC") };
+
+    client.create_impact_product(&admin, &owner, &impact_data, &100);
+    let early_entries: u32 = env.cost_estimate().resources().write_entries;
+    let early_bytes: u32 = env.cost_estimate().resources().write_bytes;
+
+    for _ in 0..100 {
+        client.create_impact_product(&admin, &owner, &impact_data, &100);
+    }
+    client.create_impact_product(&admin, &owner, &impact_data, &100);
+    let late_entries: u32 = env.cost_estimate().resources().write_entries;
+    let late_bytes: u32 = env.cost_estimate().resources().write_bytes;
+
+    assert_eq!(early_entries, late_entries);
+    assert_eq!(early_bytes, late_bytes);
+}
+
+#[test]
+fn test_mint_extends_token_ttl() {
+    let env: Env = Env::default();
+    env.mock_all_auths();
+    let admin: Address = Address::generate(&env);
+    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
+    let client: ImpactProductNFTClient<'_> = ImpactProductNFTClient::new(&env, &contract_id);
+    let owner: Address = Address::generate(&env);
+    let impact_data: ImpactData = ImpactData { category: String::from_str(&env, "category"), impact_value: 99, location: String::from_str(&env, "location"), start_date: 9999, end_date: 10000, beneficiaries: String::from_str(&env, "someone"), verified: false, metadata_uri: String::from_str(&env, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC") };
+    let token_id: u32 = client.create_impact_product(&admin, &owner, &impact_data, &100);
+
+    let extend_to: u32 = client.get_ttl_config().extend_to;
+    env.as_contract(&contract_id, || {
+        assert_eq!(env.storage().persistent().get_ttl(&DataKey::Owner(token_id)), extend_to);
+        assert_eq!(env.storage().persistent().get_ttl(&DataKey::ImpactData(token_id)), extend_to);
+        assert_eq!(env.storage().persistent().get_ttl(&DataKey::Balance(owner.clone())), extend_to);
+    });
+}
+
+#[test]
+fn test_extend_token_ttl_keeps_token_live() {
+    let env: Env = Env::default();
+    env.mock_all_auths();
+    let admin: Address = Address::generate(&env);
+    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
+    let client: ImpactProductNFTClient<'_> = ImpactProductNFTClient::new(&env, &contract_id);
+    let owner: Address = Address::generate(&env);
+    let impact_data: ImpactData = ImpactData { category: String::from_str(&env, "category"), impact_value: 99, location: String::from_str(&env, "location"), start_date: 9999, end_date: 10000, beneficiaries: String::from_str(&env, "someone"), verified: false, metadata_uri: String::from_str(&env, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC") };
+    client.set_ttl_config(&admin, &5000, &10000);
+    let token_id: u32 = client.create_impact_product(&admin, &owner, &impact_data, &100);
+
+    env.ledger().with_mut(|li| li.sequence_number += 9500);
+    env.as_contract(&contract_id, || {
+        assert_eq!(env.storage().persistent().get_ttl(&DataKey::ImpactData(token_id)), 500);
+    });
+
+    client.extend_token_ttl(&vec![&env, token_id]);
+    env.as_contract(&contract_id, || {
+        assert_eq!(env.storage().persistent().get_ttl(&DataKey::Owner(token_id)), 10000);
+        assert_eq!(env.storage().persistent().get_ttl(&DataKey::ImpactData(token_id)), 10000);
+        assert_eq!(env.storage().persistent().get_ttl(&DataKey::TokenPrice(token_id)), 10000);
+    });
+
+    env.ledger().with_mut(|li| li.sequence_number += 9500);
+    assert_eq!(client.owner_of(&token_id), owner);
+    assert_eq!(client.get_impact_data(&token_id), impact_data);
+}
+
+#[test]
+fn test_extend_token_ttl_keeps_attributes_and_indexes_live() {
+    let env: Env = Env::default();
+    env.mock_all_auths();
+    let admin: Address = Address::generate(&env);
+    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
+    let client: ImpactProductNFTClient<'_> = ImpactProductNFTClient::new(&env, &contract_id);
+    let owner: Address = Address::generate(&env);
+    let buyer: Address = Address::generate(&env);
+    let impact_data: ImpactData = ImpactData { category: String::from_str(&env, "category"), impact_value: 99, location: String::from_str(&env, "location"), start_date: 9999, end_date: 10000, beneficiaries: String::from_str(&env, "someone"), verified: false, metadata_uri: String::from_str(&env, "") };
+    let mut attributes: Map<String, AttributeValue> = Map::new(&env);
+    attributes.set(String::from_str(&env, "trees"), AttributeValue::Integer(12));
+    client.set_ttl_config(&admin, &5000, &10000);
+    let token_id: u32 = client.create_with_attributes(&admin, &owner, &impact_data, &100, &attributes);
+
+    env.ledger().with_mut(|li| li.sequence_number += 9500);
+    client.extend_token_ttl(&vec![&env, token_id]);
+    env.as_contract(&contract_id, || {
+        let keys: [DataKey; 9] = [DataKey::Attributes(token_id), DataKey::OwnerToken(owner.clone(), 0), DataKey::OwnerTokenCount(owner.clone()), DataKey::CreatorToken(owner.clone(), 0), DataKey::CreatorTokenCount(owner.clone()), DataKey::CategoryToken(impact_data.category.clone(), 0), DataKey::CategoryTokenCount(impact_data.category.clone()), DataKey::VintageToken(1970, 0), DataKey::VintageTokenCount(1970)];
+        for key in keys.iter() {
+            assert_eq!(env.storage().persistent().get_ttl(key), 10000);
+        }
+    });
+    env.ledger().with_mut(|li| li.sequence_number += 9500);
+
+    assert_eq!(client.get_attributes(&token_id), attributes);
+    assert_eq!(client.tokens_of_owner(&owner, &0, &10), vec![&env, token_id]);
+    assert_eq!(client.tokens_of_creator(&owner, &0, &10), vec![&env, token_id]);
+    assert_eq!(client.tokens_of_category(&impact_data.category, &0, &10), vec![&env, token_id]);
+    assert_eq!(client.get_tokens_by_vintage(&1970), vec![&env, token_id]);
+    client.transfer(&owner, &buyer, &token_id);
+    assert_eq!(client.tokens_of_owner(&buyer, &0, &10), vec![&env, token_id]);
+    client.retire(&buyer, &token_id, &String::from_str(&env, "Acme Corp"), &String::from_str(&env, "2025 offset"));
+    assert_eq!(client.tokens_of_category(&impact_data.category, &0, &10), vec![&env]);
+}
+
+#[test]
+fn test_extend_token_ttl_missing_token() {
+    let env: Env = Env::default();
+    let admin: Address = Address::generate(&env);
+    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
+    let client: ImpactProductNFTClient<'_> = ImpactProductNFTClient::new(&env, &contract_id);
+    assert_eq!(client.try_extend_token_ttl(&vec![&env, 1]), Err(Ok(ContractError::TokenNotFound)));
+}
+
+#[test]
+fn test_set_ttl_config() {
+    let env: Env = Env::default();
+    env.mock_all_auths();
+    let admin: Address = Address::generate(&env);
+    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
+    let client: ImpactProductNFTClient<'_> = ImpactProductNFTClient::new(&env, &contract_id);
+    client.set_ttl_config(&admin, &1000, &10000);
+    assert_eq!(client.get_ttl_config(), TtlConfig { threshold: 1000, extend_to: 10000 });
+}
+
+#[test]
+fn test_set_ttl_config_invalid() {
+    let env: Env = Env::default();
+    env.mock_all_auths();
+    let admin: Address = Address::generate(&env);
+    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
+    let client: ImpactProductNFTClient<'_> = ImpactProductNFTClient::new(&env, &contract_id);
+    assert_eq!(client.try_set_ttl_config(&admin, &10000, &1000), Err(Ok(ContractError::InvalidTtlConfig)));
+}
+
+#[test]
+fn test_set_ttl_config_not_admin() {
+    let env: Env = Env::default();
+    env.mock_all_auths();
+    let admin: Address = Address::generate(&env);
+    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
+    let client: ImpactProductNFTClient<'_> = ImpactProductNFTClient::new(&env, &contract_id);
+    let user: Address = Address::generate(&env);
+    assert_eq!(client.try_set_ttl_config(&user, &1000, &10000), Err(Ok(ContractError::MissingRole)));
+}
+
+#[test]
+fn test_retire() {
+    let env: Env = Env::default();
+    env.mock_all_auths();
+    let admin: Address = Address::generate(&env);
+    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
+    let client: ImpactProductNFTClient<'_> = ImpactProductNFTClient::new(&env, &contract_id);
+    let owner: Address = Address::generate(&env);
+    let impact_data: ImpactData = ImpactData { category: String::from_str(&env, "category"), impact_value: 99, location: String::from_str(&env, "location"), start_date: 9999, end_date: 10000, beneficiaries: String::from_str(&env, "someone"), verified: false, metadata_uri: String::from_str(&env, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC") };
+    for _ in 0..3 {
+        client.create_impact_product(&admin, &owner, &impact_data, &100);
+    }
+
+    let certificate: RetirementCertificate = client.retire(&owner, &1, &String::from_str(&env, "Acme Corp"), &String::from_str(&env, "2025 offset"));
+    assert_eq!(certificate.retiree, owner);
+    assert_eq!(certificate.impact_value, 99);
+    assert_eq!(certificate.category, String::from_str(&env, "category"));
+    assert!(client.is_retired(&1));
+    assert_eq!(client.get_retirement(&1), certificate);
+    assert_eq!(client.get_retirements_by_retiree(&owner), vec![&env, certificate]);
+
+    assert_eq!(client.token_count(), 3);
+    assert_eq!(client.circulating_supply(), 2);
+    assert_eq!(client.balance(&owner), 2);
+    assert_eq!(client.get_tokens_by_creator(&owner), vec![&env, 3, 2]);
+    assert_eq!(client.get_tokens_by_category(&String::from_str(&env, "category")), vec![&env, 3, 2]);
+
+    client.retire(&owner, &2, &String::from_str(&env, "Acme Corp"), &String::from_str(&env, "2025 offset"));
+    assert_eq!(client.get_retired_impact_by_category(&String::from_str(&env, "category")), 198);
+    assert_eq!(client.get_retirements_by_retiree(&owner).len(), 2);
+}
+
+#[test]
+fn test_transfer_retired_token() {
+    let env: Env = Env::default();
+    env.mock_all_auths();
+    let admin: Address = Address::generate(&env);
+    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
+    let client: ImpactProductNFTClient<'_> = ImpactProductNFTClient::new(&env, &contract_id);
+    let owner: Address = Address::generate(&env);
+    let to: Address = Address::generate(&env);
+    let impact_data: ImpactData = ImpactData { category: String::from_str(&env, "category"), impact_value: 99, location: String::from_str(&env, "location"), start_date: 9999, end_date: 10000, beneficiaries: String::from_str(&env, "someone"), verified: false, metadata_uri: String::from_str(&env, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC") };
+    let token_id: u32 = client.create_impact_product(&admin, &owner, &impact_data, &100);
+    client.retire(&owner, &token_id, &String::from_str(&env, "Acme Corp"), &String::from_str(&env, "2025 offset"));
+    assert_eq!(client.try_transfer(&owner, &to, &token_id), Err(Ok(ContractError::TokenRetired)));
+}
+
+#[test]
+fn test_retire_not_owner() {
+    let env: Env = Env::default();
+    env.mock_all_auths();
+    let admin: Address = Address::generate(&env);
+    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
+    let client: ImpactProductNFTClient<'_> = ImpactProductNFTClient::new(&env, &contract_id);
+    let owner: Address = Address::generate(&env);
+    let other: Address = Address::generate(&env);
+    let impact_data: ImpactData = ImpactData { category: String::from_str(&env, "category"), impact_value: 99, location: String::from_str(&env, "location"), start_date: 9999, end_date: 10000, beneficiaries: String::from_str(&env, "someone"), verified: false, metadata_uri: String::from_str(&env, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC") };
+    let token_id: u32 = client.create_impact_product(&admin, &owner, &impact_data, &100);
+    assert_eq!(client.try_retire(&other, &token_id, &String::from_str(&env, "Acme Corp"), &String::from_str(&env, "2025 offset")), Err(Ok(ContractError::NotOwner)));
+}
+
+#[test]
+fn test_retire_unindexed_token() {
+    let env: Env = Env::default();
+    env.mock_all_auths();
+    let admin: Address = Address::generate(&env);
+    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
+    let client: ImpactProductNFTClient<'_> = ImpactProductNFTClient::new(&env, &contract_id);
+    let owner: Address = Address::generate(&env);
+    let impact_data: ImpactData = ImpactData { category: String::from_str(&env, "category"), impact_value: 99, location: String::from_str(&env, "location"), start_date: 9999, end_date: 10000, beneficiaries: String::from_str(&env, "someone"), verified: false, metadata_uri: String::from_str(&env, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC") };
+    let first: u32 = client.create_impact_product(&admin, &owner, &impact_data, &100);
+    let second: u32 = client.create_impact_product(&admin, &owner, &impact_data, &100);
+
+    // Drop the first token from the category and vintage indexes and forget its creator, as for a token
+    // carried over without them.
+    env.as_contract(&contract_id, || {
+        env.storage().persistent().remove(&DataKey::CategoryIndex(first));
+        env.storage().persistent().remove(&DataKey::VintageIndex(first));
+        env.storage().persistent().remove(&DataKey::TokenCreator(first));
+    });
+    client.retire(&owner, &first, &String::from_str(&env, "Acme Corp"), &String::from_str(&env, "2025 offset"));
+    assert!(client.is_retired(&first));
+    assert_eq!(client.get_tokens_by_category(&impact_data.category), vec![&env, first, second]);
+    client.retire(&owner, &second, &String::from_str(&env, "Acme Corp"), &String::from_str(&env, "2025 offset"));
+    assert_eq!(client.get_tokens_by_category(&impact_data.category), vec![&env, first]);
+}
+
+#[test]
+fn test_project_totals() {
+    let env: Env = Env::default();
+    env.mock_all_auths();
+    let admin: Address = Address::generate(&env);
+    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
+    let client: ImpactProductNFTClient<'_> = ImpactProductNFTClient::new(&env, &contract_id);
+    let owner: Address = Address::generate(&env);
+    let validator: Address = Address::generate(&env);
+    let impact_data: ImpactData = ImpactData { category: String::from_str(&env, "category"), impact_value: 99, location: String::from_str(&env, "location"), start_date: 9999, end_date: 10000, beneficiaries: String::from_str(&env, "someone"), verified: false, metadata_uri: String::from_str(&env, "") };
+    let first: u32 = client.create_impact_product(&admin, &owner, &impact_data, &100);
+    let second: u32 = client.create_impact_product(&admin, &owner, &impact_data, &100);
+    let loose: u32 = client.create_impact_product(&admin, &owner, &impact_data, &100);
+    client.add_validator(&admin, &validator);
+    client.set_quorum(&admin, &1);
+    client.verify_token(&validator, &first, &true, &String::from_str(&env, "ipfs://evidence"));
+
+    assert_eq!(client.try_assign_project(&owner, &vec![&env, second], &7), Err(Ok(ContractError::MissingRole)));
+    assert_eq!(client.try_assign_project(&admin, &vec![&env, 99], &7), Err(Ok(ContractError::TokenNotFound)));
+    client.assign_project(&admin, &vec![&env, first, second], &7);
+    assert_eq!(client.try_assign_project(&admin, &vec![&env, first], &8), Err(Ok(ContractError::TokenAlreadyInProject)));
+    assert_eq!(client.get_token_project(&first), Some(7));
+    assert_eq!(client.get_token_project(&loose), None);
+    assert_eq!(client.get_project_totals(&7), ProjectTotals { verified: 99, retired: 0 });
+
+    // Retired tokens move from the verified total to the retired one.
+    client.verify_token(&validator, &second, &true, &String::from_str(&env, "ipfs://evidence"));
+    client.verify_token(&validator, &loose, &true, &String::from_str(&env, "ipfs://evidence"));
+    assert_eq!(client.get_project_totals(&7), ProjectTotals { verified: 198, retired: 0 });
+    client.retire(&owner, &first, &String::from_str(&env, "Acme Corp"), &String::from_str(&env, "2025 offset"));
+    assert_eq!(client.get_project_totals(&7), ProjectTotals { verified: 99, retired: 99 });
+
+    // An approved material change voids the verification.
+    let mut inflated: ImpactData = client.get_impact_data(&second);
+    inflated.impact_value = 1000;
+    client.update_impact_data(&second, &inflated);
+    client.approve_impact_update(&admin, &second);
+    assert_eq!(client.get_project_totals(&7), ProjectTotals { verified: 0, retired: 99 });
+    client.verify_token(&validator, &second, &true, &String::from_str(&env, "ipfs://evidence-2"));
+    assert_eq!(client.get_project_totals(&7), ProjectTotals { verified: 1000, retired: 99 });
+    assert_eq!(client.get_project_totals(&8), ProjectTotals { verified: 0, retired: 0 });
+}
+
+#[test]
+fn test_max_supply() {
+    let env: Env = Env::default();
+    env.mock_all_auths();
+    let admin: Address = Address::generate(&env);
+    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", Some(2u32), "Regen Bazaar Impact Product", "RIP"));
+    let client: ImpactProductNFTClient<'_> = ImpactProductNFTClient::new(&env, &contract_id);
+    let owner: Address = Address::generate(&env);
+    let impact_data: ImpactData = ImpactData { category: String::from_str(&env, "category"), impact_value: 99, location: String::from_str(&env, "location"), start_date: 9999, end_date: 10000, beneficiaries: String::from_str(&env, "someone"), verified: false, metadata_uri: String::from_str(&env, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC") };
+    assert_eq!(client.max_supply(), Some(2));
+    client.create_impact_product(&admin, &owner, &impact_data, &100);
+    assert_eq!(client.remaining_supply(), Some(1));
+    client.create_impact_product(&admin, &owner, &impact_data, &100);
+    assert_eq!(client.remaining_supply(), Some(0));
+
+    let result = client.try_create_impact_product(&admin, &owner, &impact_data, &100);
+    assert_eq!(result, Err(Ok(ContractError::SupplyCapReached)));
+
+    client.set_max_supply(&admin, &None);
+    assert_eq!(client.remaining_supply(), None);
+    client.create_impact_product(&admin, &owner, &impact_data, &100);
+    assert_eq!(client.token_count(), 3);
+}
+
+#[test]
+fn test_set_max_supply_below_issued() {
+    let env: Env = Env::default();
+    env.mock_all_auths();
+    let admin: Address = Address::generate(&env);
+    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
+    let client: ImpactProductNFTClient<'_> = ImpactProductNFTClient::new(&env, &contract_id);
+    let owner: Address = Address::generate(&env);
+    let impact_data: ImpactData = ImpactData { category: String::from_str(&env, "category"), impact_value: 99, location: String::from_str(&env, "location"), start_date: 9999, end_date: 10000, beneficiaries: String::from_str(&env, "someone"), verified: false, metadata_uri: String::from_str(&env, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC") };
+    client.create_impact_product(&admin, &owner, &impact_data, &100);
+    client.create_impact_product(&admin, &owner, &impact_data, &100);
+    let result = client.try_set_max_supply(&admin, &Some(1));
+    assert_eq!(result, Err(Ok(ContractError::CapBelowIssued)));
+}
+
+#[test]
+fn test_category_cap() {
+    let env: Env = Env::default();
+    env.mock_all_auths();
+    let admin: Address = Address::generate(&env);
+    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
+    let client: ImpactProductNFTClient<'_> = ImpactProductNFTClient::new(&env, &contract_id);
+    let owner: Address = Address::generate(&env);
+    let category: String = String::from_str(&env, "category");
+    let mut impact_data: ImpactData = ImpactData { category: category.clone(), impact_value: 99, location: String::from_str(&env, "location"), start_date: 9999, end_date: 10000, beneficiaries: String::from_str(&env, "someone"), verified: false, metadata_uri: String::from_str(&env, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC") };
+    assert_eq!(client.remaining_category_supply(&category), None);
+    client.set_category_cap(&admin, &category, &Some(1));
+    assert_eq!(client.category_cap(&category), Some(1));
+
+    client.create_impact_product(&admin, &owner, &impact_data, &100);
+    assert_eq!(client.remaining_category_supply(&category), Some(0));
+    let result = client.try_create_impact_product(&admin, &owner, &impact_data, &100);
+    assert_eq!(result, Err(Ok(ContractError::CategoryCapReached)));
+
+    impact_data.category = String::from_str(&env, "other");
+    let token_id: u32 = client.create_impact_product(&admin, &owner, &impact_data, &100);
+    impact_data.category = category.clone();
+    client.update_impact_data(&token_id, &impact_data);
+    let result = client.try_approve_impact_update(&admin, &token_id);
+    assert_eq!(result, Err(Ok(ContractError::CategoryCapReached)));
+
+    client.retire(&owner, &1, &String::from_str(&env, "Acme Corp"), &String::from_str(&env, "2025 offset"));
+    assert_eq!(client.remaining_category_supply(&category), Some(0));
+}
+
+#[test]
+fn test_owner_of_missing_token() {
+    let env: Env = Env::default();
+    let admin: Address = Address::generate(&env);
+    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
+    let client: ImpactProductNFTClient<'_> = ImpactProductNFTClient::new(&env, &contract_id);
+    assert_eq!(client.try_owner_of(&1), Err(Ok(ContractError::TokenNotFound)));
+    assert_eq!(client.try_get_impact_data(&1), Err(Ok(ContractError::TokenNotFound)));
+    assert_eq!(client.try_token_uri(&1), Err(Ok(ContractError::TokenNotFound)));
+    assert_eq!(client.try_get_token_price(&1), Err(Ok(ContractError::TokenNotFound)));
+    assert_eq!(client.try_get_retirement(&1), Err(Ok(ContractError::TokenNotRetired)));
+}
+
+#[test]
+fn test_create_impact_product_errors() {
+    let env: Env = Env::default();
+    env.mock_all_auths();
+    let admin: Address = Address::generate(&env);
+    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
+    let client: ImpactProductNFTClient<'_> = ImpactProductNFTClient::new(&env, &contract_id);
+    let owner: Address = Address::generate(&env);
+    let mut impact_data: ImpactData = ImpactData { category: String::from_str(&env, "category"), impact_value: 99, location: String::from_str(&env, "location"), start_date: 9999, end_date: 10000, beneficiaries: String::from_str(&env, "someone"), verified: false, metadata_uri: String::from_str(&env, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC") };
+
+    assert_eq!(client.try_create_impact_product(&admin, &owner, &impact_data, &0), Err(Ok(ContractError::InvalidPrice)));
+    impact_data.impact_value = 0;
+    assert_eq!(client.try_create_impact_product(&admin, &owner, &impact_data, &100), Err(Ok(ContractError::InvalidImpactValue)));
+    impact_data.category = String::from_str(&env, "");
+    assert_eq!(client.try_create_impact_product(&admin, &owner, &impact_data, &100), Err(Ok(ContractError::EmptyCategory)));
+
+    client.pause(&admin);
+    assert_eq!(client.try_create_impact_product(&admin, &owner, &impact_data, &100), Err(Ok(ContractError::ContractPaused)));
+}
+
+#[test]
+fn test_validator_errors() {
+    let env: Env = Env::default();
+    env.mock_all_auths();
+    let admin: Address = Address::generate(&env);
+    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
+    let client: ImpactProductNFTClient<'_> = ImpactProductNFTClient::new(&env, &contract_id);
+    let owner: Address = Address::generate(&env);
+    let impact_data: ImpactData = ImpactData { category: String::from_str(&env, "category"), impact_value: 99, location: String::from_str(&env, "location"), start_date: 9999, end_date: 10000, beneficiaries: String::from_str(&env, "someone"), verified: false, metadata_uri: String::from_str(&env, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC") };
+    let token_id: u32 = client.create_impact_product(&admin, &owner, &impact_data, &100);
+    let validator: Address = Address::generate(&env);
+
+    assert_eq!(client.try_remove_validator(&admin, &validator), Err(Ok(ContractError::ValidatorNotRegistered)));
+    client.add_validator(&admin, &validator);
+    assert_eq!(client.try_add_validator(&admin, &validator), Err(Ok(ContractError::ValidatorAlreadyRegistered)));
+    assert_eq!(client.try_set_quorum(&admin, &0), Err(Ok(ContractError::InvalidQuorum)));
+    assert_eq!(client.try_verify_token(&validator, &token_id, &true, &String::from_str(&env, "")), Err(Ok(ContractError::EmptyEvidence)));
+
+    client.set_quorum(&admin, &1);
+    client.verify_token(&validator, &token_id, &true, &String::from_str(&env, "ipfs://evidence"));
+    assert_eq!(client.try_verify_token(&validator, &token_id, &true, &String::from_str(&env, "ipfs://evidence")), Err(Ok(ContractError::AlreadyVerified)));
+}
+
+#[test]
+fn test_approve_errors() {
+    let env: Env = Env::default();
+    env.mock_all_auths();
+    let admin: Address = Address::generate(&env);
+    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
+    let client: ImpactProductNFTClient<'_> = ImpactProductNFTClient::new(&env, &contract_id);
+    let owner: Address = Address::generate(&env);
+    let operator: Address = Address::generate(&env);
+    let impact_data: ImpactData = ImpactData { category: String::from_str(&env, "category"), impact_value: 99, location: String::from_str(&env, "location"), start_date: 9999, end_date: 10000, beneficiaries: String::from_str(&env, "someone"), verified: false, metadata_uri: String::from_str(&env, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC") };
+    let token_id: u32 = client.create_impact_product(&admin, &owner, &impact_data, &100);
+    env.ledger().with_mut(|li| li.sequence_number = 100);
+
+    assert_eq!(client.try_approve(&operator, &operator, &token_id, &200), Err(Ok(ContractError::NotOwner)));
+    assert_eq!(client.try_approve(&owner, &operator, &token_id, &50), Err(Ok(ContractError::InvalidLiveUntilLedger)));
+    assert_eq!(client.try_approve_for_all(&owner, &operator, &50), Err(Ok(ContractError::InvalidLiveUntilLedger)));
+    assert_eq!(client.try_retire(&owner, &token_id, &String::from_str(&env, ""), &String::from_str(&env, "2025 offset")), Err(Ok(ContractError::EmptyBeneficiary)));
+}
+
+#[test]
+fn test_royalty_info() {
+    let env: Env = Env::default();
+    env.mock_all_auths();
+    let admin: Address = Address::generate(&env);
+    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
+    let client: ImpactProductNFTClient<'_> = ImpactProductNFTClient::new(&env, &contract_id);
+    let creator: Address = Address::generate(&env);
+    let treasury: Address = Address::generate(&env);
+    let payout: Address = Address::generate(&env);
+    let impact_data: ImpactData = ImpactData { category: String::from_str(&env, "category"), impact_value: 99, location: String::from_str(&env, "location"), start_date: 9999, end_date: 10000, beneficiaries: String::from_str(&env, "someone"), verified: false, metadata_uri: String::from_str(&env, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC") };
+    let first: u32 = client.create_impact_product(&admin, &creator, &impact_data, &100);
+    let second: u32 = client.create_impact_product(&admin, &creator, &impact_data, &100);
+
+    assert_eq!(client.royalty_info(&first, &1_000), (creator.clone(), 0));
+
+    client.set_default_royalty(&admin, &treasury, &250);
+    assert_eq!(client.get_default_royalty(), Some(RoyaltyInfo { receiver: treasury.clone(), basis_points: 250 }));
+    assert_eq!(client.royalty_info(&first, &1_000), (treasury.clone(), 25));
+    assert_eq!(client.royalty_info(&first, &i128::MAX), (treasury.clone(), i128::MAX / 10_000 * 250 + i128::MAX % 10_000 * 250 / 10_000));
+
+    client.set_token_royalty(&admin, &second, &creator, &1_000);
+    assert_eq!(client.royalty_info(&second, &1_000), (creator.clone(), 100));
+
+    client.set_royalty_receiver(&creator, &first, &payout);
+    assert_eq!(client.royalty_info(&first, &1_000), (payout.clone(), 25));
+    assert_eq!(client.royalty_info(&second, &1_000), (creator, 100));
+}
+
+#[test]
+fn test_royalty_errors() {
+    let env: Env = Env::default();
+    env.mock_all_auths();
+    let admin: Address = Address::generate(&env);
+    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
+    let client: ImpactProductNFTClient<'_> = ImpactProductNFTClient::new(&env, &contract_id);
+    let creator: Address = Address::generate(&env);
+    let buyer: Address = Address::generate(&env);
+    let impact_data: ImpactData = ImpactData { category: String::from_str(&env, "category"), impact_value: 99, location: String::from_str(&env, "location"), start_date: 9999, end_date: 10000, beneficiaries: String::from_str(&env, "someone"), verified: false, metadata_uri: String::from_str(&env, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC") };
+    let token_id: u32 = client.create_impact_product(&admin, &creator, &impact_data, &100);
+    client.transfer(&creator, &buyer, &token_id);
+
+    assert_eq!(client.try_set_default_royalty(&admin, &creator, &10_001), Err(Ok(ContractError::InvalidRoyalty)));
+    assert_eq!(client.try_set_default_royalty(&creator, &creator, &100), Err(Ok(ContractError::MissingRole)));
+    assert_eq!(client.try_set_token_royalty(&admin, &99, &creator, &100), Err(Ok(ContractError::TokenNotFound)));
+    assert_eq!(client.try_set_royalty_receiver(&buyer, &token_id, &buyer), Err(Ok(ContractError::NotCreator)));
+    assert_eq!(client.try_royalty_info(&token_id, &-1), Err(Ok(ContractError::InvalidPrice)));
+    assert_eq!(client.try_royalty_info(&99, &1_000), Err(Ok(ContractError::TokenNotFound)));
+}
+
+#[test]
+fn test_token_uri_falls_back_to_base_uri() {
+    let env: Env = Env::default();
+    env.mock_all_auths();
+    let admin: Address = Address::generate(&env);
+    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://example.com/tokens/", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
+    let client: ImpactProductNFTClient<'_> = ImpactProductNFTClient::new(&env, &contract_id);
+    let owner: Address = Address::generate(&env);
+    let mut impact_data: ImpactData = ImpactData { category: String::from_str(&env, "category"), impact_value: 99, location: String::from_str(&env, "location"), start_date: 9999, end_date: 10000, beneficiaries: String::from_str(&env, "someone"), verified: false, metadata_uri: String::from_str(&env, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC") };
+    client.create_impact_product(&admin, &owner, &impact_data, &100);
+    impact_data.metadata_uri = String::from_str(&env, "");
+    for _ in 0..11 {
+        client.create_impact_product(&admin, &owner, &impact_data, &100);
+    }
+
+    assert_eq!(client.token_uri(&1), String::from_str(&env, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC"));
+    assert_eq!(client.token_uri(&2), String::from_str(&env, "https://example.com/tokens/2"));
+    assert_eq!(client.token_uri(&12), String::from_str(&env, "https://example.com/tokens/12"));
+    assert_eq!(client.try_token_uri(&13), Err(Ok(ContractError::TokenNotFound)));
+
+    // Clearing the metadata URI drops the stored one, and setting it again restores it.
+    impact_data.metadata_uri = String::from_str(&env, "");
+    assert!(client.update_impact_data(&1, &impact_data));
+    assert_eq!(client.token_uri(&1), String::from_str(&env, "https://example.com/tokens/1"));
+    impact_data.metadata_uri = String::from_str(&env, "ipfs://Qm123");
+    assert!(client.update_impact_data(&2, &impact_data));
+    assert_eq!(client.token_uri(&2), String::from_str(&env, "ipfs://Qm123"));
+}
+
+#[test]
+fn test_token_metadata_json() {
+    let env: Env = Env::default();
+    env.mock_all_auths();
+    let admin: Address = Address::generate(&env);
+    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
+    let client: ImpactProductNFTClient<'_> = ImpactProductNFTClient::new(&env, &contract_id);
+    let owner: Address = Address::generate(&env);
+    let mut impact_data: ImpactData = ImpactData { category: String::from_str(&env, "Reforestation"), impact_value: 1000, location: String::from_str(&env, "Kenya \"Rift\" Valley"), start_date: 1700000000, end_date: 1700086400, beneficiaries: String::from_str(&env, "Local\ncommunities"), verified: true, metadata_uri: String::from_str(&env, "ipfs://Qm123") };
+    client.create_impact_product(&admin, &owner, &impact_data, &100);
+
+    let expected: String = String::from_str(&env, concat!(
+        "{\"name\":\"Regen Bazaar Impact Product #1\",\"token_id\":1,\"external_url\":\"ipfs://Qm123\",\"attributes\":[",
+        "{\"trait_type\":\"category\",\"value\":\"Reforestation\"},",
+        "{\"trait_type\":\"impact_value\",\"value\":1000},",
+        "{\"trait_type\":\"location\",\"value\":\"Kenya \\\"Rift\\\" Valley\"},",
+        "{\"trait_type\":\"start_date\",\"display_type\":\"date\",\"value\":1700000000},",
+        "{\"trait_type\":\"end_date\",\"display_type\":\"date\",\"value\":1700086400},",
+        "{\"trait_type\":\"beneficiaries\",\"value\":\"Local\\ncommunities\"},",
+        "{\"trait_type\":\"verified\",\"value\":true},",
+        "{\"trait_type\":\"impact_score\",\"value\":1200}]}"
+    ));
+    assert_eq!(client.token_metadata_json(&1), expected);
+    assert_eq!(client.try_token_metadata_json(&2), Err(Ok(ContractError::TokenNotFound)));
+
+    let long_location: [u8; 5000] = [b'a'; 5000];
+    impact_data.location = String::from_bytes(&env, &long_location);
+    client.create_impact_product(&admin, &owner, &impact_data, &100);
+    assert_eq!(client.try_token_metadata_json(&2), Err(Ok(ContractError::MetadataTooLarge)));
+
+    // Escaping doubles every quote, so a value that fits raw can still overflow once escaped.
+    impact_data.location = String::from_bytes(&env, &[b'"'; 2000]);
+    client.create_impact_product(&admin, &owner, &impact_data, &100);
+    assert_eq!(client.try_token_metadata_json(&3), Err(Ok(ContractError::MetadataTooLarge)));
+    impact_data.location = String::from_bytes(&env, &[b'"'; 1500]);
+    client.create_impact_product(&admin, &owner, &impact_data, &100);
+    assert_eq!(client.token_metadata_json(&4).len(), expected.len() - 21 + 3000);
+}
+
+#[test]
+fn test_token_attributes() {
+    let env: Env = Env::default();
+    env.mock_all_auths();
+    let admin: Address = Address::generate(&env);
+    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
+    let client: ImpactProductNFTClient<'_> = ImpactProductNFTClient::new(&env, &contract_id);
+    let owner: Address = Address::generate(&env);
+    let impact_data: ImpactData = ImpactData { category: String::from_str(&env, "Reforestation"), impact_value: 1000, location: String::from_str(&env, "Kenya"), start_date: 1700000000, end_date: 1700086400, beneficiaries: String::from_str(&env, "Local communities"), verified: false, metadata_uri: String::from_str(&env, "") };
+
+    let mut attributes: Map<String, AttributeValue> = Map::new(&env);
+    attributes.set(String::from_str(&env, "species"), AttributeValue::Text(String::from_str(&env, "Rhizophora \"red\"")));
+    attributes.set(String::from_str(&env, "trees"), AttributeValue::Integer(-3));
+    attributes.set(String::from_str(&env, "native"), AttributeValue::Boolean(true));
+    let token_id: u32 = client.create_with_attributes(&admin, &owner, &impact_data, &100, &attributes);
+    assert_eq!(client.get_attributes(&token_id), attributes);
+    let mut reserved: Map<String, AttributeValue> = attributes.clone();
+    reserved.set(String::from_str(&env, "location"), AttributeValue::Text(String::from_str(&env, "Elsewhere")));
+    assert_eq!(client.try_create_with_attributes(&admin, &owner, &impact_data, &100, &reserved), Err(Ok(ContractError::ReservedAttributeName)));
+
+    let json: String = client.token_metadata_json(&token_id);
+    let mut buf: [u8; 1024] = [0; 1024];
+    json.copy_into_slice(&mut buf[..json.len() as usize]);
+    let json: &str = core::str::from_utf8(&buf[..json.len() as usize]).unwrap();
+    assert!(json.ends_with("{\"trait_type\":\"native\",\"value\":true},{\"trait_type\":\"species\",\"value\":\"Rhizophora \\\"red\\\"\"},{\"trait_type\":\"trees\",\"value\":-3}]}"));
+
+    let plain: u32 = client.create_impact_product(&admin, &owner, &impact_data, &100);
+    assert!(client.get_attributes(&plain).is_empty());
+    assert_eq!(client.try_get_attributes(&99), Err(Ok(ContractError::TokenNotFound)));
+
+    let token_ids: Vec<u32> = client.batch_create_with_attributes(&admin, &owner, &vec![&env, impact_data.clone(), impact_data.clone()], &vec![&env, 100u128, 100u128], &vec![&env, attributes.clone(), Map::new(&env)]);
+    assert_eq!(client.get_attributes(&token_ids.get_unchecked(0)), attributes);
+    assert!(client.get_attributes(&token_ids.get_unchecked(1)).is_empty());
+    assert_eq!(client.try_batch_create_with_attributes(&admin, &owner, &vec![&env, impact_data.clone(), impact_data], &vec![&env, 100u128, 100u128], &vec![&env, attributes]), Err(Ok(ContractError::BatchLengthMismatch)));
+}
+
+#[test]
+fn test_update_impact_data_governance() {
+    let env: Env = Env::default();
+    env.mock_all_auths();
+    let admin: Address = Address::generate(&env);
+    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
+    let client: ImpactProductNFTClient<'_> = ImpactProductNFTClient::new(&env, &contract_id);
+    let owner: Address = Address::generate(&env);
+    let validator: Address = Address::generate(&env);
+    let original: ImpactData = ImpactData { category: String::from_str(&env, "category"), impact_value: 99, location: String::from_str(&env, "location"), start_date: 9999, end_date: 10000, beneficiaries: String::from_str(&env, "someone"), verified: false, metadata_uri: String::from_str(&env, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC") };
+    let token_id: u32 = client.create_impact_product(&admin, &owner, &original, &100);
+    client.add_validator(&admin, &validator);
+    client.set_quorum(&admin, &1);
+    client.verify_token(&validator, &token_id, &true, &String::from_str(&env, "ipfs://evidence"));
+    let verified: ImpactData = client.get_impact_data(&token_id);
+    env.ledger().with_mut(|li| li.timestamp = 500);
+
+    // Descriptive fields apply immediately and keep the verification.
+    let mut relocated: ImpactData = verified.clone();
+    relocated.location = String::from_str(&env, "new location");
+    assert!(client.update_impact_data(&token_id, &relocated));
+    assert_eq!(client.get_impact_data(&token_id), relocated);
+
+    // Material fields wait for review.
+    let mut inflated: ImpactData = relocated.clone();
+    inflated.impact_value = 1000;
+    assert!(!client.update_impact_data(&token_id, &inflated));
+    assert_eq!(client.get_impact_data(&token_id), relocated);
+    assert_eq!(client.get_pending_update(&token_id), Some(ImpactUpdateProposal { proposer: owner.clone(), impact_data: inflated.clone(), timestamp: 500 }));
+
+    client.reject_impact_update(&admin, &token_id);
+    assert_eq!(client.get_pending_update(&token_id), None);
+    assert_eq!(client.get_impact_data(&token_id), relocated);
+
+    // Once approved, the material change voids the verification.
+    client.update_impact_data(&token_id, &inflated);
+    client.approve_impact_update(&admin, &token_id);
+    let updated: ImpactData = client.get_impact_data(&token_id);
+    assert_eq!(updated.impact_value, 1000);
+    assert!(!updated.verified);
+    assert_eq!(client.get_attestations(&token_id).len(), 0);
+    assert_eq!(client.get_pending_update(&token_id), None);
+    assert_eq!(client.get_impact_history(&token_id), vec![
+        &env,
+        ImpactDataVersion { version: 0, impact_data: verified, replaced_at: 500 },
+        ImpactDataVersion { version: 1, impact_data: relocated, replaced_at: 500 }
+    ]);
+
+    // Owners cannot mark their own tokens verified, and a single verifier cannot approve it.
+    let mut self_verified: ImpactData = updated.clone();
+    self_verified.verified = true;
+    assert_eq!(client.try_update_impact_data(&token_id, &self_verified), Err(Ok(ContractError::VerificationNotEditable)));
+    assert_eq!(client.get_pending_update(&token_id), None);
+    assert_eq!(client.try_approve_impact_update(&admin, &token_id), Err(Ok(ContractError::NoPendingUpdate)));
+    env.as_contract(&contract_id, || {
+        crate::governance::set_pending(&env, token_id, &ImpactUpdateProposal { proposer: owner.clone(), impact_data: self_verified.clone(), timestamp: 500 });
+    });
+    client.approve_impact_update(&admin, &token_id);
+    assert!(!client.get_impact_data(&token_id).verified);
+    assert!(client.verify_token(&validator, &token_id, &true, &String::from_str(&env, "ipfs://evidence")));
+}
+
+#[test]
+fn test_update_impact_data_governance_errors() {
+    let env: Env = Env::default();
+    env.mock_all_auths();
+    let admin: Address = Address::generate(&env);
+    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
+    let client: ImpactProductNFTClient<'_> = ImpactProductNFTClient::new(&env, &contract_id);
+    let owner: Address = Address::generate(&env);
+    let buyer: Address = Address::generate(&env);
+    let mut impact_data: ImpactData = ImpactData { category: String::from_str(&env, "category"), impact_value: 99, location: String::from_str(&env, "location"), start_date: 9999, end_date: 10000, beneficiaries: String::from_str(&env, "someone"), verified: false, metadata_uri: String::from_str(&env, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC") };
+    let token_id: u32 = client.create_impact_product(&admin, &owner, &impact_data, &100);
+
+    assert_eq!(client.try_approve_impact_update(&admin, &token_id), Err(Ok(ContractError::NoPendingUpdate)));
+    assert_eq!(client.try_reject_impact_update(&admin, &token_id), Err(Ok(ContractError::NoPendingUpdate)));
+
+    impact_data.end_date = 20000;
+    client.update_impact_data(&token_id, &impact_data);
+    assert_eq!(client.try_approve_impact_update(&owner, &token_id), Err(Ok(ContractError::MissingRole)));
+
+    client.transfer(&owner, &buyer, &token_id);
+    assert_eq!(client.try_approve_impact_update(&admin, &token_id), Err(Ok(ContractError::NotOwner)));
+}
+
+#[test]
+fn test_vintages() {
+    let env: Env = Env::default();
+    env.mock_all_auths();
+    let admin: Address = Address::generate(&env);
+    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
+    let client: ImpactProductNFTClient<'_> = ImpactProductNFTClient::new(&env, &contract_id);
+    let owner: Address = Address::generate(&env);
+    let mut impact_data: ImpactData = ImpactData { category: String::from_str(&env, "category"), impact_value: 99, location: String::from_str(&env, "location"), start_date: 1672531200, end_date: 1704067199, beneficiaries: String::from_str(&env, "someone"), verified: false, metadata_uri: String::from_str(&env, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC") };
+    client.create_impact_product(&admin, &owner, &impact_data, &100);
+    client.create_impact_product(&admin, &owner, &impact_data, &100);
+    impact_data.start_date = 1704067200;
+    impact_data.end_date = 1719792000;
+    client.create_impact_product(&admin, &owner, &impact_data, &100);
+
+    assert_eq!(client.vintage_year(&1), 2023);
+    assert_eq!(client.vintage_year(&3), 2024);
+    assert_eq!(client.get_tokens_by_vintage(&2023), vec![&env, 1, 2]);
+    assert_eq!(client.get_tokens_by_vintage(&2024), vec![&env, 3]);
+
+    client.update_impact_data(&1, &impact_data);
+    client.approve_impact_update(&admin, &1);
+    assert_eq!(client.get_tokens_by_vintage(&2023), vec![&env, 2]);
+    assert_eq!(client.get_tokens_by_vintage(&2024), vec![&env, 3, 1]);
+
+    client.retire(&owner, &2, &String::from_str(&env, "Acme Corp"), &String::from_str(&env, "2025 offset"));
+    assert_eq!(client.try_get_tokens_by_vintage(&2023), Err(Ok(ContractError::NoTokensFound)));
+
+    impact_data.end_date = impact_data.start_date;
+    assert_eq!(client.try_create_impact_product(&admin, &owner, &impact_data, &100), Err(Ok(ContractError::InvalidPeriod)));
+    assert_eq!(client.try_update_impact_data(&3, &impact_data), Err(Ok(ContractError::InvalidPeriod)));
+}
+
+#[test]
+fn test_token_expiry() {
+    let env: Env = Env::default();
+    env.mock_all_auths();
+    let admin: Address = Address::generate(&env);
+    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
+    let client: ImpactProductNFTClient<'_> = ImpactProductNFTClient::new(&env, &contract_id);
+    let owner: Address = Address::generate(&env);
+    let impact_data: ImpactData = ImpactData { category: String::from_str(&env, "category"), impact_value: 99, location: String::from_str(&env, "location"), start_date: 9999, end_date: 10000, beneficiaries: String::from_str(&env, "someone"), verified: false, metadata_uri: String::from_str(&env, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC") };
+    let token_id: u32 = client.create_impact_product(&admin, &owner, &impact_data, &100);
+    env.ledger().with_mut(|li| li.timestamp = 1000);
+
+    assert!(client.is_active(&token_id));
+    assert!(!client.is_active(&99));
+    assert_eq!(client.try_set_token_expiry(&admin, &token_id, &Some(1000)), Err(Ok(ContractError::InvalidExpiry)));
+    assert_eq!(client.try_set_token_expiry(&owner, &token_id, &Some(2000)), Err(Ok(ContractError::MissingRole)));
+
+    client.set_token_expiry(&admin, &token_id, &Some(2000));
+    assert_eq!(client.get_token_expiry(&token_id), Some(2000));
+    assert!(client.is_active(&token_id));
+
+    env.ledger().with_mut(|li| li.timestamp = 2000);
+    assert!(client.is_expired(&token_id));
+    assert!(!client.is_active(&token_id));
+
+    client.retire(&owner, &token_id, &String::from_str(&env, "Acme Corp"), &String::from_str(&env, "2025 offset"));
+    assert!(client.is_retired(&token_id));
+    assert!(!client.is_active(&token_id));
+}
+
+#[test]
+fn test_impact_scoring() {
+    let env: Env = Env::default();
+    env.mock_all_auths();
+    let admin: Address = Address::generate(&env);
+    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
+    let client: ImpactProductNFTClient<'_> = ImpactProductNFTClient::new(&env, &contract_id);
+    let owner: Address = Address::generate(&env);
+    let year: u128 = 365 * 86400;
+    let mut impact_data: ImpactData = ImpactData { category: String::from_str(&env, "Reforestation"), impact_value: 1000, location: String::from_str(&env, "Kenya"), start_date: 0, end_date: 200 * 86400, beneficiaries: String::from_str(&env, "someone"), verified: false, metadata_uri: String::from_str(&env, "") };
+    let first: u32 = client.create_impact_product(&admin, &owner, &impact_data, &100);
+    impact_data.verified = true;
+    let second: u32 = client.create_impact_product(&admin, &owner, &impact_data, &100);
+
+    assert_eq!(client.get_scoring_version(), 0);
+    assert_eq!(client.calculate_impact_score(&first), 1320);
+    assert_eq!(client.calculate_impact_score(&second), 1584);
+
+    let mut config: ScoringConfig = client.get_scoring_config();
+    config.category_weights.set(String::from_str(&env, "Reforestation"), 20000);
+    config.region_multipliers.set(String::from_str(&env, "Kenya"), 15000);
+    config.verification_bonus_bps = 5000;
+    config.duration_tiers = vec![&env, DurationTier { min_duration: 90 * 86400, multiplier_bps: 12000 }];
+    config.decay_bps_per_year = 1000;
+    assert_eq!(client.set_scoring_config(&admin, &config), 1);
+    let third: u32 = client.create_impact_product(&admin, &owner, &impact_data, &100);
+
+    env.ledger().with_mut(|li| li.timestamp = (200 * 86400 + 2 * year) as u64);
+    assert_eq!(client.calculate_impact_score(&first), 2880);
+    assert_eq!(client.calculate_impact_score(&third), 4320);
+    assert_eq!(client.get_token_scoring_version(&second), 0);
+    assert_eq!(client.get_token_scoring_version(&third), 1);
+    assert_eq!(client.calculate_impact_score_at(&second, &client.get_token_scoring_version(&second)), 1584);
+    assert_eq!(client.get_scoring_config_at(&1), config);
+
+    config.decay_bps_per_year = 20000;
+    assert_eq!(client.try_set_scoring_config(&admin, &config), Err(Ok(ContractError::InvalidScoringConfig)));
+    config.decay_bps_per_year = 0;
+    config.duration_tiers = vec![&env, DurationTier { min_duration: 90 * 86400, multiplier_bps: 12000 }, DurationTier { min_duration: 30 * 86400, multiplier_bps: 11000 }];
+    assert_eq!(client.try_set_scoring_config(&admin, &config), Err(Ok(ContractError::InvalidScoringConfig)));
+    config.duration_tiers = Vec::new(&env);
+    config.category_weights.set(String::from_str(&env, "Reforestation"), 0);
+    assert_eq!(client.try_set_scoring_config(&admin, &config), Err(Ok(ContractError::InvalidScoringConfig)));
+    assert_eq!(client.try_set_scoring_config(&owner, &client.get_scoring_config()), Err(Ok(ContractError::MissingRole)));
+    assert_eq!(client.try_calculate_impact_score_at(&first, &2), Err(Ok(ContractError::UnknownScoringVersion)));
+    assert_eq!(client.try_get_token_scoring_version(&99), Err(Ok(ContractError::TokenNotFound)));
+}
+
+#[test]
+fn test_batch_create_impact_products() {
+    let env: Env = Env::default();
+    env.mock_all_auths();
+    let admin: Address = Address::generate(&env);
+    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", Some(4u32), "Regen Bazaar Impact Product", "RIP"));
+    let client: ImpactProductNFTClient<'_> = ImpactProductNFTClient::new(&env, &contract_id);
+    let owner: Address = Address::generate(&env);
+    let hectare: ImpactData = ImpactData { category: String::from_str(&env, "Reforestation"), impact_value: 10, location: String::from_str(&env, "Plot A"), start_date: 9999, end_date: 10000, beneficiaries: String::from_str(&env, "someone"), verified: false, metadata_uri: String::from_str(&env, "") };
+    let mut second: ImpactData = hectare.clone();
+    second.location = String::from_str(&env, "Plot B");
+
+    let token_ids: Vec<u32> = client.batch_create_impact_products(&admin, &owner, &vec![&env, hectare.clone(), second.clone()], &vec![&env, 100u128, 200u128]);
+    assert_eq!(token_ids, vec![&env, 1u32, 2u32]);
+    assert_eq!(client.balance(&owner), 2);
+    assert_eq!(client.get_impact_data(&2), second);
+    assert_eq!(client.get_token_price(&2), 200);
+    assert_eq!(client.get_tokens_by_category(&hectare.category), vec![&env, 1u32, 2u32]);
+
+    assert_eq!(client.try_batch_create_impact_products(&admin, &owner, &vec![&env, hectare.clone(), hectare.clone(), hectare.clone()], &vec![&env, 100u128, 100u128, 100u128]), Err(Ok(ContractError::SupplyCapReached)));
+    assert_eq!(client.try_batch_create_impact_products(&admin, &owner, &vec![&env, hectare.clone()], &vec![&env, 100u128, 100u128]), Err(Ok(ContractError::BatchLengthMismatch)));
+    assert_eq!(client.try_batch_create_impact_products(&admin, &owner, &Vec::new(&env), &Vec::new(&env)), Err(Ok(ContractError::InvalidBatchSize)));
+    assert_eq!(client.try_batch_create_impact_products(&admin, &owner, &vec![&env, hectare.clone(), hectare.clone()], &vec![&env, 100u128, 0u128]), Err(Ok(ContractError::InvalidPrice)));
+    assert_eq!(client.try_batch_create_impact_products(&owner, &owner, &vec![&env, hectare.clone()], &vec![&env, 100u128]), Err(Ok(ContractError::MissingRole)));
+    assert_eq!(client.token_count(), 2);
+
+    client.set_category_cap(&admin, &hectare.category, &Some(3));
+    assert_eq!(client.try_batch_create_impact_products(&admin, &owner, &vec![&env, hectare.clone(), hectare.clone()], &vec![&env, 100u128, 100u128]), Err(Ok(ContractError::CategoryCapReached)));
+    assert_eq!(client.token_count(), 2);
+    assert_eq!(client.balance(&owner), 2);
+}
+
+#[test]
+fn test_enumeration() {
+    let env: Env = Env::default();
+    env.mock_all_auths();
+    let admin: Address = Address::generate(&env);
+    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
+    let client: ImpactProductNFTClient<'_> = ImpactProductNFTClient::new(&env, &contract_id);
+    let alice: Address = Address::generate(&env);
+    let bob: Address = Address::generate(&env);
+    let trees: String = String::from_str(&env, "Tree preservation");
+    let mut impact_data: ImpactData = ImpactData { category: trees.clone(), impact_value: 10, location: String::from_str(&env, "location"), start_date: 9999, end_date: 10000, beneficiaries: String::from_str(&env, "someone"), verified: false, metadata_uri: String::from_str(&env, "") };
+    for price in [100u128, 200, 300] {
+        client.create_impact_product(&admin, &alice, &impact_data, &price);
+    }
+    impact_data.category = String::from_str(&env, "Eco tourism");
+    impact_data.verified = true;
+    client.create_impact_product(&admin, &bob, &impact_data, &400);
+
+    assert_eq!(client.tokens_of_owner(&alice, &0, &10), vec![&env, 1u32, 2u32, 3u32]);
+    assert_eq!(client.tokens_of_owner(&alice, &1, &1), vec![&env, 2u32]);
+    assert_eq!(client.tokens_of_owner(&alice, &5, &10), Vec::new(&env));
+
+    client.transfer(&alice, &bob, &1);
+    assert_eq!(client.tokens_of_owner(&alice, &0, &10), vec![&env, 3u32, 2u32]);
+    assert_eq!(client.tokens_of_owner(&bob, &0, &10), vec![&env, 4u32, 1u32]);
+    client.approve(&bob, &alice, &4, &100);
+    client.transfer_from(&alice, &bob, &alice, &4);
+    assert_eq!(client.tokens_of_owner(&alice, &0, &10), vec![&env, 3u32, 2u32, 4u32]);
+    client.retire(&alice, &2, &String::from_str(&env, "Acme Corp"), &String::from_str(&env, "2025 offset"));
+    assert_eq!(client.tokens_of_owner(&alice, &0, &10), vec![&env, 3u32, 4u32]);
+    assert_eq!(client.balance(&alice), 2);
+
+    assert_eq!(client.token_by_index(&1), 2);
+    assert_eq!(client.try_token_by_index(&4), Err(Ok(ContractError::IndexOutOfBounds)));
+    assert_eq!(client.tokens_of_creator(&alice, &1, &5), vec![&env, 3u32]);
+    assert_eq!(client.tokens_of_category(&trees, &0, &1), vec![&env, 1u32]);
+
+    let mut filter: TokenFilter = TokenFilter { owner: None, creator: None, category: None, verified: None, min_price: Some(150), max_price: Some(400) };
+    assert_eq!(client.query_tokens(&filter, &0, &10), TokenPage { token_ids: vec![&env, 3u32, 4u32], next_cursor: None });
+    assert_eq!(client.query_tokens(&filter, &0, &1), TokenPage { token_ids: vec![&env, 3u32], next_cursor: Some(3) });
+    filter.verified = Some(false);
+    filter.owner = Some(alice.clone());
+    assert_eq!(client.query_tokens(&filter, &0, &10), TokenPage { token_ids: vec![&env, 3u32], next_cursor: None });
+    filter.owner = None;
+    filter.category = Some(trees);
+    filter.min_price = None;
+    assert_eq!(client.query_tokens(&filter, &0, &10), TokenPage { token_ids: vec![&env, 1u32, 3u32], next_cursor: None });
+}
+
+#[test]
+fn test_index_owners() {
+    let env: Env = Env::default();
+    env.mock_all_auths();
+    let admin: Address = Address::generate(&env);
+    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
+    let client: ImpactProductNFTClient<'_> = ImpactProductNFTClient::new(&env, &contract_id);
+    let owner: Address = Address::generate(&env);
+    let impact_data: ImpactData = ImpactData { category: String::from_str(&env, "category"), impact_value: 10, location: String::from_str(&env, "location"), start_date: 9999, end_date: 10000, beneficiaries: String::from_str(&env, "someone"), verified: false, metadata_uri: String::from_str(&env, "") };
+    for _ in 0..3 {
+        client.create_impact_product(&admin, &owner, &impact_data, &100);
+    }
+    env.as_contract(&contract_id, || {
+        for token_id in 1..=3u32 {
+            env.storage().persistent().remove(&DataKey::OwnerIndex(token_id));
+            env.storage().persistent().remove(&DataKey::OwnerToken(owner.clone(), token_id - 1));
+        }
+        env.storage().persistent().remove(&DataKey::OwnerTokenCount(owner.clone()));
+    });
+    assert_eq!(client.tokens_of_owner(&owner, &0, &10), Vec::new(&env));
+
+    let buyer: Address = Address::generate(&env);
+    client.transfer(&owner, &buyer, &3);
+    assert_eq!(client.tokens_of_owner(&buyer, &0, &10), vec![&env, 3u32]);
+
+    assert_eq!(client.try_index_owners(&buyer, &10), Err(Ok(ContractError::MissingRole)));
+    assert!(!client.index_owners(&admin, &1));
+    assert!(client.index_owners(&admin, &10));
+    assert_eq!(client.tokens_of_owner(&owner, &0, &10), vec![&env, 1u32, 2u32]);
+    assert_eq!(client.tokens_of_owner(&buyer, &0, &10), vec![&env, 3u32]);
+}
+
+#[test]
+fn test_pause_scopes() {
+    let env: Env = Env::default();
+    env.mock_all_auths();
+    let admin: Address = Address::generate(&env);
+    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
+    let client: ImpactProductNFTClient<'_> = ImpactProductNFTClient::new(&env, &contract_id);
+    let owner: Address = Address::generate(&env);
+    let buyer: Address = Address::generate(&env);
+    let mut impact_data: ImpactData = ImpactData { category: String::from_str(&env, "category"), impact_value: 99, location: String::from_str(&env, "location"), start_date: 9999, end_date: 10000, beneficiaries: String::from_str(&env, "someone"), verified: false, metadata_uri: String::from_str(&env, "") };
+    let token_id: u32 = client.create_impact_product(&admin, &owner, &impact_data, &100);
+    env.ledger().with_mut(|li| li.sequence_number = 100);
+    client.approve(&owner, &buyer, &token_id, &200);
+    client.set_approval_for_all(&owner, &buyer, &true, &200);
+
+    client.pause_scope(&admin, &PauseScope::Transfers);
+    assert_eq!(env.events().all(), vec![&env, (contract_id.clone(), (symbol_short!("paused"), PauseScope::Transfers).into_val(&env), admin.into_val(&env))]);
+    client.pause_scope(&admin, &PauseScope::Pricing);
+    // Pausing a paused scope changes nothing and announces nothing.
+    client.pause_scope(&admin, &PauseScope::Pricing);
+    assert_eq!(env.events().all(), Vec::<(Address, Vec<Val>, Val)>::new(&env));
+    assert_eq!(client.paused_scopes(), vec![&env, PauseScope::Transfers, PauseScope::Pricing]);
+    assert!(!client.is_paused());
+    assert_eq!(client.try_transfer(&owner, &buyer, &token_id), Err(Ok(ContractError::ContractPaused)));
+    assert_eq!(client.try_transfer_from(&buyer, &owner, &buyer, &token_id), Err(Ok(ContractError::ContractPaused)));
+    assert_eq!(client.try_approve(&owner, &buyer, &token_id, &300), Err(Ok(ContractError::ContractPaused)));
+    assert_eq!(client.try_set_approval_for_all(&owner, &buyer, &true, &300), Err(Ok(ContractError::ContractPaused)));
+    assert_eq!(client.try_retire(&owner, &token_id, &String::from_str(&env, "Acme Corp"), &String::from_str(&env, "2025 offset")), Err(Ok(ContractError::ContractPaused)));
+    assert_eq!(client.try_update_token_price(&token_id, &200), Err(Ok(ContractError::ContractPaused)));
+    assert_eq!(client.try_set_default_royalty(&admin, &owner, &500), Err(Ok(ContractError::ContractPaused)));
+    // Withdrawing approvals stays open while transfers are paused.
+    client.approve(&owner, &buyer, &token_id, &0);
+    assert_eq!(client.get_approved(&token_id), None);
+    client.set_approval_for_all(&owner, &buyer, &false, &0);
+    assert!(!client.is_approved_for_all(&owner, &buyer));
+    client.revoke(&owner, &token_id);
+    client.create_impact_product(&admin, &owner, &impact_data, &100);
+    impact_data.location = String::from_str(&env, "elsewhere");
+    assert!(client.update_impact_data(&token_id, &impact_data));
+
+    client.unpause_scope(&admin, &PauseScope::Transfers);
+    assert_eq!(env.events().all(), vec![&env, (contract_id.clone(), (symbol_short!("unpaused"), PauseScope::Transfers).into_val(&env), admin.into_val(&env))]);
+    client.unpause_scope(&admin, &PauseScope::Minting);
+    assert_eq!(env.events().all(), Vec::<(Address, Vec<Val>, Val)>::new(&env));
+    assert_eq!(client.paused_scopes(), vec![&env, PauseScope::Pricing]);
+    client.transfer(&owner, &buyer, &token_id);
+
+    client.pause(&admin);
+    assert_eq!(client.paused_scopes(), vec![&env, PauseScope::Minting, PauseScope::Transfers, PauseScope::Metadata, PauseScope::Pricing]);
+    assert_eq!(client.try_create_impact_product(&admin, &owner, &impact_data, &100), Err(Ok(ContractError::ContractPaused)));
+    assert_eq!(client.try_update_impact_data(&token_id, &impact_data), Err(Ok(ContractError::ContractPaused)));
+    assert_eq!(client.try_transfer(&buyer, &owner, &token_id), Err(Ok(ContractError::ContractPaused)));
+    assert_eq!(client.try_pause_scope(&owner, &PauseScope::Minting), Err(Ok(ContractError::MissingRole)));
+
+    client.unpause(&admin);
+    assert_eq!(client.paused_scopes(), vec![&env, PauseScope::Pricing]);
+    client.transfer(&buyer, &owner, &token_id);
+}
+
+#[test]
+fn test_upgrade_keeps_state() {
+    let env: Env = Env::default();
+    env.mock_all_auths();
+    let admin: Address = Address::generate(&env);
+    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
+    let client: ImpactProductNFTClient<'_> = ImpactProductNFTClient::new(&env, &contract_id);
+    let owner: Address = Address::generate(&env);
+    let impact_data: ImpactData = ImpactData { category: String::from_str(&env, "category"), impact_value: 99, location: String::from_str(&env, "location"), start_date: 9999, end_date: 10000, beneficiaries: String::from_str(&env, "someone"), verified: false, metadata_uri: String::from_str(&env, "") };
+    let token_id: u32 = client.create_impact_product(&admin, &owner, &impact_data, &100);
+    assert_eq!(client.schema_version(), 2);
+
+    let new_wasm_hash: BytesN<32> = env.deployer().upload_contract_wasm(contract_nft::WASM);
+    assert_eq!(client.try_upgrade(&owner, &new_wasm_hash), Err(Ok(ContractError::MissingRole)));
+    client.upgrade(&admin, &new_wasm_hash);
+
+    env.as_contract(&contract_id, || {
+        assert_eq!(env.storage().persistent().get::<DataKey, ImpactData>(&DataKey::ImpactData(token_id)), Some(impact_data));
+        assert_eq!(env.storage().persistent().get::<DataKey, Address>(&DataKey::Owner(token_id)), Some(owner));
+        assert_eq!(env.storage().instance().get::<DataKey, u32>(&DataKey::SchemaVersion), Some(2));
+    });
+}
+
+#[test]
+fn test_migrate_schema() {
+    let env: Env = Env::default();
+    env.mock_all_auths();
+    let admin: Address = Address::generate(&env);
+    let contract_id: Address = env.register(ImpactProductNFT, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
+    let client: ImpactProductNFTClient<'_> = ImpactProductNFTClient::new(&env, &contract_id);
+    let owner: Address = Address::generate(&env);
+    let impact_data: ImpactData = ImpactData { category: String::from_str(&env, "category"), impact_value: 99, location: String::from_str(&env, "location"), start_date: 9999, end_date: 10000, beneficiaries: String::from_str(&env, "someone"), verified: false, metadata_uri: String::from_str(&env, "") };
+    let token_id: u32 = client.create_impact_product(&admin, &owner, &impact_data, &100);
+
+    let minter: Address = Address::generate(&env);
+
+    // Version 1 kept each role's holders in one instance list.
+    env.as_contract(&contract_id, || {
+        env.storage().instance().remove(&DataKey::SchemaVersion);
+        for role in [DEFAULT_ADMIN_ROLE, MINTER_ROLE] {
+            env.storage().persistent().remove(&AccessKey::RoleMember(role.clone(), admin.clone()));
+            env.storage().instance().remove(&AccessKey::RoleMemberCount(role.clone()));
+        }
+        env.storage().instance().set(&AccessKey::RoleMembers(DEFAULT_ADMIN_ROLE), &vec![&env, admin.clone()]);
+        env.storage().instance().set(&AccessKey::RoleMembers(MINTER_ROLE), &vec![&env, admin.clone(), minter.clone()]);
+    });
+    assert_eq!(client.schema_version(), 0);
+    assert!(!client.has_role(&MINTER_ROLE, &minter));
+    assert_eq!(client.try_migrate(&Address::generate(&env)), Err(Ok(ContractError::MissingRole)));
+
+    assert_eq!(client.migrate(&admin), 2);
+    assert_eq!(client.schema_version(), 2);
+    assert_eq!(client.get_impact_data(&token_id), impact_data);
+    assert!(client.has_role(&MINTER_ROLE, &minter));
+    assert_eq!(client.get_role_member_count(&MINTER_ROLE), 2);
+    assert_eq!(client.get_role_member_count(&DEFAULT_ADMIN_ROLE), 1);
+    client.create_impact_product(&minter, &owner, &impact_data, &100);
+    assert_eq!(client.migrate(&admin), 2);
+
+    env.as_contract(&contract_id, || {
+        assert!(!env.storage().instance().has(&AccessKey::RoleMembers(MINTER_ROLE)));
+        env.storage().instance().set(&DataKey::SchemaVersion, &3u32);
+    });
+    assert_eq!(client.try_migrate(&admin), Err(Ok(ContractError::UnsupportedSchemaVersion)));
+}
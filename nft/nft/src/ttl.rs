@@ -0,0 +1,107 @@
+use soroban_sdk::{contracttype, Address, Env, String};
+
+use crate::{governance, vintage, ContractError, DataKey, ImpactData};
+
+pub const DAY_IN_LEDGERS: u32 = 17280;
+pub const DEFAULT_THRESHOLD: u32 = 7 * DAY_IN_LEDGERS;
+pub const DEFAULT_EXTEND_TO: u32 = 30 * DAY_IN_LEDGERS;
+
+/// Entries whose remaining TTL drops below `threshold` ledgers are extended to live
+/// for `extend_to` ledgers.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TtlConfig {
+    pub threshold: u32,
+    pub extend_to: u32
+}
+
+pub fn get_config(env: &Env) -> TtlConfig {
+    env.storage().instance().get(&DataKey::TtlConfig).unwrap_or(TtlConfig { threshold: DEFAULT_THRESHOLD, extend_to: DEFAULT_EXTEND_TO })
+}
+
+/// Stores `config`. The threshold must be positive and below `extend_to`, which in turn
+/// cannot exceed the network's maximum TTL.
+pub fn set_config(env: &Env, config: &TtlConfig) -> Result<(), ContractError> {
+    if config.threshold == 0 || config.threshold >= config.extend_to || config.extend_to > env.storage().max_ttl() {
+        return Err(ContractError::InvalidTtlConfig);
+    }
+    env.storage().instance().set(&DataKey::TtlConfig, config);
+    Ok(())
+}
+
+/// Extends the contract instance, which holds the roles, pause flag and settings.
+pub fn extend_instance(env: &Env) {
+    let config: TtlConfig = get_config(env);
+    env.storage().instance().extend_ttl(config.threshold, config.extend_to);
+}
+
+/// Extends a persistent entry. The entry must exist.
+pub fn extend_persistent(env: &Env, key: &DataKey) {
+    let config: TtlConfig = get_config(env);
+    env.storage().persistent().extend_ttl(key, config.threshold, config.extend_to);
+}
+
+/// Extends every persistent entry stored for `token_id`, including its slots in the owner,
+/// creator, category and vintage indexes, the counts of those indexes and its project's totals.
+pub fn extend_token(env: &Env, token_id: u32) {
+    let keys: [DataKey; 19] = [
+        DataKey::Owner(token_id),
+        DataKey::Approval(token_id),
+        DataKey::ImpactData(token_id),
+        DataKey::Attributes(token_id),
+        DataKey::TokenURI(token_id),
+        DataKey::TokenPrice(token_id),
+        DataKey::CategoryIndex(token_id),
+        DataKey::TokenCreator(token_id),
+        DataKey::CreatorIndex(token_id),
+        DataKey::Attestations(token_id),
+        DataKey::Retirement(token_id),
+        DataKey::TokenRoyalty(token_id),
+        DataKey::PendingUpdate(token_id),
+        DataKey::ImpactVersionCount(token_id),
+        DataKey::VintageIndex(token_id),
+        DataKey::Expiry(token_id),
+        DataKey::TokenScoringVersion(token_id),
+        DataKey::OwnerIndex(token_id),
+        DataKey::TokenProject(token_id)
+    ];
+    for key in keys.iter() {
+        if env.storage().persistent().has(key) {
+            extend_persistent(env, key);
+        }
+    }
+    for version in 0..governance::get_version_count(env, token_id) {
+        extend_persistent(env, &DataKey::ImpactVersion(token_id, version));
+    }
+
+    let owner: Option<Address> = env.storage().persistent().get(&DataKey::Owner(token_id));
+    if let Some(owner) = owner {
+        extend_persistent(env, &DataKey::Balance(owner.clone()));
+        extend_slot(env, &DataKey::OwnerIndex(token_id), |index| DataKey::OwnerToken(owner.clone(), index), DataKey::OwnerTokenCount(owner.clone()));
+    }
+    let creator: Option<Address> = env.storage().persistent().get(&DataKey::TokenCreator(token_id));
+    if let Some(creator) = creator {
+        extend_slot(env, &DataKey::CreatorIndex(token_id), |index| DataKey::CreatorToken(creator.clone(), index), DataKey::CreatorTokenCount(creator.clone()));
+    }
+    let project_id: Option<u32> = env.storage().persistent().get(&DataKey::TokenProject(token_id));
+    if let Some(project_id) = project_id && env.storage().persistent().has(&DataKey::ProjectTotals(project_id)) {
+        extend_persistent(env, &DataKey::ProjectTotals(project_id));
+    }
+    let impact_data: Option<ImpactData> = env.storage().persistent().get(&DataKey::ImpactData(token_id));
+    if let Some(impact_data) = impact_data {
+        let category: String = impact_data.category.clone();
+        extend_slot(env, &DataKey::CategoryIndex(token_id), |index| DataKey::CategoryToken(category.clone(), index), DataKey::CategoryTokenCount(category.clone()));
+        let year: u32 = vintage::vintage_year(&impact_data);
+        extend_slot(env, &DataKey::VintageIndex(token_id), |index| DataKey::VintageToken(year, index), DataKey::VintageTokenCount(year));
+    }
+}
+
+/// Extends the index slot `index_key` points at, and the index's count. Tokens missing
+/// from the index are skipped.
+fn extend_slot(env: &Env, index_key: &DataKey, slot_key: impl Fn(u32) -> DataKey, count_key: DataKey) {
+    let Some(index) = env.storage().persistent().get::<DataKey, u32>(index_key) else {
+        return;
+    };
+    extend_persistent(env, &slot_key(index));
+    extend_persistent(env, &count_key);
+}
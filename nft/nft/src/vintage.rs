@@ -0,0 +1,53 @@
+use soroban_sdk::Env;
+
+use crate::{ttl, ContractError, DataKey, ImpactData};
+
+const SECONDS_PER_DAY: u128 = 86400;
+
+/// The impact period must end after it starts.
+pub fn validate_period(impact_data: &ImpactData) -> Result<(), ContractError> {
+    if impact_data.end_date <= impact_data.start_date {
+        return Err(ContractError::InvalidPeriod);
+    }
+    Ok(())
+}
+
+/// Calendar year (UTC) in which the impact period ends.
+pub fn vintage_year(impact_data: &ImpactData) -> u32 {
+    year_of(impact_data.end_date)
+}
+
+/// Converts a unix timestamp in seconds to its UTC calendar year, using the days-to-civil
+/// algorithm from Howard Hinnant's date library.
+fn year_of(timestamp: u128) -> u32 {
+    let z: u128 = timestamp / SECONDS_PER_DAY + 719468;
+    let era: u128 = z / 146097;
+    let doe: u128 = z - era * 146097;
+    let yoe: u128 = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy: u128 = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp: u128 = (5 * doy + 2) / 153;
+    let year: u128 = yoe + era * 400 + if mp >= 10 { 1 } else { 0 };
+    u32::try_from(year).unwrap_or(u32::MAX)
+}
+
+pub fn get_expiry(env: &Env, token_id: u32) -> Option<u64> {
+    env.storage().persistent().get(&DataKey::Expiry(token_id))
+}
+
+pub fn set_expiry(env: &Env, token_id: u32, expires_at: Option<u64>) {
+    let key: DataKey = DataKey::Expiry(token_id);
+    match expires_at {
+        Some(timestamp) => {
+            env.storage().persistent().set(&key, &timestamp);
+            ttl::extend_persistent(env, &key);
+        }
+        None => env.storage().persistent().remove(&key)
+    }
+}
+
+pub fn is_expired(env: &Env, token_id: u32) -> bool {
+    match get_expiry(env, token_id) {
+        Some(expires_at) => env.ledger().timestamp() >= expires_at,
+        None => false
+    }
+}
@@ -0,0 +1,25 @@
+use soroban_sdk::contracterror;
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum ContractError {
+    // Vault state
+    AlreadyFractionalized = 1,
+    NotFractionalized = 2,
+    VaultClosed = 3,
+    PartiallyRetired = 4,
+
+    // Shares
+    InvalidAmount = 5,
+    InsufficientBalance = 6,
+    InsufficientAllowance = 7,
+    InvalidExpiration = 8,
+    NotAllShares = 9,
+    SharesVaulted = 13,
+
+    // Impact
+    InvalidImpactValue = 10,
+    EmptyBeneficiary = 11,
+    TokenInactive = 12,
+}
@@ -0,0 +1,327 @@
+#![no_std]
+mod error;
+mod storage;
+mod test;
+
+use soroban_sdk::{contract, contractimpl, contracttype, symbol_short, Address, Env, String};
+
+pub use error::ContractError;
+
+mod contract_nft {
+    soroban_sdk::contractimport!(
+        file = "../../target/wasm32v1-none/release/nft.wasm"
+    );
+}
+
+/// Shares carry seven decimals, so one unit of the token's impact value is split into
+/// 10^7 shares.
+pub const SHARE_DECIMALS: u32 = 7;
+pub const SHARES_PER_IMPACT_UNIT: i128 = 10_000_000;
+
+/// Beneficiary and reason recorded on the underlying token when its shares were retired
+/// for different beneficiaries or reasons; the vault's retirement records hold each one.
+const SHARE_HOLDERS_BENEFICIARY: &str = "Impact vault share holders";
+const SHARE_HOLDERS_REASON: &str = "Retired in fractions, see the vault's retirement records";
+
+/// Holds a single ImpactProductNFT token and issues fungible shares against its impact
+/// value. The vault itself is the share token and follows the Soroban token interface.
+#[contract]
+pub struct ImpactVault;
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum VaultState {
+    /// Waiting for a token to be deposited.
+    Empty,
+    /// Holding the token while its shares circulate.
+    Locked,
+    /// A holder of every share unlocked the token.
+    BoughtOut,
+    /// Every share was retired and the token was retired with them.
+    Retired
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AllowanceData {
+    pub amount: i128,
+    pub expiration_ledger: u32
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FractionalRetirement {
+    pub retiree: Address,
+    pub beneficiary_name: String,
+    pub reason: String,
+    pub shares: i128,
+    pub timestamp: u64
+}
+
+/// What every fractional retirement so far has in common; a field is `None` once two
+/// retirements disagree on it.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RetirementSummary {
+    pub beneficiary_name: Option<String>,
+    pub reason: Option<String>
+}
+
+#[contracttype]
+pub enum DataKey {
+    NftContract,
+    State,
+    TokenId,
+    TotalSupply,
+    Balance(Address),
+    Allowance(Address, Address),
+    RetiredShares,
+    RetirementCount,
+    Retirement(u32),
+    RetirementSummary
+}
+
+#[contractimpl]
+impl ImpactVault {
+    const NAME: &'static str = "Regen Bazaar Impact Share";
+    const SYMBOL: &'static str = "RIPS";
+
+    pub fn __constructor(env: Env, nft_contract: Address) {
+        storage::set_nft_contract(&env, &nft_contract);
+        storage::set_state(&env, &VaultState::Empty);
+        storage::extend_instance(&env);
+    }
+
+    pub fn nft_contract(env: Env) -> Address {
+        storage::get_nft_contract(&env)
+    }
+
+    pub fn token_id(env: Env) -> Option<u32> {
+        storage::get_token_id(&env)
+    }
+
+    pub fn state(env: Env) -> VaultState {
+        storage::get_state(&env)
+    }
+
+    /// Takes custody of `token_id` from `owner` and mints them
+    /// `impact_value * SHARES_PER_IMPACT_UNIT` shares. Expired tokens cannot be
+    /// fractionalized. Returns the number of shares minted.
+    pub fn fractionalize(env: Env, owner: Address, token_id: u32) -> Result<i128, ContractError> {
+        owner.require_auth();
+        if storage::get_state(&env) != VaultState::Empty {
+            return Err(ContractError::AlreadyFractionalized);
+        }
+        let nft: contract_nft::Client<'_> = contract_nft::Client::new(&env, &storage::get_nft_contract(&env));
+        if !nft.is_active(&token_id) {
+            return Err(ContractError::TokenInactive);
+        }
+        let impact_data: contract_nft::ImpactData = nft.get_impact_data(&token_id);
+        let shares: i128 = i128::try_from(impact_data.impact_value)
+            .ok()
+            .and_then(|value| value.checked_mul(SHARES_PER_IMPACT_UNIT))
+            .filter(|shares| *shares > 0)
+            .ok_or(ContractError::InvalidImpactValue)?;
+
+        nft.transfer(&owner, &env.current_contract_address(), &token_id);
+
+        storage::extend_instance(&env);
+        storage::set_token_id(&env, token_id);
+        storage::set_state(&env, &VaultState::Locked);
+        storage::set_total_supply(&env, shares);
+        storage::set_balance(&env, &owner, shares);
+        env.events().publish((symbol_short!("mint"), owner), shares);
+        Ok(shares)
+    }
+
+    /// Permanently retires `amount` of `holder`'s shares on behalf of `beneficiary_name`.
+    /// When the last outstanding share is retired the vault retires the underlying token,
+    /// recording the beneficiary and reason its fractional retirements share.
+    pub fn retire_shares(env: Env, holder: Address, amount: i128, beneficiary_name: String, reason: String) -> Result<FractionalRetirement, ContractError> {
+        holder.require_auth();
+        Self::_require_locked(&env)?;
+        if amount <= 0 {
+            return Err(ContractError::InvalidAmount);
+        }
+        if beneficiary_name.is_empty() {
+            return Err(ContractError::EmptyBeneficiary);
+        }
+
+        storage::extend_instance(&env);
+        Self::_spend_balance(&env, &holder, amount)?;
+        let supply: i128 = storage::get_total_supply(&env) - amount;
+        storage::set_total_supply(&env, supply);
+
+        let retirement: FractionalRetirement = FractionalRetirement {
+            retiree: holder.clone(),
+            beneficiary_name,
+            reason,
+            shares: amount,
+            timestamp: env.ledger().timestamp()
+        };
+        storage::add_retirement(&env, &retirement);
+        env.events().publish((symbol_short!("burn"), holder.clone()), amount);
+        env.events().publish((symbol_short!("retire"), holder), amount);
+
+        if supply == 0 {
+            let nft: contract_nft::Client<'_> = contract_nft::Client::new(&env, &storage::get_nft_contract(&env));
+            let token_id: u32 = storage::get_token_id(&env).expect("locked vault holds a token");
+            let summary: RetirementSummary = storage::get_retirement_summary(&env).expect("retired shares are recorded");
+            let beneficiary_name: String = summary.beneficiary_name.unwrap_or(String::from_str(&env, SHARE_HOLDERS_BENEFICIARY));
+            let reason: String = summary.reason.unwrap_or(String::from_str(&env, SHARE_HOLDERS_REASON));
+            nft.retire(&env.current_contract_address(), &token_id, &beneficiary_name, &reason);
+            storage::set_state(&env, &VaultState::Retired);
+        }
+        Ok(retirement)
+    }
+
+    /// Burns every outstanding share, all of which `holder` must own, and releases the
+    /// underlying token to them. Not available once any shares have been retired, since
+    /// the token would then carry impact that was already claimed.
+    pub fn buyout(env: Env, holder: Address) -> Result<u32, ContractError> {
+        holder.require_auth();
+        Self::_require_locked(&env)?;
+        if storage::get_retired_shares(&env) > 0 {
+            return Err(ContractError::PartiallyRetired);
+        }
+        let supply: i128 = storage::get_total_supply(&env);
+        if storage::get_balance(&env, &holder) != supply {
+            return Err(ContractError::NotAllShares);
+        }
+
+        storage::extend_instance(&env);
+        storage::set_balance(&env, &holder, 0);
+        storage::set_total_supply(&env, 0);
+        storage::set_state(&env, &VaultState::BoughtOut);
+
+        let nft: contract_nft::Client<'_> = contract_nft::Client::new(&env, &storage::get_nft_contract(&env));
+        let token_id: u32 = storage::get_token_id(&env).expect("locked vault holds a token");
+        nft.transfer(&env.current_contract_address(), &holder, &token_id);
+        env.events().publish((symbol_short!("burn"), holder.clone()), supply);
+        env.events().publish((symbol_short!("buyout"), holder, token_id), supply);
+        Ok(token_id)
+    }
+
+    pub fn total_supply(env: Env) -> i128 {
+        storage::get_total_supply(&env)
+    }
+
+    pub fn retired_shares(env: Env) -> i128 {
+        storage::get_retired_shares(&env)
+    }
+
+    pub fn get_retirement_count(env: Env) -> u32 {
+        storage::get_retirement_count(&env)
+    }
+
+    pub fn get_retirement(env: Env, index: u32) -> Option<FractionalRetirement> {
+        storage::get_retirement(&env, index)
+    }
+
+    // --- Token interface ---
+
+    pub fn allowance(env: Env, from: Address, spender: Address) -> i128 {
+        match storage::get_allowance(&env, &from, &spender) {
+            Some(allowance) if allowance.expiration_ledger >= env.ledger().sequence() => allowance.amount,
+            _ => 0
+        }
+    }
+
+    pub fn approve(env: Env, from: Address, spender: Address, amount: i128, expiration_ledger: u32) -> Result<(), ContractError> {
+        from.require_auth();
+        if amount < 0 {
+            return Err(ContractError::InvalidAmount);
+        }
+        if amount > 0 && expiration_ledger < env.ledger().sequence() {
+            return Err(ContractError::InvalidExpiration);
+        }
+        storage::extend_instance(&env);
+        storage::set_allowance(&env, &from, &spender, &AllowanceData { amount, expiration_ledger });
+        env.events().publish((symbol_short!("approve"), from, spender), (amount, expiration_ledger));
+        Ok(())
+    }
+
+    pub fn balance(env: Env, id: Address) -> i128 {
+        storage::get_balance(&env, &id)
+    }
+
+    pub fn transfer(env: Env, from: Address, to: Address, amount: i128) -> Result<(), ContractError> {
+        from.require_auth();
+        Self::_transfer(&env, &from, &to, amount)
+    }
+
+    pub fn transfer_from(env: Env, spender: Address, from: Address, to: Address, amount: i128) -> Result<(), ContractError> {
+        spender.require_auth();
+        Self::_spend_allowance(&env, &from, &spender, amount)?;
+        Self::_transfer(&env, &from, &to, amount)
+    }
+
+    /// Part of the token interface only. Shares exist only while they are backed by the
+    /// vaulted token, and burning them would leave the token's eventual retirement claiming
+    /// impact nobody retired; retire them with `retire_shares` or release the token with
+    /// `buyout` instead.
+    pub fn burn(env: Env, from: Address, _amount: i128) -> Result<(), ContractError> {
+        from.require_auth();
+        Self::_require_burnable(&env)
+    }
+
+    pub fn burn_from(env: Env, spender: Address, _from: Address, _amount: i128) -> Result<(), ContractError> {
+        spender.require_auth();
+        Self::_require_burnable(&env)
+    }
+
+    pub fn decimals(_env: Env) -> u32 {
+        SHARE_DECIMALS
+    }
+
+    pub fn name(env: Env) -> String {
+        String::from_str(&env, Self::NAME)
+    }
+
+    pub fn symbol(env: Env) -> String {
+        String::from_str(&env, Self::SYMBOL)
+    }
+
+    fn _transfer(env: &Env, from: &Address, to: &Address, amount: i128) -> Result<(), ContractError> {
+        if amount < 0 {
+            return Err(ContractError::InvalidAmount);
+        }
+        storage::extend_instance(env);
+        Self::_spend_balance(env, from, amount)?;
+        storage::set_balance(env, to, storage::get_balance(env, to) + amount);
+        env.events().publish((symbol_short!("transfer"), from.clone(), to.clone()), amount);
+        Ok(())
+    }
+
+    fn _require_burnable(env: &Env) -> Result<(), ContractError> {
+        Self::_require_locked(env)?;
+        Err(ContractError::SharesVaulted)
+    }
+
+    fn _spend_allowance(env: &Env, from: &Address, spender: &Address, amount: i128) -> Result<(), ContractError> {
+        let allowance: i128 = Self::allowance(env.clone(), from.clone(), spender.clone());
+        if allowance < amount {
+            return Err(ContractError::InsufficientAllowance);
+        }
+        let expiration_ledger: u32 = storage::get_allowance(env, from, spender).map(|data| data.expiration_ledger).unwrap_or(0);
+        storage::set_allowance(env, from, spender, &AllowanceData { amount: allowance - amount, expiration_ledger });
+        Ok(())
+    }
+
+    fn _spend_balance(env: &Env, owner: &Address, amount: i128) -> Result<(), ContractError> {
+        let balance: i128 = storage::get_balance(env, owner);
+        if balance < amount {
+            return Err(ContractError::InsufficientBalance);
+        }
+        storage::set_balance(env, owner, balance - amount);
+        Ok(())
+    }
+
+    fn _require_locked(env: &Env) -> Result<(), ContractError> {
+        match storage::get_state(env) {
+            VaultState::Locked => Ok(()),
+            VaultState::Empty => Err(ContractError::NotFractionalized),
+            VaultState::BoughtOut | VaultState::Retired => Err(ContractError::VaultClosed)
+        }
+    }
+}
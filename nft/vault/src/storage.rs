@@ -0,0 +1,117 @@
+use soroban_sdk::{Address, Env};
+
+use crate::{AllowanceData, DataKey, FractionalRetirement, RetirementSummary, VaultState};
+
+pub const DAY_IN_LEDGERS: u32 = 17280;
+pub const TTL_THRESHOLD: u32 = 7 * DAY_IN_LEDGERS;
+pub const TTL_EXTEND_TO: u32 = 30 * DAY_IN_LEDGERS;
+
+/// Extends the contract instance, which holds the vault state and share supply.
+pub fn extend_instance(env: &Env) {
+    env.storage().instance().extend_ttl(TTL_THRESHOLD, TTL_EXTEND_TO);
+}
+
+// --- Vault ---
+
+pub fn get_nft_contract(env: &Env) -> Address {
+    env.storage().instance().get(&DataKey::NftContract).expect("vault is initialized")
+}
+
+pub fn set_nft_contract(env: &Env, nft_contract: &Address) {
+    env.storage().instance().set(&DataKey::NftContract, nft_contract);
+}
+
+pub fn get_state(env: &Env) -> VaultState {
+    env.storage().instance().get(&DataKey::State).unwrap_or(VaultState::Empty)
+}
+
+pub fn set_state(env: &Env, state: &VaultState) {
+    env.storage().instance().set(&DataKey::State, state);
+}
+
+pub fn get_token_id(env: &Env) -> Option<u32> {
+    env.storage().instance().get(&DataKey::TokenId)
+}
+
+pub fn set_token_id(env: &Env, token_id: u32) {
+    env.storage().instance().set(&DataKey::TokenId, &token_id);
+}
+
+// --- Shares ---
+
+pub fn get_total_supply(env: &Env) -> i128 {
+    env.storage().instance().get(&DataKey::TotalSupply).unwrap_or(0)
+}
+
+pub fn set_total_supply(env: &Env, supply: i128) {
+    env.storage().instance().set(&DataKey::TotalSupply, &supply);
+}
+
+pub fn get_balance(env: &Env, owner: &Address) -> i128 {
+    let key: DataKey = DataKey::Balance(owner.clone());
+    let balance: Option<i128> = env.storage().persistent().get(&key);
+    if balance.is_some() {
+        env.storage().persistent().extend_ttl(&key, TTL_THRESHOLD, TTL_EXTEND_TO);
+    }
+    balance.unwrap_or(0)
+}
+
+pub fn set_balance(env: &Env, owner: &Address, balance: i128) {
+    let key: DataKey = DataKey::Balance(owner.clone());
+    env.storage().persistent().set(&key, &balance);
+    env.storage().persistent().extend_ttl(&key, TTL_THRESHOLD, TTL_EXTEND_TO);
+}
+
+// --- Allowances ---
+
+pub fn get_allowance(env: &Env, from: &Address, spender: &Address) -> Option<AllowanceData> {
+    env.storage().temporary().get(&DataKey::Allowance(from.clone(), spender.clone()))
+}
+
+/// Allowances live in temporary storage until their expiration ledger.
+pub fn set_allowance(env: &Env, from: &Address, spender: &Address, allowance: &AllowanceData) {
+    let key: DataKey = DataKey::Allowance(from.clone(), spender.clone());
+    env.storage().temporary().set(&key, allowance);
+    if allowance.amount > 0 {
+        let live_for: u32 = allowance.expiration_ledger - env.ledger().sequence();
+        env.storage().temporary().extend_ttl(&key, live_for, live_for);
+    }
+}
+
+// --- Retirements ---
+
+pub fn get_retired_shares(env: &Env) -> i128 {
+    env.storage().instance().get(&DataKey::RetiredShares).unwrap_or(0)
+}
+
+pub fn get_retirement_count(env: &Env) -> u32 {
+    env.storage().instance().get(&DataKey::RetirementCount).unwrap_or(0)
+}
+
+pub fn get_retirement(env: &Env, index: u32) -> Option<FractionalRetirement> {
+    env.storage().persistent().get(&DataKey::Retirement(index))
+}
+
+pub fn get_retirement_summary(env: &Env) -> Option<RetirementSummary> {
+    env.storage().instance().get(&DataKey::RetirementSummary)
+}
+
+/// Stores `retirement` under the next index, adds its shares to the retired total and
+/// folds its beneficiary and reason into the summary.
+pub fn add_retirement(env: &Env, retirement: &FractionalRetirement) -> u32 {
+    let index: u32 = get_retirement_count(env);
+    let key: DataKey = DataKey::Retirement(index);
+    env.storage().persistent().set(&key, retirement);
+    env.storage().persistent().extend_ttl(&key, TTL_THRESHOLD, TTL_EXTEND_TO);
+    env.storage().instance().set(&DataKey::RetirementCount, &(index + 1));
+    env.storage().instance().set(&DataKey::RetiredShares, &(get_retired_shares(env) + retirement.shares));
+    let summary: RetirementSummary = match get_retirement_summary(env) {
+        Some(summary) => RetirementSummary {
+            beneficiary_name: summary.beneficiary_name.filter(|name| *name == retirement.beneficiary_name),
+            reason: summary.reason.filter(|reason| *reason == retirement.reason)
+        },
+        None => RetirementSummary { beneficiary_name: Some(retirement.beneficiary_name.clone()), reason: Some(retirement.reason.clone()) }
+    };
+    env.storage().instance().set(&DataKey::RetirementSummary, &summary);
+    index
+}
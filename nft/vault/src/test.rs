@@ -0,0 +1,154 @@
+#![cfg(test)]
+use crate::contract_nft::{self, ImpactData};
+use crate::{ContractError, FractionalRetirement, ImpactVault, ImpactVaultClient, VaultState, SHARES_PER_IMPACT_UNIT};
+use soroban_sdk::{Env, String, Address};
+use soroban_sdk::testutils::{Address as _, Ledger};
+
+#[test]
+fn test_fractionalize_and_buyout() {
+    let env: Env = Env::default();
+    env.mock_all_auths();
+    let admin: Address = Address::generate(&env);
+    let owner: Address = Address::generate(&env);
+    let buyer: Address = Address::generate(&env);
+    let nft_id: Address = env.register(contract_nft::WASM, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
+    let nft: contract_nft::Client<'_> = contract_nft::Client::new(&env, &nft_id);
+    let vault_id: Address = env.register(ImpactVault, (&nft_id,));
+    let vault: ImpactVaultClient<'_> = ImpactVaultClient::new(&env, &vault_id);
+    let impact_data: ImpactData = ImpactData { category: String::from_str(&env, "Tree preservation"), impact_value: 500, location: String::from_str(&env, "location"), start_date: 9999, end_date: 10000, beneficiaries: String::from_str(&env, "someone"), verified: true, metadata_uri: String::from_str(&env, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC") };
+    let token_id: u32 = nft.create_impact_product(&admin, &owner, &impact_data, &100);
+
+    let shares: i128 = vault.fractionalize(&owner, &token_id);
+    assert_eq!(shares, 500 * SHARES_PER_IMPACT_UNIT);
+    assert_eq!(nft.owner_of(&token_id), vault_id);
+    assert_eq!(vault.state(), VaultState::Locked);
+    assert_eq!(vault.token_id(), Some(token_id));
+    assert_eq!(vault.total_supply(), shares);
+    assert_eq!(vault.balance(&owner), shares);
+    assert_eq!(vault.decimals(), 7);
+
+    vault.transfer(&owner, &buyer, &(shares / 2));
+    assert_eq!(vault.try_buyout(&buyer), Err(Ok(ContractError::NotAllShares)));
+
+    vault.approve(&owner, &buyer, &(shares / 2), &100);
+    assert_eq!(vault.allowance(&owner, &buyer), shares / 2);
+    vault.transfer_from(&buyer, &owner, &buyer, &(shares / 2));
+    assert_eq!(vault.allowance(&owner, &buyer), 0);
+    assert_eq!(vault.balance(&buyer), shares);
+
+    assert_eq!(vault.buyout(&buyer), token_id);
+    assert_eq!(nft.owner_of(&token_id), buyer);
+    assert_eq!(vault.state(), VaultState::BoughtOut);
+    assert_eq!(vault.total_supply(), 0);
+    assert_eq!(vault.balance(&buyer), 0);
+    assert_eq!(vault.try_retire_shares(&buyer, &1, &String::from_str(&env, "Acme Corp"), &String::from_str(&env, "2025 offset")), Err(Ok(ContractError::VaultClosed)));
+}
+
+#[test]
+fn test_retire_shares_retires_token() {
+    let env: Env = Env::default();
+    env.mock_all_auths();
+    let admin: Address = Address::generate(&env);
+    let owner: Address = Address::generate(&env);
+    let holder: Address = Address::generate(&env);
+    let nft_id: Address = env.register(contract_nft::WASM, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
+    let nft: contract_nft::Client<'_> = contract_nft::Client::new(&env, &nft_id);
+    let vault_id: Address = env.register(ImpactVault, (&nft_id,));
+    let vault: ImpactVaultClient<'_> = ImpactVaultClient::new(&env, &vault_id);
+    let impact_data: ImpactData = ImpactData { category: String::from_str(&env, "Tree preservation"), impact_value: 500, location: String::from_str(&env, "location"), start_date: 9999, end_date: 10000, beneficiaries: String::from_str(&env, "someone"), verified: true, metadata_uri: String::from_str(&env, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC") };
+    let token_id: u32 = nft.create_impact_product(&admin, &owner, &impact_data, &100);
+    let shares: i128 = vault.fractionalize(&owner, &token_id);
+    vault.transfer(&owner, &holder, &(200 * SHARES_PER_IMPACT_UNIT));
+    env.ledger().with_mut(|li| li.timestamp = 12345);
+
+    let retirement: FractionalRetirement = vault.retire_shares(&holder, &(200 * SHARES_PER_IMPACT_UNIT), &String::from_str(&env, "Acme Corp"), &String::from_str(&env, "2025 offset"));
+    assert_eq!(retirement, FractionalRetirement { retiree: holder.clone(), beneficiary_name: String::from_str(&env, "Acme Corp"), reason: String::from_str(&env, "2025 offset"), shares: 200 * SHARES_PER_IMPACT_UNIT, timestamp: 12345 });
+    assert_eq!(vault.get_retirement(&0), Some(retirement));
+    assert_eq!(vault.total_supply(), 300 * SHARES_PER_IMPACT_UNIT);
+    assert_eq!(vault.balance(&holder), 0);
+    assert_eq!(vault.try_buyout(&owner), Err(Ok(ContractError::PartiallyRetired)));
+    assert!(!nft.is_retired(&token_id));
+
+    vault.retire_shares(&owner, &(300 * SHARES_PER_IMPACT_UNIT), &String::from_str(&env, "Owner Ltd"), &String::from_str(&env, "2025 offset"));
+    assert_eq!(vault.get_retirement_count(), 2);
+    assert_eq!(vault.retired_shares(), shares);
+    assert_eq!(vault.total_supply(), 0);
+    assert_eq!(vault.state(), VaultState::Retired);
+    assert!(nft.is_retired(&token_id));
+    assert_eq!(nft.get_retirement(&token_id).retiree, vault_id);
+    // The holders retired for different beneficiaries but for the same reason.
+    assert_eq!(nft.get_retirement(&token_id).beneficiary_name, String::from_str(&env, "Impact vault share holders"));
+    assert_eq!(nft.get_retirement(&token_id).reason, String::from_str(&env, "2025 offset"));
+    assert_eq!(nft.get_retired_impact_by_category(&impact_data.category), 500);
+}
+
+#[test]
+fn test_vault_errors() {
+    let env: Env = Env::default();
+    env.mock_all_auths();
+    let admin: Address = Address::generate(&env);
+    let owner: Address = Address::generate(&env);
+    let spender: Address = Address::generate(&env);
+    let nft_id: Address = env.register(contract_nft::WASM, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
+    let nft: contract_nft::Client<'_> = contract_nft::Client::new(&env, &nft_id);
+    let vault_id: Address = env.register(ImpactVault, (&nft_id,));
+    let vault: ImpactVaultClient<'_> = ImpactVaultClient::new(&env, &vault_id);
+    let impact_data: ImpactData = ImpactData { category: String::from_str(&env, "Tree preservation"), impact_value: 500, location: String::from_str(&env, "location"), start_date: 9999, end_date: 10000, beneficiaries: String::from_str(&env, "someone"), verified: true, metadata_uri: String::from_str(&env, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC") };
+    let first: u32 = nft.create_impact_product(&admin, &owner, &impact_data, &100);
+    let second: u32 = nft.create_impact_product(&admin, &owner, &impact_data, &100);
+    let beneficiary: String = String::from_str(&env, "Acme Corp");
+    let reason: String = String::from_str(&env, "2025 offset");
+
+    assert_eq!(vault.try_retire_shares(&owner, &1, &beneficiary, &reason), Err(Ok(ContractError::NotFractionalized)));
+    assert_eq!(vault.try_buyout(&owner), Err(Ok(ContractError::NotFractionalized)));
+
+    nft.set_token_expiry(&admin, &second, &Some(50));
+    env.ledger().with_mut(|li| li.timestamp = 50);
+    assert_eq!(vault.try_fractionalize(&owner, &second), Err(Ok(ContractError::TokenInactive)));
+
+    vault.fractionalize(&owner, &first);
+    assert_eq!(vault.try_fractionalize(&owner, &second), Err(Ok(ContractError::AlreadyFractionalized)));
+    assert_eq!(vault.try_retire_shares(&owner, &0, &beneficiary, &reason), Err(Ok(ContractError::InvalidAmount)));
+    assert_eq!(vault.try_retire_shares(&owner, &1, &String::from_str(&env, ""), &reason), Err(Ok(ContractError::EmptyBeneficiary)));
+    assert_eq!(vault.try_retire_shares(&spender, &1, &beneficiary, &reason), Err(Ok(ContractError::InsufficientBalance)));
+    assert_eq!(vault.try_transfer(&owner, &spender, &-1), Err(Ok(ContractError::InvalidAmount)));
+    assert_eq!(vault.try_transfer_from(&spender, &owner, &spender, &1), Err(Ok(ContractError::InsufficientAllowance)));
+
+    env.ledger().with_mut(|li| li.sequence_number = 100);
+    assert_eq!(vault.try_approve(&owner, &spender, &1, &99), Err(Ok(ContractError::InvalidExpiration)));
+    vault.approve(&owner, &spender, &1, &150);
+    env.ledger().with_mut(|li| li.sequence_number = 151);
+    assert_eq!(vault.allowance(&owner, &spender), 0);
+    assert_eq!(vault.try_transfer_from(&spender, &owner, &spender, &1), Err(Ok(ContractError::InsufficientAllowance)));
+}
+
+#[test]
+fn test_burn_rejected_while_vaulted() {
+    let env: Env = Env::default();
+    env.mock_all_auths();
+    let admin: Address = Address::generate(&env);
+    let owner: Address = Address::generate(&env);
+    let spender: Address = Address::generate(&env);
+    let nft_id: Address = env.register(contract_nft::WASM, (&admin, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC", None::<u32>, "Regen Bazaar Impact Product", "RIP"));
+    let nft: contract_nft::Client<'_> = contract_nft::Client::new(&env, &nft_id);
+    let vault_id: Address = env.register(ImpactVault, (&nft_id,));
+    let vault: ImpactVaultClient<'_> = ImpactVaultClient::new(&env, &vault_id);
+    let impact_data: ImpactData = ImpactData { category: String::from_str(&env, "Tree preservation"), impact_value: 500, location: String::from_str(&env, "location"), start_date: 9999, end_date: 10000, beneficiaries: String::from_str(&env, "someone"), verified: true, metadata_uri: String::from_str(&env, "https://ipfs.io/ipfs/QmegWR31kiQcD9S2katTXKxracbAgLs2QLBRGruFW3NhXC") };
+    let token_id: u32 = nft.create_impact_product(&admin, &owner, &impact_data, &100);
+
+    assert_eq!(vault.try_burn(&owner, &1), Err(Ok(ContractError::NotFractionalized)));
+    let shares: i128 = vault.fractionalize(&owner, &token_id);
+
+    vault.approve(&owner, &spender, &(50 * SHARES_PER_IMPACT_UNIT), &100);
+    assert_eq!(vault.try_burn(&owner, &(100 * SHARES_PER_IMPACT_UNIT)), Err(Ok(ContractError::SharesVaulted)));
+    assert_eq!(vault.try_burn_from(&spender, &owner, &(50 * SHARES_PER_IMPACT_UNIT)), Err(Ok(ContractError::SharesVaulted)));
+    assert_eq!(vault.allowance(&owner, &spender), 50 * SHARES_PER_IMPACT_UNIT);
+    assert_eq!(vault.balance(&owner), shares);
+    assert_eq!(vault.total_supply(), shares);
+
+    // Retiring every share retires exactly the impact the holders claimed.
+    vault.retire_shares(&owner, &shares, &String::from_str(&env, "Acme Corp"), &String::from_str(&env, "2025 offset"));
+    assert_eq!(nft.get_retirement(&token_id).beneficiary_name, String::from_str(&env, "Acme Corp"));
+    assert_eq!(nft.get_retirement(&token_id).reason, String::from_str(&env, "2025 offset"));
+    assert_eq!(vault.try_burn(&owner, &0), Err(Ok(ContractError::VaultClosed)));
+}